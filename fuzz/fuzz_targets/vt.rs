@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../src/events/vt.rs"]
+mod vt;
+
+fuzz_target!(|data: &[u8]| {
+    let (split, data) = match data.split_first() {
+        Some((split, data)) => (usize::from(*split), data),
+        None => return,
+    };
+
+    // Feeding the same bytes across two reads must never panic, and the raw
+    // bytes of every token are always a prefix of the input.
+    let mut parser = vt::Parser::default();
+    let (first, second) = data.split_at(split.min(data.len()));
+
+    let raw: Vec<u8> = parser
+        .feed(first)
+        .into_iter()
+        .chain(parser.feed(second))
+        .flat_map(|(_, raw)| raw)
+        .collect();
+
+    assert!(data.starts_with(&raw));
+});
@@ -1,4 +1,5 @@
 mod dev;
+mod graph;
 mod resources;
 mod serve;
 mod users;
@@ -55,6 +56,7 @@ pub struct Root {
 #[derive(Subcommand, Container)]
 enum RootCmd {
     Dev(dev::Dev),
+    Graph(graph::Graph),
     Resources(resources::Resources),
     Serve(serve::Serve),
     Users(users::Users),
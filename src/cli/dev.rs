@@ -2,6 +2,7 @@ mod agent;
 mod authz;
 mod dashboard;
 mod graph;
+mod replay;
 mod shell;
 mod stdin;
 
@@ -21,6 +22,7 @@ enum DevCmd {
     Authz(authz::Authz),
     Dashboard(dashboard::Dashboard),
     Graph(graph::Cmd),
+    Replay(replay::Replay),
     Shell(shell::Shell),
     Stdin(stdin::Stdin),
 }
@@ -1,6 +1,4 @@
-use std::{
-    io::Read, iter::Iterator, os::fd::AsRawFd, pin::Pin, sync::atomic::Ordering, task::Context,
-};
+use std::{io::Read, os::fd::AsRawFd, pin::Pin, sync::atomic::Ordering, task::Context};
 
 use cata::{Command, Container};
 use clap::Parser;
@@ -13,7 +11,18 @@ use tokio::{
     time::Duration,
 };
 
-use crate::{dashboard, events::Event, io::Writer};
+use crate::{
+    cluster::Connection,
+    dashboard,
+    events::Event,
+    io::Writer,
+    resources::preferences::Preferences,
+    widget::{
+        self,
+        keymap::{self, Keymap},
+        theme,
+    },
+};
 
 static STDIN_TOKEN: mio::Token = mio::Token(0);
 
@@ -22,8 +31,21 @@ pub struct Dashboard {
     #[arg(long, default_value = "10")]
     fps: u16,
 
+    /// Start at a path, such as `pods/default/nginx/logs`.
     #[arg(long)]
-    route: Vec<String>,
+    route: Option<String>,
+
+    /// Allow shells on nodes, see the `node-shell` server feature.
+    #[arg(long)]
+    node_shell: bool,
+
+    /// Keys to use for moving around and closing things.
+    #[arg(long, value_enum, default_value_t)]
+    keymap: keymap::Preset,
+
+    /// Colours to use.
+    #[arg(long, value_enum, default_value_t)]
+    theme: theme::Preset,
 }
 
 struct Stdin {
@@ -95,13 +117,20 @@ impl AsyncRead for Stdin {
 impl Command for Dashboard {
     async fn run(&self) -> Result<()> {
         dashboard::FPS.store(self.fps, Ordering::Relaxed);
+        let _ = widget::node::shell::ENABLED.set(self.node_shell);
 
         ratatui::init();
 
         let (stop_tx, mut stop_rx) = unbounded_channel::<()>();
 
+        let client = kube::Client::try_default().await?;
+
         let dashboard = dashboard::Dashboard::builder()
-            .client(kube::Client::try_default().await?)
+            .connection(Connection::new("default", client))
+            .preferences(Preferences {
+                keymap: Keymap::preset(self.keymap),
+                theme: self.theme.into(),
+            })
             .build()
             .start(Stdin::new()?, LocalWriter { stop: stop_tx })?;
 
@@ -118,6 +147,10 @@ impl Command for Dashboard {
             },
         }))?;
 
+        if let Some(route) = self.route.as_ref() {
+            dashboard.send(Event::Goto(widget::palette::route(route)))?;
+        }
+
         stop_rx.recv().await;
 
         Ok(())
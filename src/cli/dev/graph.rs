@@ -15,10 +15,9 @@ use ratatui::{
 use tokio::io::AsyncReadExt;
 
 use crate::{
-    events::{Event, Keypress},
-    exit_keys,
+    events::{self, Event, Keypress},
     resources::ResourceGraph,
-    widget::graph,
+    widget::{graph, keymap},
 };
 
 #[derive(Parser, Container)]
@@ -48,26 +47,29 @@ impl Command for Cmd {
         let mut buf = Vec::new();
         let mut i: usize = 0;
         let mut state = graph::State::default();
+        let mut parser = events::Parser::default();
 
-        loop {
+        'outer: loop {
             tokio::select! {
                 _ = stdin.read_buf(&mut buf) => {
-                    let ev = Event::from(buf.as_slice());
+                    let evs = Event::parse(&mut parser, &buf);
                     buf.clear();
 
-                    let Some(key) = ev.key() else {
-                        continue;
-                    };
-
-                    tracing::info!("key: {:?}", key);
-
-                    match key {
-                        exit_keys!() => break,
-                        Keypress::CursorLeft => i = i.saturating_sub(1),
-                        Keypress::CursorRight => i = i.saturating_add(1),
-                        Keypress::CursorDown => state.next(),
-                        Keypress::CursorUp => state.prev(),
-                        _ => {},
+                    for ev in evs {
+                        let Some(key) = ev.key() else {
+                            continue;
+                        };
+
+                        tracing::info!("key: {:?}", key);
+
+                        match key {
+                            key if keymap::exit(key) => break 'outer,
+                            Keypress::CursorLeft => i = i.saturating_sub(1),
+                            Keypress::CursorRight => i = i.saturating_add(1),
+                            Keypress::CursorDown => state.next(),
+                            Keypress::CursorUp => state.prev(),
+                            _ => {},
+                        }
                     }
                 }
                 _ = interval.tick() => {
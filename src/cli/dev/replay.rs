@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use cata::{Command, Container};
+use clap::Parser;
+use eyre::{eyre, Result, WrapErr};
+use serde_json::Value;
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    time::{sleep, Duration},
+};
+
+/// Play back a session recording, as written by `kty serve --recordings`.
+#[derive(Parser, Container)]
+pub struct Replay {
+    /// Recording to play back.
+    file: PathBuf,
+
+    /// Multiplier for how fast to play the recording back.
+    #[clap(long, default_value = "1.0")]
+    speed: f64,
+
+    /// Cap the time between events, so that idle periods are skipped over.
+    #[clap(long)]
+    idle_limit: Option<humantime::Duration>,
+}
+
+#[async_trait::async_trait]
+impl Command for Replay {
+    async fn run(&self) -> Result<()> {
+        if self.speed <= 0.0 {
+            return Err(eyre!("--speed must be greater than 0"));
+        }
+
+        let file = File::open(&self.file)
+            .await
+            .wrap_err_with(|| format!("unable to open {}", self.file.display()))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let header: Value = serde_json::from_str(
+            &lines
+                .next_line()
+                .await?
+                .ok_or_else(|| eyre!("recording is empty"))?,
+        )?;
+
+        if header["version"] != 2 {
+            return Err(eyre!(
+                "unsupported recording version: {}",
+                header["version"]
+            ));
+        }
+
+        let idle_limit = self
+            .idle_limit
+            .map(|limit| limit.as_secs_f64())
+            .or_else(|| header["idle_time_limit"].as_f64());
+
+        let mut stdout = tokio::io::stdout();
+        let mut last = 0.0;
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event: (f64, String, String) =
+                serde_json::from_str(&line).wrap_err_with(|| format!("invalid event: {line}"))?;
+            let (time, kind, data) = event;
+
+            // Only output is played back, input is already part of it as echoed
+            // by the terminal.
+            if kind != "o" {
+                continue;
+            }
+
+            let mut delay = (time - last).max(0.0);
+            if let Some(limit) = idle_limit {
+                delay = delay.min(limit);
+            }
+
+            last = time;
+
+            sleep(Duration::from_secs_f64(delay / self.speed)).await;
+
+            stdout.write_all(data.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ use tokio::{
 };
 use tokio_util::bytes::Bytes;
 
-use crate::events::{Event, Keypress};
+use crate::events::{self, Event, Keypress};
 
 /// Throwaway meant to test why tokio blocks on stdin.
 #[derive(Parser, Container)]
@@ -49,6 +49,7 @@ fn poll_stdin(tx: &UnboundedSender<Bytes>) -> Result<()> {
 
 async fn event_loop(mut rx: UnboundedReceiver<Bytes>) {
     let mut tick = tokio::time::interval(Duration::from_millis(100));
+    let mut parser = events::Parser::default();
 
     loop {
         tokio::select! {
@@ -57,11 +58,10 @@ async fn event_loop(mut rx: UnboundedReceiver<Bytes>) {
                     break;
                 };
 
-                let ev: Event = msg.into();
-                tracing::info!("ev: {:?}", ev);
+                let evs = Event::parse(&mut parser, &msg);
+                tracing::info!("evs: {:?}", evs);
 
-
-                if matches!(ev.key(), Some(Keypress::Control('c'))) {
+                if evs.iter().any(|ev| matches!(ev.key(), Some(Keypress::Control('c')))) {
                     break;
                 }
             }
@@ -0,0 +1,182 @@
+use std::io::Write;
+
+use cata::{Command, Container};
+use clap::{Parser, ValueEnum};
+use clio::Output;
+use eyre::Result;
+use k8s_openapi::api::core::v1::{ObjectReference, Pod};
+use kube::Api;
+use petgraph::{graph::Graph as PetGraph, visit::EdgeRef};
+use serde::Serialize;
+
+use super::namespace;
+use crate::resources::ResourceGraph;
+
+#[derive(Clone, Debug, ValueEnum)]
+enum Kind {
+    Pod,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum Format {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+/// Export the graph of resources related to an object. This is the same graph
+/// that is rendered in the dashboard.
+#[derive(Parser, Container)]
+pub struct Graph {
+    /// Kind of resource to build the graph for.
+    #[arg(value_enum)]
+    kind: Kind,
+
+    /// Resource to build the graph for. The format is `<namespace>/<name>`,
+    /// your default namespace will be used if only `<name>` is provided.
+    name: String,
+
+    /// Format to output the graph in.
+    #[arg(long, value_enum, default_value_t = Format::Dot)]
+    format: Format,
+
+    /// Destination for the graph, defaults to stdout.
+    #[arg(short, long, default_value = "-")]
+    output: Output,
+}
+
+#[async_trait::async_trait]
+impl Command for Graph {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(err, skip_all, fields(activity = "graph.export"))]
+    async fn run(&self) -> Result<()> {
+        let client = kube::Client::try_default().await?;
+
+        let (ns, name) = match self.name.split_once('/') {
+            Some((ns, name)) => (ns.to_string(), name.to_string()),
+            None => (namespace(None).await?, self.name.clone()),
+        };
+
+        let graph = match self.kind {
+            Kind::Pod => {
+                Api::<Pod>::namespaced(client.clone(), ns.as_str())
+                    .get(name.as_str())
+                    .await?
+                    .graph(&client)
+                    .await?
+            }
+        };
+
+        let content = match self.format {
+            Format::Dot => to_dot(&graph),
+            Format::Mermaid => to_mermaid(&graph),
+            Format::Json => serde_json::to_string_pretty(&Export::from(&graph))?,
+        };
+
+        let mut output = self.output.clone();
+        output.write_all(content.as_bytes())?;
+        output.write_all(b"\n")?;
+        output.flush()?;
+
+        Ok(())
+    }
+}
+
+fn label(reference: &ObjectReference) -> String {
+    [
+        reference.kind.as_deref(),
+        reference.namespace.as_deref(),
+        reference.name.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn to_dot(graph: &PetGraph<ObjectReference, ()>) -> String {
+    let mut out = vec!["digraph {".to_string()];
+
+    for idx in graph.node_indices() {
+        out.push(format!(
+            "    {} [label=\"{}\"];",
+            idx.index(),
+            label(&graph[idx]).replace('"', "\\\"")
+        ));
+    }
+
+    for edge in graph.edge_references() {
+        out.push(format!(
+            "    {} -> {};",
+            edge.source().index(),
+            edge.target().index()
+        ));
+    }
+
+    out.push("}".to_string());
+
+    out.join("\n")
+}
+
+fn to_mermaid(graph: &PetGraph<ObjectReference, ()>) -> String {
+    let mut out = vec!["graph LR".to_string()];
+
+    for idx in graph.node_indices() {
+        out.push(format!(
+            "    n{}[\"{}\"]",
+            idx.index(),
+            label(&graph[idx]).replace('"', "#quot;")
+        ));
+    }
+
+    for edge in graph.edge_references() {
+        out.push(format!(
+            "    n{} --> n{}",
+            edge.source().index(),
+            edge.target().index()
+        ));
+    }
+
+    out.join("\n")
+}
+
+#[derive(Serialize)]
+struct Node<'a> {
+    id: usize,
+    label: String,
+    reference: &'a ObjectReference,
+}
+
+#[derive(Serialize)]
+struct Edge {
+    from: usize,
+    to: usize,
+}
+
+#[derive(Serialize)]
+struct Export<'a> {
+    nodes: Vec<Node<'a>>,
+    edges: Vec<Edge>,
+}
+
+impl<'a> From<&'a PetGraph<ObjectReference, ()>> for Export<'a> {
+    fn from(graph: &'a PetGraph<ObjectReference, ()>) -> Self {
+        Self {
+            nodes: graph
+                .node_indices()
+                .map(|idx| Node {
+                    id: idx.index(),
+                    label: label(&graph[idx]),
+                    reference: &graph[idx],
+                })
+                .collect(),
+            edges: graph
+                .edge_references()
+                .map(|edge| Edge {
+                    from: edge.source().index(),
+                    to: edge.target().index(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,21 +1,27 @@
-use std::{net::IpAddr, path::Path, sync::Arc};
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use cata::{Command, Container};
 use clap::Parser;
-use eyre::{eyre, Result};
+use eyre::{eyre, Result, WrapErr};
+use itertools::Itertools;
 use kube::{api::Api, runtime::events::Reporter};
 use pkcs8::{Document, PrivateKeyInfo};
 use russh::{server::Config, MethodSet};
 use russh_keys::key::KeyPair;
 use ssh_key::PrivateKey;
-use strum::VariantArray;
 use warp::Filter;
 
 use crate::{
+    cluster::Cluster,
     health,
     openid::{self, Fetch},
-    resources,
+    recording, resources,
     ssh::{self, ControllerBuilder, CurrentPodBuilder, Features},
+    widget,
 };
 
 static CLIENT_ID: &str = "y2KACghcNrpTlB1Q10h3MYwYYmiMShPD";
@@ -67,7 +73,7 @@ pub struct Serve {
     #[clap(
         long,
         value_enum,
-        default_values_t = Features::VARIANTS,
+        default_values_t = Features::DEFAULT.to_vec(),
     )]
     features: Vec<Features>,
 
@@ -90,6 +96,46 @@ pub struct Serve {
     /// when running on a local cluster.
     #[clap(long, env = "POD_IP", default_value_t = local_ip_address::local_ip().unwrap_or("127.0.0.1".parse().unwrap()))]
     pod_ip: IpAddr,
+
+    /// Number of lines to fetch when initially showing logs. Older lines are
+    /// fetched on demand when scrolling back.
+    #[clap(long, default_value = "1000")]
+    log_tail_lines: i64,
+    /// Only fetch logs newer than this when initially showing logs.
+    #[clap(long)]
+    log_since: Option<humantime::Duration>,
+    /// Maximum amount of memory, in MiB, to use for log buffers across all
+    /// sessions. Once reached, the oldest lines are dropped from every buffer.
+    #[clap(long, default_value = "256")]
+    log_memory_limit: usize,
+
+    /// Name of a `ConfigMap`, in the server's namespace, with the default
+    /// `keymap`, `theme` and `bindings` for every session. Users can override
+    /// these with `kty.dev/` annotations on their `Key`.
+    #[clap(long, env = "KTY_PREFERENCES")]
+    preferences: Option<String>,
+
+    /// Name of the cluster the server runs in. Users pick clusters by name,
+    /// this is only visible when there's more than one.
+    #[clap(long, env = "KTY_CLUSTER_NAME", default_value = "local")]
+    cluster_name: String,
+    /// Contexts from the kubeconfig to serve, in addition to the cluster the
+    /// server runs in. Each one is named after its context.
+    #[clap(long, env = "KTY_CONTEXTS", value_delimiter = ',')]
+    context: Vec<String>,
+    /// Directory with credentials for remote clusters, one directory per
+    /// cluster with `server`, `token` and `ca.crt` files in it. This is what a
+    /// service account token `Secret` looks like when it is mounted, with an
+    /// extra `server` key.
+    #[clap(long, env = "KTY_CLUSTERS")]
+    clusters: Option<PathBuf>,
+
+    /// Directory to record sessions to, in asciinema's `.cast` format. Every
+    /// dashboard gets a recording of what was shown and every shell gets one of
+    /// its own, with input. Nothing is recorded when unset. Play them back with
+    /// `kty dev replay`.
+    #[clap(long, env = "KTY_RECORDINGS")]
+    recordings: Option<PathBuf>,
 }
 
 impl Serve {
@@ -103,6 +149,30 @@ impl Serve {
         Ok(())
     }
 
+    async fn remote(&self) -> Result<Vec<Cluster>> {
+        let mut clusters = Vec::new();
+
+        for context in &self.context {
+            clusters.push(Cluster::context(context).await?);
+        }
+
+        if let Some(dir) = &self.clusters {
+            clusters.extend(Cluster::directory(dir).await?);
+        }
+
+        if let Some(name) = clusters
+            .iter()
+            .map(Cluster::name)
+            .chain(std::iter::once(self.cluster_name.as_str()))
+            .duplicates()
+            .next()
+        {
+            return Err(eyre!("cluster names must be unique: {name}"));
+        }
+
+        Ok(clusters)
+    }
+
     async fn serve_ssh(&self) -> Result<()> {
         let cfg = kube::Config::infer().await?;
 
@@ -120,10 +190,42 @@ impl Serve {
                     .addr(self.pod_ip)
                     .build()?,
             )
-            .config(cfg)
+            .cluster(Cluster::new(self.cluster_name.clone(), cfg))
+            .remote(self.remote().await?)
             .reporter(Some(reporter.clone()))
+            .preferences(self.preferences.clone())
             .build()?;
 
+        if widget::log::CONFIG
+            .set(widget::log::Config {
+                tail_lines: Some(self.log_tail_lines),
+                since_seconds: self
+                    .log_since
+                    .map(|since| i64::try_from(since.as_secs()))
+                    .transpose()?,
+                memory_limit: self.log_memory_limit.saturating_mul(1024 * 1024),
+            })
+            .is_err()
+        {
+            return Err(eyre!("log config already set"));
+        }
+
+        if widget::node::shell::ENABLED
+            .set(self.features.contains(&Features::NodeShell))
+            .is_err()
+        {
+            return Err(eyre!("node shell config already set"));
+        }
+
+        if let Some(dir) = &self.recordings {
+            std::fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("unable to create {}", dir.display()))?;
+
+            if recording::DIR.set(dir.clone()).is_err() {
+                return Err(eyre!("recordings already set"));
+            }
+        }
+
         if !self.no_create {
             resources::create(&Api::all(ctrl.client()?), true).await?;
         }
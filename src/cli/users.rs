@@ -13,6 +13,7 @@ use russh_keys::{key::PublicKey, parse_public_key_base64};
 use serde::Serialize;
 
 use crate::{
+    cluster::Cluster,
     identity::{key, Identity},
     resources::KubeID,
     ssh::{Authenticate, ControllerBuilder},
@@ -50,10 +51,10 @@ impl Command for Check {
         let identity = Identity::new(self.id.clone(), self.groups.clone());
 
         let ctrl = ControllerBuilder::default()
-            .config(kube::Config::infer().await?)
+            .cluster(Cluster::new("local", kube::Config::infer().await?))
             .build()?;
 
-        if identity.authenticate(&ctrl).await?.is_some() {
+        if identity.authenticate(&ctrl, ctrl.home()).await?.is_some() {
             println!("{identity} has access");
         } else {
             return Err(eyre!("{identity} does not have access"));
@@ -0,0 +1,173 @@
+use std::path::Path;
+
+use eyre::{eyre, Result, WrapErr};
+use kube::config::{
+    self, KubeConfigOptions, Kubeconfig, NamedAuthInfo, NamedCluster, NamedContext,
+};
+
+use crate::resources::authz::Authz;
+
+// Files that make up the credentials for a remote cluster, these are the same
+// names that a service account token `Secret` uses.
+static SERVER: &str = "server";
+static TOKEN: &str = "token";
+static CA: &str = "ca.crt";
+static NAMESPACE: &str = "namespace";
+
+/// A cluster the server can create clients for. Every client impersonates a
+/// user, so the server's credentials for each cluster need to be allowed to
+/// impersonate whoever is using it.
+#[derive(Clone)]
+pub struct Cluster {
+    name: String,
+    config: kube::Config,
+}
+
+impl Cluster {
+    pub fn new(name: impl Into<String>, config: kube::Config) -> Self {
+        Self {
+            name: name.into(),
+            config,
+        }
+    }
+
+    /// Load a context from the local kubeconfig, using the context's name for
+    /// the cluster.
+    pub async fn context(name: &str) -> Result<Self> {
+        let config = kube::Config::from_kubeconfig(&KubeConfigOptions {
+            context: Some(name.to_string()),
+            ..Default::default()
+        })
+        .await
+        .wrap_err_with(|| format!("unable to load context {name}"))?;
+
+        Ok(Self::new(name, config))
+    }
+
+    /// Load service account credentials for a remote cluster from a directory,
+    /// such as a mounted `Secret`. It needs `server`, `token` and `ca.crt`
+    /// files, `namespace` is optional. The token is re-read as it changes, so
+    /// it can be rotated without restarting the server.
+    pub async fn credentials(dir: &Path) -> Result<Self> {
+        let name = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| eyre!("invalid cluster directory: {}", dir.display()))?
+            .to_string();
+
+        let server = tokio::fs::read_to_string(dir.join(SERVER))
+            .await
+            .wrap_err_with(|| format!("unable to read server for {name}"))?;
+
+        let namespace = tokio::fs::read_to_string(dir.join(NAMESPACE))
+            .await
+            .ok()
+            .map(|ns| ns.trim().to_string());
+
+        let path = |file: &str| Some(dir.join(file).to_string_lossy().to_string());
+
+        let kubeconfig = Kubeconfig {
+            clusters: vec![NamedCluster {
+                name: name.clone(),
+                cluster: Some(config::Cluster {
+                    server: Some(server.trim().to_string()),
+                    certificate_authority: path(CA),
+                    ..Default::default()
+                }),
+            }],
+            auth_infos: vec![NamedAuthInfo {
+                name: name.clone(),
+                auth_info: Some(config::AuthInfo {
+                    token_file: path(TOKEN),
+                    ..Default::default()
+                }),
+            }],
+            contexts: vec![NamedContext {
+                name: name.clone(),
+                context: Some(config::Context {
+                    cluster: name.clone(),
+                    user: name.clone(),
+                    namespace,
+                    ..Default::default()
+                }),
+            }],
+            current_context: Some(name.clone()),
+            ..Default::default()
+        };
+
+        let config =
+            kube::Config::from_custom_kubeconfig(kubeconfig, &KubeConfigOptions::default())
+                .await
+                .wrap_err_with(|| format!("unable to load credentials for {name}"))?;
+
+        Ok(Self::new(name, config))
+    }
+
+    /// Every cluster in `dir`, one per directory. See `credentials` for what
+    /// each one needs.
+    pub async fn directory(dir: &Path) -> Result<Vec<Self>> {
+        let mut entries = tokio::fs::read_dir(dir)
+            .await
+            .wrap_err_with(|| format!("unable to read {}", dir.display()))?;
+
+        let mut clusters = Vec::new();
+
+        while let Some(entry) = entries.next_entry().await? {
+            // Mounted `Secrets` have hidden entries, such as `..data`, next to
+            // the keys themselves.
+            if !entry.file_type().await?.is_dir()
+                || entry.file_name().to_string_lossy().starts_with('.')
+            {
+                continue;
+            }
+
+            clusters.push(Self::credentials(&entry.path()).await?);
+        }
+
+        clusters.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(clusters)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A client with the server's own credentials.
+    pub fn client(&self) -> Result<kube::Client, kube::Error> {
+        kube::Client::try_from(self.config.clone())
+    }
+
+    /// A client that acts as `user`. This replaces any impersonation that is
+    /// part of the cluster's own credentials.
+    pub fn impersonate(
+        &self,
+        user: String,
+        groups: Vec<String>,
+    ) -> Result<kube::Client, kube::Error> {
+        let mut cfg = self.config.clone();
+        cfg.auth_info.impersonate = Some(user);
+        cfg.auth_info.impersonate_groups = (!groups.is_empty()).then_some(groups);
+
+        kube::Client::try_from(cfg)
+    }
+}
+
+/// A cluster that a dashboard can use, with a client for the user and the
+/// permissions they have there.
+#[derive(Clone)]
+pub struct Connection {
+    pub name: String,
+    pub client: kube::Client,
+    pub authz: Authz,
+}
+
+impl Connection {
+    pub fn new(name: impl Into<String>, client: kube::Client) -> Self {
+        Self {
+            name: name.into(),
+            authz: Authz::new(client.clone()),
+            client,
+        }
+    }
+}
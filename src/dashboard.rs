@@ -4,6 +4,7 @@ use std::{
     time::Duration,
 };
 
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use bon::Builder;
 use eyre::{eyre, Report, Result};
 use futures::TryStreamExt;
@@ -25,8 +26,12 @@ use tokio::{
 use tokio_util::io::ReaderStream;
 
 use crate::{
-    events::{Broadcast, Event, Input, Keypress, StringError},
+    cluster::Connection,
+    events::{Broadcast, Event, Input, Keypress, Parser, StringError},
+    exports::Owned,
     io::{backend::Backend, Writer},
+    recording::{self, Recorder},
+    resources::preferences::Preferences,
     widget::{apex::Apex, Raw, Widget},
 };
 
@@ -51,7 +56,16 @@ pub fn render_interval() -> Duration {
 
 #[derive(Builder)]
 pub struct Dashboard {
-    client: kube::Client,
+    connection: Connection,
+    // Clusters that can be switched to, including the current one.
+    #[builder(default)]
+    clusters: Vec<Connection>,
+    exports: Option<Owned>,
+    #[builder(default)]
+    preferences: Preferences,
+    // Shells started from the dashboard are recorded with this. Output is
+    // recorded by whatever `stdout` is.
+    recorder: Option<Recorder>,
 }
 
 impl Dashboard {
@@ -82,13 +96,17 @@ impl Dashboard {
             let stream = ReaderStream::new(stdin);
             tokio::pin!(stream);
 
+            let mut parser = Parser::default();
+
             loop {
                 tokio::select! {
                     () = reader_tx.closed() => {
                         break;
                     }
                     Ok(Some(msg)) = stream.try_next() => {
-                        reader_tx.send(msg.into())?;
+                        for ev in Event::parse(&mut parser, &msg) {
+                            reader_tx.send(ev)?;
+                        }
                     }
                 }
             }
@@ -97,7 +115,11 @@ impl Dashboard {
         });
 
         let rt = Builder::new_current_thread().enable_all().build()?;
-        let client = self.client.clone();
+        let connection = self.connection.clone();
+        let clusters = self.clusters.clone();
+        let exports = self.exports.take();
+        let preferences = self.preferences.clone();
+        let recorder = self.recorder.clone();
 
         let local_stdout = stdout.clone();
 
@@ -105,8 +127,12 @@ impl Dashboard {
             TOTAL_DASHBOARD_THREADS.inc();
             ACTIVE_DASHBOARD_THREADS.inc();
 
+            preferences.apply();
+            recording::set(recorder);
+
             if catch_unwind(AssertUnwindSafe(|| {
-                if let Err(err) = rt.block_on(run(client, rx, local_stdout)) {
+                if let Err(err) = rt.block_on(run(connection, clusters, exports, rx, local_stdout))
+                {
                     tracing::error!("Unhandled dashboard error: {err:?}");
                 }
             }))
@@ -155,7 +181,9 @@ impl Mode {
 }
 
 async fn run(
-    client: kube::Client,
+    connection: Connection,
+    clusters: Vec<Connection>,
+    mut exports: Option<Owned>,
     mut rx: UnboundedReceiver<Event>,
 
     stdout: impl Writer,
@@ -169,10 +197,12 @@ async fn run(
     let (backend, window_size) = Backend::with_size(stdout.blocking());
     let mut term = Terminal::new(backend)?;
 
+    modes(&mut stdout.blocking(), true)?;
+
     // kube::Client ends up being cloned by ~every widget, it'd be nice to Arc<> it
     // so that there's not a bunch of copying. Unfortunately, the Api interface
     // doesn't like Arc<>.
-    let mut state = Mode::UI(Box::new(Apex::new(client)));
+    let mut state = Mode::UI(Box::new(Apex::new(connection, clusters)));
 
     loop {
         // It is important that this doesn't go *too* fast. Repeatedly writing to the
@@ -201,6 +231,8 @@ async fn run(
                 let raw_result =
                     draw_raw(raw_widget, &mut term, &mut rx, stdout.non_blocking()).await;
 
+                modes(&mut stdout.blocking(), true)?;
+
                 let area = term.get_frame().area();
 
                 let result = current_widget.dispatch(
@@ -220,9 +252,31 @@ async fn run(
                 break;
             }
             Broadcast::Raw(widget) => {
+                // Whatever is attached gets input exactly as the client sent it, mouse
+                // reports would only get in the way.
+                modes(&mut stdout.blocking(), false)?;
+
                 state.raw(widget);
             }
-            Broadcast::Consumed => interval.reset_immediately(),
+            // Widgets see every render so that they can act on background work finishing, such
+            // as jumping straight into a shell. Consuming a render shouldn't force another one.
+            Broadcast::Consumed if !matches!(ev, Event::Render) => interval.reset_immediately(),
+            Broadcast::Copy(content) => {
+                copy(&mut stdout.blocking(), &content)?;
+
+                interval.reset_immediately();
+            }
+            Broadcast::Save(name, content) => {
+                if let Some(exports) = exports.as_mut() {
+                    let path = exports.save(name, content.into_bytes());
+
+                    tracing::debug!(path, "saved export");
+                } else {
+                    tracing::debug!(name, "no exports available, dropping");
+                }
+
+                interval.reset_immediately();
+            }
             _ => {}
         }
     }
@@ -232,6 +286,8 @@ async fn run(
         frame.set_cursor_position(Position::default());
     })?;
 
+    modes(&mut stdout.blocking(), false)?;
+
     // This is a somewhat arbitrary sleep to allow for a flush to happen before the
     // channel is shutdown. It seems that this isn't required locally, but when
     // running from a cluster it needs a little bit of time.
@@ -242,6 +298,28 @@ async fn run(
     Ok(())
 }
 
+// Uses OSC 52 to set the clipboard of the client's terminal. This doesn't work
+// everywhere, but is supported by most modern terminals (and tmux, if
+// `set-clipboard` is on).
+fn copy(stdout: &mut impl std::io::Write, content: &str) -> Result<()> {
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(content))?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+// Button presses and the scroll wheel (1000), reported with SGR encoding
+// (1006) so that positions aren't limited to 223 columns. Bracketed paste
+// (2004) wraps pasted text so that it isn't mistaken for keys.
+fn modes(stdout: &mut impl std::io::Write, enabled: bool) -> Result<()> {
+    let mode = if enabled { 'h' } else { 'l' };
+
+    write!(stdout, "\x1b[?1000{mode}\x1b[?1006{mode}\x1b[?2004{mode}")?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
 fn draw_ui<W>(
     widget: &mut Box<dyn Widget>,
     term: &mut Terminal<Backend<W>>,
@@ -281,7 +359,6 @@ fn dispatch(
 
             widget.dispatch(ev, buffer, area)
         }
-        Event::Render => Ok(Broadcast::Ignored),
         _ => widget.dispatch(ev, buffer, area),
     }
 }
@@ -1,9 +1,13 @@
-use std::{error::Error, str};
+mod vt;
+
+use std::error::Error;
 
 use eyre::Result;
-use ratatui::backend::WindowSize;
+use ratatui::{backend::WindowSize, layout::Position};
 use tokio_util::bytes::Bytes;
 
+use self::vt::Token;
+pub use self::vt::{control, Keypress, Modifiers, Mouse, MouseButton, MouseKind, Parser};
 use crate::{resources::tunnel, widget::Raw};
 
 #[derive(Debug)]
@@ -17,11 +21,19 @@ pub enum Broadcast {
     // occurred in it. The parent is expected to handle this as part of propagating the dispatch
     // back to the apex.
     Selected(usize),
+    // A route, from `Event::Goto`, matched the item at this index. The parent is expected to open
+    // it and pass the remainder of the route along to whatever was opened.
+    Routed(usize, Vec<String>),
+    // Send the content to the client's clipboard.
+    Copy(String),
+    // Save the content, under the provided name, so that it can be downloaded via SFTP.
+    Save(String, String),
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
     Input(Input),
+    Mouse(Mouse),
     Resize(WindowSize),
     Goto(Vec<String>),
     Shutdown,
@@ -37,20 +49,34 @@ impl Event {
             _ => None,
         }
     }
-}
 
-impl From<&[u8]> for Event {
-    fn from(data: &[u8]) -> Event {
-        Bytes::copy_from_slice(data).into()
+    pub fn mouse(&self) -> Option<&Mouse> {
+        match self {
+            Event::Mouse(mouse) => Some(mouse),
+            _ => None,
+        }
+    }
+
+    /// Everything in `data`, which can be any number of events. Input that
+    /// spans reads, such as a long paste, is kept by `parser` until it's done.
+    pub fn parse(parser: &mut Parser, data: &[u8]) -> Vec<Event> {
+        parser
+            .feed(data)
+            .into_iter()
+            .map(|(token, raw)| match token {
+                Token::Key(key) => Event::Input(Input {
+                    key,
+                    raw: raw.into(),
+                }),
+                Token::Mouse(mouse) => Event::Mouse(mouse),
+            })
+            .collect()
     }
 }
 
-impl From<Bytes> for Event {
-    fn from(data: Bytes) -> Event {
-        Event::Input(Input {
-            key: data.as_ref().into(),
-            raw: data,
-        })
+impl Mouse {
+    pub fn position(self) -> Position {
+        Position::new(self.column, self.row)
     }
 }
 
@@ -76,121 +102,3 @@ impl<'a> From<&'a Input> for &'a [u8] {
         input.raw.as_ref()
     }
 }
-
-#[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone)]
-pub enum Keypress {
-    Null,
-    Control(char),
-
-    // Most of these should map to ctrl+char but some of them are mapped to their own keys and I'm
-    // not confident that I can distinguish between those use cases.
-    StartOfHeader,
-    StartOfText,
-    EndOfText,
-    EndOfTransmission,
-    Enquiry,
-    Acknowledge,
-    Bell,
-    Backspace,
-    HorizontalTab,
-    Enter, // Linefeed
-    VerticalTab,
-    Formfeed,
-    // CarriageReturn, // Enter
-    ShiftOut,
-    ShiftIn,
-    DLE,
-    XON,
-    DC2,
-    XOFF,
-    DC4,
-    NAK,
-    SYN,
-    ETB,
-    Cancel,
-    EM,
-    Substitute,
-    Escape,
-    FS,
-    GS,
-    RS,
-    US,
-    Delete,
-    Printable(char),
-
-    // Escape Sequences
-    CursorUp,
-    CursorDown,
-    CursorRight,
-    CursorLeft,
-    CursorHome,
-
-    Unknown(Bytes),
-}
-
-fn parse_escape(data: &[u8]) -> Keypress {
-    if data.len() == 1 {
-        return Keypress::Escape;
-    }
-
-    if data[1] != b'[' {
-        return Keypress::Unknown(Bytes::copy_from_slice(data));
-    }
-
-    match data[2..] {
-        [b'A'] => Keypress::CursorUp,
-        [b'B'] => Keypress::CursorDown,
-        [b'C'] => Keypress::CursorRight,
-        [b'D'] => Keypress::CursorLeft,
-        [b'H'] => Keypress::CursorHome,
-        _ => Keypress::Unknown(Bytes::copy_from_slice(data)),
-    }
-}
-
-impl From<&[u8]> for Keypress {
-    fn from(data: &[u8]) -> Keypress {
-        match data[0] {
-            b'\x00' => Keypress::Null,
-            b'\x01' => Keypress::Control('a'),
-            // b'\x01' => Keypress::StartOfHeader,
-            b'\x02' => Keypress::Control('b'),
-            // b'\x02' => Keypress::StartOfText,
-            b'\x03' => Keypress::Control('c'),
-            // b'\x03' => Keypress::EndOfText,
-            b'\x04' => Keypress::Control('d'),
-            // b'\x04' => Keypress::EndOfTransmission,
-            b'\x05' => Keypress::Control('e'),
-            // b'\x05' => Keypress::Enquiry,
-            b'\x06' => Keypress::Control('f'),
-            // b'\x06' => Keypress::Acknowledge,
-            b'\x07' => Keypress::Bell,
-            b'\x08' => Keypress::Backspace,
-            b'\x09' => Keypress::HorizontalTab,
-            b'\x0A' | b'\x0D' => Keypress::Enter,
-            b'\x0B' => Keypress::Control('k'),
-            // b'\x0B' => Keypress::VerticalTab,
-            b'\x0C' => Keypress::Formfeed,
-            b'\x0E' => Keypress::ShiftOut,
-            b'\x0F' => Keypress::ShiftIn,
-            b'\x10' => Keypress::DLE,
-            b'\x11' => Keypress::XON,
-            b'\x12' => Keypress::DC2,
-            b'\x13' => Keypress::XOFF,
-            b'\x14' => Keypress::DC4,
-            b'\x15' => Keypress::NAK,
-            b'\x16' => Keypress::SYN,
-            b'\x17' => Keypress::ETB,
-            b'\x18' => Keypress::Cancel,
-            b'\x19' => Keypress::EM,
-            b'\x1A' => Keypress::Substitute,
-            b'\x1b' => parse_escape(data),
-            b'\x1C' => Keypress::FS,
-            b'\x1D' => Keypress::GS,
-            b'\x1E' => Keypress::RS,
-            b'\x1F' => Keypress::US,
-            b'\x7f' => Keypress::Delete,
-            _ => Keypress::Printable(str::from_utf8(data).unwrap().chars().next().unwrap()),
-        }
-    }
-}
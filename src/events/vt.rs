@@ -0,0 +1,757 @@
+//! Parses what a VT100/xterm compatible terminal sends as input. A single read
+//! can contain any number of keys, escape sequences, mouse reports and pastes.
+//!
+//! This only depends on `std` so that the fuzz target can include it directly.
+
+use std::str;
+
+// Pastes that never finish are given up on after this many bytes, instead of
+// holding on to input forever.
+const MAX_PASTE: usize = 1024 * 1024;
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Keypress {
+    Null,
+    Control(char),
+
+    // Most of these should map to ctrl+char but some of them are mapped to their own keys and I'm
+    // not confident that I can distinguish between those use cases.
+    StartOfHeader,
+    StartOfText,
+    EndOfText,
+    EndOfTransmission,
+    Enquiry,
+    Acknowledge,
+    Bell,
+    Backspace,
+    HorizontalTab,
+    Enter, // Linefeed
+    VerticalTab,
+    Formfeed,
+    // CarriageReturn, // Enter
+    ShiftOut,
+    ShiftIn,
+    DLE,
+    XON,
+    DC2,
+    XOFF,
+    DC4,
+    NAK,
+    SYN,
+    ETB,
+    Cancel,
+    EM,
+    Substitute,
+    Escape,
+    FS,
+    GS,
+    RS,
+    US,
+    Delete,
+    Printable(char),
+
+    // Escape Sequences
+    CursorUp,
+    CursorDown,
+    CursorRight,
+    CursorLeft,
+    CursorHome,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    // The delete key, as opposed to `Delete` which most terminals send for backspace.
+    ForwardDelete,
+    Function(u8),
+
+    // Any of the above with modifiers held down, such as `ctrl+up` or `alt+x`.
+    Modified(Modifiers, Box<Keypress>),
+    // Text pasted while bracketed paste is on, it arrives all at once instead of as keys.
+    Paste(String),
+
+    Unknown(Vec<u8>),
+}
+
+/// Modifiers as xterm reports them, one bit each.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub control: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub const ALT: Self = Self {
+        shift: false,
+        alt: true,
+        control: false,
+        meta: false,
+    };
+
+    pub const SHIFT: Self = Self {
+        shift: true,
+        alt: false,
+        control: false,
+        meta: false,
+    };
+
+    pub const CONTROL: Self = Self {
+        shift: false,
+        alt: false,
+        control: true,
+        meta: false,
+    };
+
+    // xterm sends modifiers as a parameter that is one more than a bitmask.
+    fn from_param(param: u16) -> Self {
+        let bits = param.saturating_sub(1);
+
+        Self {
+            shift: bits & 1 != 0,
+            alt: bits & 2 != 0,
+            control: bits & 4 != 0,
+            meta: bits & 8 != 0,
+        }
+    }
+
+    fn apply(self, key: Keypress) -> Keypress {
+        if self == Self::default() {
+            key
+        } else {
+            Keypress::Modified(self, Box::new(key))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    Drag(MouseButton),
+    ScrollUp,
+    ScrollDown,
+}
+
+/// A mouse event, reported by the client's terminal in SGR (1006) mode. The
+/// column and row are zero based, the same as a `Rect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mouse {
+    pub kind: MouseKind,
+    pub column: u16,
+    pub row: u16,
+}
+
+impl Mouse {
+    // SGR reports look like `ESC [ < button ; column ; row M`, with a trailing
+    // `m` instead when a button is released. This gets everything between the
+    // `<` and the final byte.
+    fn parse(params: &[u8], release: bool) -> Option<Self> {
+        let mut fields = str::from_utf8(params)
+            .ok()?
+            .split(';')
+            .map(str::parse::<u16>);
+
+        let (Some(Ok(code)), Some(Ok(column)), Some(Ok(row)), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return None;
+        };
+
+        let button = match code & 0b11 {
+            0 => MouseButton::Left,
+            1 => MouseButton::Middle,
+            _ => MouseButton::Right,
+        };
+
+        let kind = match (code & 0b110_0000, release) {
+            (64, _) if code & 1 == 0 => MouseKind::ScrollUp,
+            (64, _) => MouseKind::ScrollDown,
+            (32, _) => MouseKind::Drag(button),
+            (_, true) => MouseKind::Up(button),
+            (_, false) => MouseKind::Down(button),
+        };
+
+        Some(Self {
+            kind,
+            column: column.saturating_sub(1),
+            row: row.saturating_sub(1),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Key(Keypress),
+    Mouse(Mouse),
+}
+
+enum Step {
+    // What was parsed and how many bytes it took.
+    Token(Token, usize),
+    // The input stops part way through something that can span reads.
+    Incomplete,
+}
+
+/// Splits input into tokens, along with the bytes each one came from. Pastes
+/// can be larger than a single read, so they're held on to until they end.
+#[derive(Debug, Default)]
+pub struct Parser {
+    pending: Vec<u8>,
+}
+
+impl Parser {
+    pub fn feed(&mut self, data: &[u8]) -> Vec<(Token, Vec<u8>)> {
+        let mut buffer = std::mem::take(&mut self.pending);
+        buffer.extend_from_slice(data);
+
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+
+        while pos < buffer.len() {
+            match next(&buffer[pos..]) {
+                Step::Token(token, len) => {
+                    tokens.push((token, buffer[pos..pos + len].to_vec()));
+                    pos += len;
+                }
+                Step::Incomplete => {
+                    self.pending = buffer[pos..].to_vec();
+
+                    break;
+                }
+            }
+        }
+
+        tokens
+    }
+}
+
+fn next(data: &[u8]) -> Step {
+    if data[0] == b'\x1b' {
+        return escape(data);
+    }
+
+    let (key, len) = single(data);
+
+    Step::Token(Token::Key(key), len)
+}
+
+fn key(key: Keypress, len: usize) -> Step {
+    Step::Token(Token::Key(key), len)
+}
+
+fn unknown(data: &[u8]) -> Step {
+    key(Keypress::Unknown(data.to_vec()), data.len())
+}
+
+/// What a terminal sends for `ctrl` and a letter. Several of these have names
+/// of their own, such as `Bell` for `ctrl-g`.
+pub fn control(c: char) -> Option<Keypress> {
+    c.is_ascii_lowercase()
+        .then(|| single(&[u8::try_from(c).unwrap_or_default() & 0x1f]).0)
+}
+
+// A single byte control character or a UTF-8 encoded character.
+fn single(data: &[u8]) -> (Keypress, usize) {
+    let key = match data[0] {
+        b'\x00' => Keypress::Null,
+        b'\x01' => Keypress::Control('a'),
+        // b'\x01' => Keypress::StartOfHeader,
+        b'\x02' => Keypress::Control('b'),
+        // b'\x02' => Keypress::StartOfText,
+        b'\x03' => Keypress::Control('c'),
+        // b'\x03' => Keypress::EndOfText,
+        b'\x04' => Keypress::Control('d'),
+        // b'\x04' => Keypress::EndOfTransmission,
+        b'\x05' => Keypress::Control('e'),
+        // b'\x05' => Keypress::Enquiry,
+        b'\x06' => Keypress::Control('f'),
+        // b'\x06' => Keypress::Acknowledge,
+        b'\x07' => Keypress::Bell,
+        b'\x08' => Keypress::Backspace,
+        b'\x09' => Keypress::HorizontalTab,
+        b'\x0A' | b'\x0D' => Keypress::Enter,
+        b'\x0B' => Keypress::Control('k'),
+        // b'\x0B' => Keypress::VerticalTab,
+        b'\x0C' => Keypress::Formfeed,
+        b'\x0E' => Keypress::ShiftOut,
+        b'\x0F' => Keypress::ShiftIn,
+        b'\x10' => Keypress::DLE,
+        b'\x11' => Keypress::XON,
+        b'\x12' => Keypress::DC2,
+        b'\x13' => Keypress::Control('s'),
+        // b'\x13' => Keypress::XOFF,
+        b'\x14' => Keypress::DC4,
+        b'\x15' => Keypress::NAK,
+        b'\x16' => Keypress::SYN,
+        b'\x17' => Keypress::ETB,
+        b'\x18' => Keypress::Cancel,
+        b'\x19' => Keypress::EM,
+        b'\x1A' => Keypress::Substitute,
+        b'\x1b' => Keypress::Escape,
+        b'\x1C' => Keypress::FS,
+        b'\x1D' => Keypress::GS,
+        b'\x1E' => Keypress::RS,
+        b'\x1F' => Keypress::US,
+        b'\x7f' => Keypress::Delete,
+        first => {
+            let len = match first {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 0,
+            };
+
+            return match data
+                .get(..len)
+                .filter(|_| len > 0)
+                .and_then(|bytes| str::from_utf8(bytes).ok())
+                .and_then(|s| s.chars().next())
+            {
+                Some(c) => (Keypress::Printable(c), len),
+                None => (Keypress::Unknown(vec![first]), 1),
+            };
+        }
+    };
+
+    (key, 1)
+}
+
+fn escape(data: &[u8]) -> Step {
+    match data.get(1) {
+        // Without a timeout, there's no way to tell an escape at the end of a read apart from
+        // the start of a sequence. Terminals send sequences all at once, so this is an escape.
+        None | Some(b'\x1b') => key(Keypress::Escape, 1),
+        Some(b'[') => csi(data),
+        Some(b'O') => ss3(data),
+        // Most terminals send alt+key as an escape followed by the key.
+        Some(_) => {
+            let (inner, len) = single(&data[1..]);
+
+            key(Modifiers::ALT.apply(inner), len + 1)
+        }
+    }
+}
+
+// `ESC [`, followed by parameters and finished by a single byte in `@..=~`.
+fn csi(data: &[u8]) -> Step {
+    let Some(end) = data
+        .iter()
+        .skip(2)
+        .position(|b| !(0x20..=0x3f).contains(b))
+        .map(|idx| idx + 2)
+    else {
+        return unknown(data);
+    };
+
+    let last = data[end];
+
+    if !(0x40..=0x7e).contains(&last) {
+        return unknown(&data[..end]);
+    }
+
+    let params = &data[2..end];
+    let len = end + 1;
+
+    if let Some(mouse) = params.strip_prefix(b"<") {
+        return match (last, Mouse::parse(mouse, last == b'm')) {
+            (b'M' | b'm', Some(mouse)) => Step::Token(Token::Mouse(mouse), len),
+            _ => unknown(&data[..len]),
+        };
+    }
+
+    if data.starts_with(PASTE_START) {
+        return paste(data);
+    }
+
+    // Legacy mouse reports are three raw bytes after `ESC [ M`. Only SGR reports are asked for,
+    // but these shouldn't turn into keypresses if they show up anyway.
+    if params.is_empty() && last == b'M' {
+        return unknown(&data[..(len + 3).min(data.len())]);
+    }
+
+    let Some(params) = str::from_utf8(params).ok().and_then(|params| {
+        params
+            .split(';')
+            .map(|p| {
+                if p.is_empty() {
+                    Ok(1)
+                } else {
+                    p.parse::<u16>()
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+    }) else {
+        return unknown(&data[..len]);
+    };
+
+    let first = params.first().copied().unwrap_or(1);
+    let modifiers = Modifiers::from_param(params.get(1).copied().unwrap_or(1));
+
+    let code = match last {
+        b'Z' => return key(Modifiers::SHIFT.apply(Keypress::HorizontalTab), len),
+        b'~' => match first {
+            1 | 7 => Keypress::CursorHome,
+            2 => Keypress::Insert,
+            3 => Keypress::ForwardDelete,
+            4 | 8 => Keypress::End,
+            5 => Keypress::PageUp,
+            6 => Keypress::PageDown,
+            #[allow(clippy::cast_possible_truncation)]
+            11..=15 => Keypress::Function((first - 10) as u8),
+            #[allow(clippy::cast_possible_truncation)]
+            17..=21 => Keypress::Function((first - 11) as u8),
+            #[allow(clippy::cast_possible_truncation)]
+            23 | 24 => Keypress::Function((first - 12) as u8),
+            _ => return unknown(&data[..len]),
+        },
+        last => match final_key(last) {
+            Some(code) => code,
+            None => return unknown(&data[..len]),
+        },
+    };
+
+    key(modifiers.apply(code), len)
+}
+
+// `ESC O` followed by a single byte, sent for arrows in application mode and
+// for F1-F4.
+fn ss3(data: &[u8]) -> Step {
+    let Some(last) = data.get(2) else {
+        return key(Modifiers::ALT.apply(Keypress::Printable('O')), 2);
+    };
+
+    match final_key(*last) {
+        Some(code) => key(code, 3),
+        None => unknown(&data[..3]),
+    }
+}
+
+// The keys that CSI and SS3 sequences share.
+fn final_key(last: u8) -> Option<Keypress> {
+    Some(match last {
+        b'A' => Keypress::CursorUp,
+        b'B' => Keypress::CursorDown,
+        b'C' => Keypress::CursorRight,
+        b'D' => Keypress::CursorLeft,
+        b'H' => Keypress::CursorHome,
+        b'F' => Keypress::End,
+        b'P'..=b'S' => Keypress::Function(last - b'P' + 1),
+        _ => return None,
+    })
+}
+
+fn paste(data: &[u8]) -> Step {
+    let content = &data[PASTE_START.len()..];
+
+    let (text, len) = match content
+        .windows(PASTE_END.len())
+        .position(|window| window == PASTE_END)
+    {
+        Some(end) => (&content[..end], PASTE_START.len() + end + PASTE_END.len()),
+        None if data.len() < MAX_PASTE => return Step::Incomplete,
+        None => (content, data.len()),
+    };
+
+    key(
+        Keypress::Paste(String::from_utf8_lossy(text).into_owned()),
+        len,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(data: &[u8]) -> Vec<Keypress> {
+        Parser::default()
+            .feed(data)
+            .into_iter()
+            .filter_map(|(token, _)| match token {
+                Token::Key(key) => Some(key),
+                Token::Mouse(_) => None,
+            })
+            .collect()
+    }
+
+    fn key(data: &[u8]) -> Keypress {
+        let keys = keys(data);
+
+        assert_eq!(
+            keys.len(),
+            1,
+            "expected one key from {data:?}, got {keys:?}"
+        );
+
+        keys.into_iter().next().unwrap()
+    }
+
+    fn modified(modifiers: Modifiers, key: Keypress) -> Keypress {
+        Keypress::Modified(modifiers, Box::new(key))
+    }
+
+    #[test]
+    fn printable() {
+        assert_eq!(
+            keys(b"ab"),
+            vec![Keypress::Printable('a'), Keypress::Printable('b')]
+        );
+        assert_eq!(
+            keys("é€😀".as_bytes()),
+            vec![
+                Keypress::Printable('é'),
+                Keypress::Printable('€'),
+                Keypress::Printable('😀'),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert_eq!(
+            keys(b"\xff\xc3a"),
+            vec![
+                Keypress::Unknown(vec![0xff]),
+                Keypress::Unknown(vec![0xc3]),
+                Keypress::Printable('a'),
+            ]
+        );
+    }
+
+    #[test]
+    fn control() {
+        assert_eq!(key(b"\x03"), Keypress::Control('c'));
+        assert_eq!(key(b"\r"), Keypress::Enter);
+        assert_eq!(key(b"\t"), Keypress::HorizontalTab);
+        assert_eq!(key(b"\x7f"), Keypress::Delete);
+        assert_eq!(key(b"\x1b"), Keypress::Escape);
+        assert_eq!(keys(b"\x1b\x1b"), vec![Keypress::Escape, Keypress::Escape]);
+    }
+
+    #[test]
+    fn cursor() {
+        assert_eq!(key(b"\x1b[A"), Keypress::CursorUp);
+        assert_eq!(key(b"\x1b[B"), Keypress::CursorDown);
+        assert_eq!(key(b"\x1b[C"), Keypress::CursorRight);
+        assert_eq!(key(b"\x1b[D"), Keypress::CursorLeft);
+        assert_eq!(key(b"\x1bOA"), Keypress::CursorUp);
+        assert_eq!(key(b"\x1bOD"), Keypress::CursorLeft);
+    }
+
+    #[test]
+    fn navigation() {
+        assert_eq!(key(b"\x1b[H"), Keypress::CursorHome);
+        assert_eq!(key(b"\x1b[1~"), Keypress::CursorHome);
+        assert_eq!(key(b"\x1bOH"), Keypress::CursorHome);
+        assert_eq!(key(b"\x1b[F"), Keypress::End);
+        assert_eq!(key(b"\x1b[4~"), Keypress::End);
+        assert_eq!(key(b"\x1b[2~"), Keypress::Insert);
+        assert_eq!(key(b"\x1b[3~"), Keypress::ForwardDelete);
+        assert_eq!(key(b"\x1b[5~"), Keypress::PageUp);
+        assert_eq!(key(b"\x1b[6~"), Keypress::PageDown);
+    }
+
+    #[test]
+    fn function() {
+        assert_eq!(key(b"\x1bOP"), Keypress::Function(1));
+        assert_eq!(key(b"\x1bOS"), Keypress::Function(4));
+        assert_eq!(key(b"\x1b[15~"), Keypress::Function(5));
+        assert_eq!(key(b"\x1b[17~"), Keypress::Function(6));
+        assert_eq!(key(b"\x1b[21~"), Keypress::Function(10));
+        assert_eq!(key(b"\x1b[23~"), Keypress::Function(11));
+        assert_eq!(key(b"\x1b[24~"), Keypress::Function(12));
+    }
+
+    #[test]
+    fn modifiers() {
+        assert_eq!(
+            key(b"\x1b[1;5A"),
+            modified(Modifiers::CONTROL, Keypress::CursorUp)
+        );
+        assert_eq!(
+            key(b"\x1b[1;2D"),
+            modified(Modifiers::SHIFT, Keypress::CursorLeft)
+        );
+        assert_eq!(
+            key(b"\x1b[1;5P"),
+            modified(Modifiers::CONTROL, Keypress::Function(1))
+        );
+        assert_eq!(
+            key(b"\x1b[3;3~"),
+            modified(Modifiers::ALT, Keypress::ForwardDelete)
+        );
+        assert_eq!(
+            key(b"\x1b[6;8~"),
+            modified(
+                Modifiers {
+                    shift: true,
+                    alt: true,
+                    control: true,
+                    meta: false,
+                },
+                Keypress::PageDown
+            )
+        );
+        assert_eq!(key(b"\x1b[1;1A"), Keypress::CursorUp);
+        assert_eq!(
+            key(b"\x1b[Z"),
+            modified(Modifiers::SHIFT, Keypress::HorizontalTab)
+        );
+    }
+
+    #[test]
+    fn alt() {
+        assert_eq!(
+            key(b"\x1bx"),
+            modified(Modifiers::ALT, Keypress::Printable('x'))
+        );
+        assert_eq!(
+            key(b"\x1b\x01"),
+            modified(Modifiers::ALT, Keypress::Control('a'))
+        );
+        assert_eq!(
+            key(b"\x1bO"),
+            modified(Modifiers::ALT, Keypress::Printable('O'))
+        );
+    }
+
+    #[test]
+    fn several() {
+        assert_eq!(
+            keys(b"\x1b[A\x1b[Bq\x1b[1;5C\x1b"),
+            vec![
+                Keypress::CursorUp,
+                Keypress::CursorDown,
+                Keypress::Printable('q'),
+                modified(Modifiers::CONTROL, Keypress::CursorRight),
+                Keypress::Escape,
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(key(b"\x1b[99~"), Keypress::Unknown(b"\x1b[99~".to_vec()));
+        assert_eq!(key(b"\x1b[1;5"), Keypress::Unknown(b"\x1b[1;5".to_vec()));
+        assert_eq!(key(b"\x1bOx"), Keypress::Unknown(b"\x1bOx".to_vec()));
+        assert_eq!(
+            keys(b"\x1b[1\x03"),
+            vec![
+                Keypress::Unknown(b"\x1b[1".to_vec()),
+                Keypress::Control('c')
+            ]
+        );
+    }
+
+    #[test]
+    fn paste() {
+        assert_eq!(
+            keys(b"a\x1b[200~hello\r\x1b[A\x1b[201~b"),
+            vec![
+                Keypress::Printable('a'),
+                Keypress::Paste("hello\r\x1b[A".to_string()),
+                Keypress::Printable('b'),
+            ]
+        );
+    }
+
+    #[test]
+    fn paste_across_reads() {
+        let mut parser = Parser::default();
+
+        assert_eq!(parser.feed(b"x\x1b[200~hel").len(), 1);
+        assert!(parser.feed(b"lo").is_empty());
+        assert_eq!(
+            parser.feed(b"\x1b[201~"),
+            vec![(
+                Token::Key(Keypress::Paste("hello".to_string())),
+                b"\x1b[200~hello\x1b[201~".to_vec()
+            )]
+        );
+    }
+
+    #[test]
+    fn paste_without_end() {
+        let mut data = PASTE_START.to_vec();
+        data.extend(std::iter::repeat(b'a').take(MAX_PASTE));
+
+        let keys = keys(&data);
+
+        assert!(matches!(keys.as_slice(), [Keypress::Paste(text)] if text.len() == MAX_PASTE));
+    }
+
+    #[test]
+    fn mouse() {
+        let tokens = Parser::default()
+            .feed(b"\x1b[<0;10;5M\x1b[<0;10;5m\x1b[<64;1;1M\x1b[<65;1;1M\x1b[<34;3;4M");
+
+        assert_eq!(
+            tokens
+                .into_iter()
+                .map(|(token, _)| token)
+                .collect::<Vec<_>>(),
+            vec![
+                Token::Mouse(Mouse {
+                    kind: MouseKind::Down(MouseButton::Left),
+                    column: 9,
+                    row: 4,
+                }),
+                Token::Mouse(Mouse {
+                    kind: MouseKind::Up(MouseButton::Left),
+                    column: 9,
+                    row: 4,
+                }),
+                Token::Mouse(Mouse {
+                    kind: MouseKind::ScrollUp,
+                    column: 0,
+                    row: 0,
+                }),
+                Token::Mouse(Mouse {
+                    kind: MouseKind::ScrollDown,
+                    column: 0,
+                    row: 0,
+                }),
+                Token::Mouse(Mouse {
+                    kind: MouseKind::Drag(MouseButton::Right),
+                    column: 2,
+                    row: 3,
+                }),
+            ]
+        );
+
+        assert_eq!(
+            key(b"\x1b[<0;1M"),
+            Keypress::Unknown(b"\x1b[<0;1M".to_vec())
+        );
+        assert_eq!(key(b"\x1b[M !!"), Keypress::Unknown(b"\x1b[M !!".to_vec()));
+    }
+
+    // Raw mode passes the bytes of each token along, so nothing can be lost or
+    // duplicated.
+    #[test]
+    fn raw_covers_input() {
+        let data: &[u8] = b"a\x1b[A\x1b[<0;1;1M\xff\x1b[200~p\x1b[201~\x1bx\x1b[1";
+
+        let raw = Parser::default()
+            .feed(data)
+            .into_iter()
+            .flat_map(|(_, raw)| raw)
+            .collect::<Vec<_>>();
+
+        assert_eq!(raw, data);
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, register_int_gauge, IntCounter, IntGauge};
+
+lazy_static! {
+    static ref EXPORTS_TOTAL: IntCounter = register_int_counter!(
+        "exports_total",
+        "Number of buffers that have been saved for download via SFTP"
+    )
+    .unwrap();
+    static ref EXPORTS_BYTES: IntGauge = register_int_gauge!(
+        "exports_bytes",
+        "Number of bytes held by buffers saved for download via SFTP"
+    )
+    .unwrap();
+}
+
+/// Directory, in the SFTP file tree, that saved buffers show up in.
+pub static PREFIX: &str = "/.kty/exports";
+
+// Saved buffers for a single owner, keyed by name.
+type Files = BTreeMap<String, Vec<u8>>;
+
+/// Buffers saved from the dashboard so that they can be downloaded via SFTP.
+/// These are kept in memory, per user, for as long as the dashboard that saved
+/// them is running.
+#[derive(Clone, Default)]
+pub struct Exports(Arc<Mutex<HashMap<String, Files>>>);
+
+impl Exports {
+    /// Access to the exports of a single user. Anything saved with the returned
+    /// handle is removed when it is dropped.
+    pub fn owner(&self, owner: String) -> Owned {
+        Owned {
+            exports: self.clone(),
+            owner,
+            saved: Vec::new(),
+        }
+    }
+
+    pub fn list(&self, owner: &str) -> Vec<(String, usize)> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(owner)
+            .map(|files| files.iter().map(|(k, v)| (k.clone(), v.len())).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, owner: &str, name: &str) -> Option<Vec<u8>> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(owner)
+            .and_then(|files| files.get(name).cloned())
+    }
+}
+
+pub struct Owned {
+    exports: Exports,
+    owner: String,
+    saved: Vec<String>,
+}
+
+impl Owned {
+    /// Save `data` as `name`, returning the full path that it can be
+    /// downloaded from. Saving over an existing name replaces it.
+    pub fn save(&mut self, name: String, data: Vec<u8>) -> String {
+        EXPORTS_TOTAL.inc();
+        EXPORTS_BYTES.add(i64::try_from(data.len()).unwrap_or(i64::MAX));
+
+        let path = format!("{PREFIX}/{name}");
+
+        if let Some(previous) = self
+            .exports
+            .0
+            .lock()
+            .unwrap()
+            .entry(self.owner.clone())
+            .or_default()
+            .insert(name.clone(), data)
+        {
+            EXPORTS_BYTES.sub(i64::try_from(previous.len()).unwrap_or(i64::MAX));
+        }
+
+        self.saved.push(name);
+
+        path
+    }
+}
+
+impl Drop for Owned {
+    fn drop(&mut self) {
+        let mut exports = self.exports.0.lock().unwrap();
+
+        let Some(files) = exports.get_mut(&self.owner) else {
+            return;
+        };
+
+        for name in &self.saved {
+            if let Some(data) = files.remove(name) {
+                EXPORTS_BYTES.sub(i64::try_from(data.len()).unwrap_or(i64::MAX));
+            }
+        }
+
+        if files.is_empty() {
+            exports.remove(&self.owner);
+        }
+    }
+}
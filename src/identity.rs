@@ -10,7 +10,10 @@ use k8s_openapi::api::authorization::v1::{
 pub use key::Key;
 use kube::api::{Api, PostParams};
 
-use crate::ssh::{Authenticate, Controller};
+use crate::{
+    cluster::Cluster,
+    ssh::{Authenticate, Controller},
+};
 
 #[derive(Clone, Debug)]
 pub struct Identity {
@@ -33,16 +36,18 @@ impl Identity {
         self
     }
 
-    pub fn client(&self, ctrl: &Controller) -> Result<kube::Client, kube::Error> {
-        ctrl.impersonate(self.name.clone(), self.groups.clone())
+    pub fn client(&self, cluster: &Cluster) -> Result<kube::Client, kube::Error> {
+        cluster.impersonate(self.name.clone(), self.groups.clone())
     }
 }
 
 #[async_trait::async_trait]
 impl Authenticate for Identity {
-    #[tracing::instrument(skip_all)]
-    async fn authenticate(&self, ctrl: &Controller) -> Result<Option<Identity>> {
-        let client = self.client(ctrl)?;
+    // Users are checked in each cluster, being allowed in one doesn't say
+    // anything about the others.
+    #[tracing::instrument(skip_all, fields(cluster = cluster.name()))]
+    async fn authenticate(&self, _: &Controller, cluster: &Cluster) -> Result<Option<Identity>> {
+        let client = self.client(cluster)?;
 
         let access = Api::<SelfSubjectAccessReview>::all(client.clone())
             .create(
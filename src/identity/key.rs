@@ -11,6 +11,7 @@ use serde_json::json;
 
 use super::Identity;
 use crate::{
+    cluster::Cluster,
     resources::{ApplyPatch, KubeID, MANAGER},
     ssh::{Authenticate, Controller},
 };
@@ -122,8 +123,10 @@ impl Authenticate for PublicKey {
     // - get the user from owner references
     // - validate the expiration
     // - should there be a status field or condition for an existing key?
-    #[tracing::instrument(skip_all)]
-    async fn authenticate(&self, ctrl: &Controller) -> Result<Option<Identity>> {
+    // Keys only live in the cluster the server runs in, the identity they map
+    // to is what gets checked in `cluster`.
+    #[tracing::instrument(skip_all, fields(cluster = cluster.name()))]
+    async fn authenticate(&self, ctrl: &Controller, cluster: &Cluster) -> Result<Option<Identity>> {
         let keys: Api<Key> = Api::default_namespaced(ctrl.client()?);
 
         let Some(key): Option<Key> = keys.get_opt(&self.kube_id()?).await? else {
@@ -134,7 +137,7 @@ impl Authenticate for PublicKey {
             return Ok(None);
         }
 
-        let Some(ident) = Identity::authenticate(&key.clone().into(), ctrl).await? else {
+        let Some(ident) = Identity::authenticate(&key.clone().into(), ctrl, cluster).await? else {
             return Ok(None);
         };
 
@@ -16,6 +16,8 @@ use russh::{server::Handle, ChannelId, CryptoVec, Disconnect};
 use tokio::io::AsyncWrite;
 use tracing::error;
 
+use crate::recording::Recorder;
+
 make_static_metric! {
     pub struct ChannelBytesSentVec: IntCounter {
         "type" => {
@@ -38,6 +40,7 @@ lazy_static! {
 pub struct Channel {
     id: ChannelId,
     handle: Arc<Handle>,
+    recorder: Option<Recorder>,
 }
 
 impl Channel {
@@ -45,18 +48,25 @@ impl Channel {
         Self {
             id,
             handle: Arc::new(handle),
+            recorder: None,
         }
     }
+
+    /// Record everything written to the channel.
+    pub fn record(mut self, recorder: Option<Recorder>) -> Self {
+        self.recorder = recorder;
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl Writer for Channel {
     fn blocking(&self) -> impl Write {
-        SshWriter::new(self.id, self.handle.clone())
+        SshWriter::new(self.id, self.handle.clone(), self.recorder.clone())
     }
 
     fn non_blocking(&self) -> impl AsyncWrite + Send + Unpin + 'static {
-        SshWriter::new(self.id, self.handle.clone())
+        SshWriter::new(self.id, self.handle.clone(), self.recorder.clone())
     }
 
     async fn shutdown(&self, msg: String) -> Result<()> {
@@ -72,16 +82,18 @@ pub struct SshWriter {
     id: ChannelId,
     handle: Arc<Handle>,
     buf: CryptoVec,
+    recorder: Option<Recorder>,
 
     active_send: Option<BoxFuture<'static, Result<(), CryptoVec>>>,
 }
 
 impl SshWriter {
-    pub fn new(id: ChannelId, handle: Arc<Handle>) -> Self {
+    pub fn new(id: ChannelId, handle: Arc<Handle>, recorder: Option<Recorder>) -> Self {
         Self {
             id,
             handle,
             buf: CryptoVec::new(),
+            recorder,
             active_send: None,
         }
     }
@@ -99,6 +111,10 @@ impl std::io::Write for SshWriter {
         let buf = self.buf.clone();
         self.buf.clear();
 
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.output(&buf);
+        }
+
         futures::executor::block_on(async move { self.handle.data(self.id, buf).await }).map_err(
             |e| {
                 std::io::Error::new(
@@ -144,7 +160,13 @@ impl AsyncWrite for SshWriter {
                 self.active_send = None;
 
                 match result {
-                    Ok(()) => Poll::Ready(Ok(buf.len())),
+                    Ok(()) => {
+                        if let Some(recorder) = self.recorder.as_ref() {
+                            recorder.output(buf);
+                        }
+
+                        Poll::Ready(Ok(buf.len()))
+                    }
                     Err(e) => {
                         error!("error writing to channel: {:?}", e);
 
@@ -3,13 +3,16 @@
 mod broadcast;
 #[warn(dead_code)]
 mod cli;
+mod cluster;
 mod dashboard;
 mod events;
+mod exports;
 mod fx;
 mod health;
 mod identity;
 mod io;
 mod openid;
+mod recording;
 mod resources;
 mod ssh;
 mod widget;
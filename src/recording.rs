@@ -0,0 +1,351 @@
+use std::{
+    cell::RefCell,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::Instant,
+};
+
+use chrono::Utc;
+use eyre::{eyre, Result, WrapErr};
+use k8s_openapi::api::core::v1::Pod;
+use kube::ResourceExt;
+use lazy_static::lazy_static;
+use prometheus::{opts, register_int_counter_vec, IntCounterVec};
+use serde_json::{json, Map, Value};
+
+lazy_static! {
+    static ref RECORDINGS: IntCounterVec = register_int_counter_vec!(
+        opts!("recordings_total", "Total number of recordings started"),
+        &["type"],
+    )
+    .unwrap();
+    static ref RECORDING_ERRORS: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "recording_errors_total",
+            "Total number of events that could not be written to a recording"
+        ),
+        &["type"],
+    )
+    .unwrap();
+}
+
+/// Where recordings are written to. Nothing is recorded when this isn't set.
+pub static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+thread_local! {
+    // The recorder for the session a dashboard is running for. Each dashboard
+    // runs on its own thread, so this is only ever one session.
+    static CURRENT: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Use `recorder` for everything on the current thread.
+pub fn set(recorder: Option<Recorder>) {
+    CURRENT.with(|current| *current.borrow_mut() = recorder);
+}
+
+pub fn current() -> Option<Recorder> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+// Anything that isn't safe to have in a file name is replaced, user names are
+// usually email addresses and can be anything the provider allows.
+fn sanitize(part: &str) -> String {
+    part.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// Bytes written to the terminal can end in the middle of a character. Events
+// must be valid strings, so anything incomplete waits for the next write.
+#[derive(Default)]
+struct Decoder {
+    pending: Vec<u8>,
+}
+
+impl Decoder {
+    fn decode(&mut self, data: &[u8]) -> String {
+        self.pending.extend_from_slice(data);
+
+        let valid = match std::str::from_utf8(&self.pending) {
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            _ => self.pending.len(),
+        };
+
+        let rest = self.pending.split_off(valid);
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending = rest;
+
+        text
+    }
+}
+
+/// An asciinema v2 file. The header is the first line and every event after
+/// it is a line of `[elapsed, type, data]`.
+struct Cast {
+    path: PathBuf,
+    file: File,
+    start: Instant,
+    output: Decoder,
+    input: Decoder,
+}
+
+impl Cast {
+    fn create(dir: &Path, name: &[&str], header: &Value) -> Result<Self> {
+        let stem = std::iter::once(Utc::now().format("%Y%m%dT%H%M%SZ").to_string())
+            .chain(name.iter().map(|part| sanitize(part)))
+            .collect::<Vec<_>>()
+            .join("-");
+
+        // Sessions for the same user can start within the same second, files
+        // are never overwritten.
+        let mut suffix = 0;
+        let (path, file) = loop {
+            let path = if suffix == 0 {
+                dir.join(format!("{stem}.cast"))
+            } else {
+                dir.join(format!("{stem}-{suffix}.cast"))
+            };
+
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => break (path, file),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+                Err(err) => {
+                    return Err(err)
+                        .wrap_err_with(|| format!("unable to create {}", path.display()))
+                }
+            }
+        };
+
+        let mut cast = Self {
+            path,
+            file,
+            start: Instant::now(),
+            output: Decoder::default(),
+            input: Decoder::default(),
+        };
+
+        cast.line(header)?;
+
+        Ok(cast)
+    }
+
+    fn line(&mut self, value: &Value) -> io::Result<()> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+
+        // One write per event, so that nothing is lost if the server stops.
+        self.file.write_all(&line)
+    }
+
+    fn event(&mut self, kind: &str, data: &str) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+
+        self.line(&json!([elapsed, kind, data]))
+    }
+
+    fn output(&mut self, data: &[u8]) -> io::Result<()> {
+        let data = self.output.decode(data);
+
+        self.event("o", &data)
+    }
+
+    fn input(&mut self, data: &[u8]) -> io::Result<()> {
+        let data = self.input.decode(data);
+
+        self.event("i", &data)
+    }
+}
+
+fn header(width: u16, height: u16, title: &str, env: &[(&str, &str)]) -> Value {
+    json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "timestamp": Utc::now().timestamp(),
+        "title": title,
+        "env": env
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), Value::from(*v)))
+            .collect::<Map<_, _>>(),
+    })
+}
+
+fn log(kind: &str, result: io::Result<()>) {
+    if let Err(err) = result {
+        RECORDING_ERRORS.with_label_values(&[kind]).inc();
+
+        tracing::error!("unable to write to recording: {err:?}");
+    }
+}
+
+struct State {
+    dir: PathBuf,
+    user: String,
+    cluster: String,
+    term: String,
+    size: (u16, u16),
+    session: Cast,
+    // While a shell is attached, its output goes to its own recording instead
+    // of the session's.
+    exec: Option<Cast>,
+}
+
+/// Records everything shown to a user for a session, and everything that they
+/// type into shells. The session itself is one file and each shell gets a file
+/// of its own, tagged with the pod and container it was in.
+#[derive(Clone)]
+pub struct Recorder {
+    state: Arc<Mutex<State>>,
+}
+
+impl Recorder {
+    pub fn new(
+        dir: &Path,
+        user: &str,
+        cluster: &str,
+        term: &str,
+        size: (u16, u16),
+    ) -> Result<Self> {
+        let session = Cast::create(
+            dir,
+            &[user],
+            &header(
+                size.0,
+                size.1,
+                &format!("{user} on {cluster}"),
+                &[("TERM", term), ("KTY_USER", user), ("KTY_CLUSTER", cluster)],
+            ),
+        )?;
+
+        RECORDINGS.with_label_values(&["session"]).inc();
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(State {
+                dir: dir.to_path_buf(),
+                user: user.to_string(),
+                cluster: cluster.to_string(),
+                term: term.to_string(),
+                size,
+                session,
+                exec: None,
+            })),
+        })
+    }
+
+    fn with(&self, f: impl FnOnce(&mut State)) {
+        if let Ok(mut state) = self.state.lock() {
+            f(&mut state);
+        }
+    }
+
+    /// Output sent to the user's terminal.
+    pub fn output(&self, data: &[u8]) {
+        self.with(|state| match state.exec.as_mut() {
+            Some(cast) => log("exec", cast.output(data)),
+            None => log("session", state.session.output(data)),
+        });
+    }
+
+    pub fn resize(&self, width: u16, height: u16) {
+        self.with(|state| {
+            state.size = (width, height);
+
+            let size = format!("{width}x{height}");
+
+            log("session", state.session.event("r", &size));
+
+            if let Some(cast) = state.exec.as_mut() {
+                log("exec", cast.event("r", &size));
+            }
+        });
+    }
+
+    /// The dashboard moved to another cluster, shells started after this are
+    /// tagged with it.
+    pub fn cluster(&self, name: &str) {
+        self.with(|state| {
+            state.cluster = name.to_string();
+
+            log(
+                "session",
+                state.session.event("m", &format!("cluster {name}")),
+            );
+        });
+    }
+
+    /// Start recording a shell in `container`. Until the returned `Attached`
+    /// is dropped, output goes to the shell's recording.
+    pub fn attach(&self, pod: &Pod, container: &str) -> Result<Attached> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| eyre!("recording lock poisoned"))?;
+
+        let namespace = pod.namespace().unwrap_or_default();
+        let name = pod.name_any();
+
+        let cast = Cast::create(
+            &state.dir,
+            &[&state.user, &namespace, &name, container],
+            &header(
+                state.size.0,
+                state.size.1,
+                &format!("{} in {namespace}/{name}/{container}", state.user),
+                &[
+                    ("TERM", &state.term),
+                    ("KTY_USER", &state.user),
+                    ("KTY_CLUSTER", &state.cluster),
+                    ("KTY_NAMESPACE", &namespace),
+                    ("KTY_POD", &name),
+                    ("KTY_CONTAINER", container),
+                ],
+            ),
+        )?;
+
+        // The session's recording points at where the rest of it is.
+        let marker = format!("exec {}", cast.path.display());
+        log("session", state.session.event("m", &marker));
+
+        state.exec = Some(cast);
+
+        RECORDINGS.with_label_values(&["exec"]).inc();
+
+        Ok(Attached {
+            recorder: self.clone(),
+        })
+    }
+}
+
+/// A shell that is being recorded.
+pub struct Attached {
+    recorder: Recorder,
+}
+
+impl Attached {
+    /// Input sent to the shell.
+    pub fn input(&self, data: &[u8]) {
+        self.recorder.with(|state| {
+            if let Some(cast) = state.exec.as_mut() {
+                log("exec", cast.input(data));
+            }
+        });
+    }
+}
+
+impl Drop for Attached {
+    fn drop(&mut self) {
+        self.recorder.with(|state| state.exec = None);
+    }
+}
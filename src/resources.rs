@@ -1,13 +1,21 @@
+pub mod action;
 pub mod age;
+pub mod authz;
 pub mod container;
 pub mod file;
 pub mod install;
+pub mod metrics;
 pub mod node;
 pub mod pod;
+pub mod preferences;
+pub mod quantity;
+pub mod query;
 pub mod refs;
 pub mod status;
 pub mod store;
+pub mod template;
 pub mod tunnel;
+pub mod workload;
 
 use color_eyre::Section;
 use eyre::{eyre, Report, Result};
@@ -16,7 +24,12 @@ use futures::{Stream, StreamExt};
 use itertools::Itertools;
 use json_value_merge::Merge;
 use k8s_openapi::{
-    api::core::v1::ObjectReference,
+    api::{
+        authorization::v1::{
+            ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+        },
+        core::v1::ObjectReference,
+    },
     apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition,
     apimachinery::pkg::apis::meta::v1::OwnerReference,
 };
@@ -31,8 +44,9 @@ use kube::{
     CustomResourceExt, Resource,
 };
 use petgraph::Graph;
+pub use query::{Filter, Query};
 use regex::Regex;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 pub use tunnel::Tunnel;
 
 use crate::identity;
@@ -83,6 +97,25 @@ pub(crate) async fn create(
     Ok(success)
 }
 
+/// Check whether the client is allowed to do something, via a
+/// `SelfSubjectAccessReview`.
+pub async fn access(client: kube::Client, attrs: ResourceAttributes) -> Result<bool> {
+    let access = Api::<SelfSubjectAccessReview>::all(client)
+        .create(
+            &PostParams::default(),
+            &SelfSubjectAccessReview {
+                spec: SelfSubjectAccessReviewSpec {
+                    resource_attributes: Some(attrs),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(access.status.map_or(false, |status| status.allowed))
+}
+
 pub(crate) trait KubeID {
     fn kube_id(&self) -> Result<String>;
 }
@@ -130,8 +163,92 @@ where
     }
 }
 
-pub trait Filter {
-    fn matches(&self, filter: &str) -> bool;
+// Parse yaml that has been edited by a user, dropping fields that can't be
+// written (`managedFields` and `status`).
+fn prepare(raw: &str) -> Result<serde_json::Value> {
+    let mut value: serde_json::Value = serde_yaml::from_str(raw)?;
+
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| eyre!("expected an object"))?;
+    obj.remove("status");
+
+    if let Some(meta) = obj.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        meta.remove("managedFields");
+    }
+
+    Ok(value)
+}
+
+/// Server-side apply an edited `raw` yaml representation of an object as
+/// `MANAGER`. The object must keep its name and namespace, this is for editing
+/// existing objects and not creating new ones, see `create_raw` for that.
+pub async fn apply<K>(
+    client: kube::Client,
+    namespace: Option<String>,
+    name: String,
+    raw: String,
+    force: bool,
+) -> Result<K>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+{
+    let value = prepare(&raw)?;
+
+    let (edited_ns, edited_name) = {
+        let resource: K = serde_json::from_value(value.clone())?;
+
+        (resource.namespace(), resource.name_any())
+    };
+
+    if edited_name != name || edited_ns != namespace {
+        return Err(eyre!("name and namespace cannot be changed"));
+    }
+
+    // `K` could be cluster or namespace scoped, going through `DynamicObject`
+    // allows for handling both.
+    let ar = api::ApiResource::erase::<K>(&());
+    let api = match namespace {
+        Some(ns) => Api::<DynamicObject>::namespaced_with(client, &ns, &ar),
+        None => Api::<DynamicObject>::all_with(client, &ar),
+    };
+
+    let mut params = PatchParams::apply(MANAGER);
+    if force {
+        params = params.force();
+    }
+
+    api.patch(&name, &params, &api::Patch::Apply(&value))
+        .await?
+        .try_parse()
+        .map_err(Into::into)
+}
+
+/// Create a new object from `raw` yaml, as `MANAGER`. The type is looked up
+/// via discovery so that this works for anything the server knows about.
+/// Objects without a namespace are created in the client's default namespace.
+pub async fn create_raw(client: kube::Client, raw: String) -> Result<DynamicObject> {
+    let mut obj: DynamicObject = serde_json::from_value(prepare(&raw)?)?;
+
+    if obj.types.is_none() {
+        return Err(eyre!("apiVersion and kind are required"));
+    }
+
+    if obj.metadata.namespace.is_none() {
+        obj.metadata.namespace = Some(client.default_namespace().to_string());
+    }
+
+    obj.dynamic(client)
+        .await?
+        .create(
+            &PostParams {
+                field_manager: Some(MANAGER.to_string()),
+                ..Default::default()
+            },
+            &obj,
+        )
+        .await
+        .map_err(Into::into)
 }
 
 pub trait Compare {
@@ -0,0 +1,325 @@
+use std::{fmt, time::Duration};
+
+use chrono::Utc;
+use eyre::{eyre, Report, Result};
+use futures::future::join_all;
+use k8s_openapi::api::{
+    apps::v1::{Deployment, StatefulSet},
+    authorization::v1::ResourceAttributes,
+    core::v1::{Node, Pod},
+};
+use kube::{
+    api::{DeleteParams, EvictParams, ListParams, Patch, PatchParams},
+    Api, ResourceExt,
+};
+use serde_json::json;
+use tokio::time::Instant;
+
+use super::{node::NodeExt, workload::Workload, MANAGER};
+
+static RESTARTED_AT: &str = "kubectl.kubernetes.io/restartedAt";
+static MIRROR: &str = "kubernetes.io/config.mirror";
+
+static EVICTION_RETRY: Duration = Duration::from_secs(5);
+static EVICTION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Something that can be done to a resource from the dashboard.
+#[derive(Clone, Debug)]
+pub enum Action {
+    Delete { namespace: String, name: String },
+    Restart(Workload),
+    Scale { workload: Workload, replicas: i32 },
+    Cordon(String),
+    Uncordon(String),
+    Drain(String),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delete { namespace, name } => write!(f, "Delete pods/{namespace}/{name}"),
+            Self::Restart(workload) => write!(f, "Restart {workload}"),
+            Self::Scale { workload, replicas } => {
+                write!(f, "Scale {workload} (currently {replicas})")
+            }
+            Self::Cordon(name) => write!(f, "Cordon nodes/{name}"),
+            Self::Uncordon(name) => write!(f, "Uncordon nodes/{name}"),
+            Self::Drain(name) => write!(f, "Drain nodes/{name}"),
+        }
+    }
+}
+
+impl Action {
+    /// Actions for a pod. Restarting and scaling apply to the deployment or
+    /// statefulset that owns it, if there is one.
+    pub async fn pod(client: kube::Client, pod: &Pod) -> Result<Vec<Self>> {
+        let mut actions = vec![Self::Delete {
+            namespace: pod.namespace().unwrap_or_default(),
+            name: pod.name_any(),
+        }];
+
+        // Users that can only see pods can't look up what owns them, they still
+        // get to delete the pod.
+        let owner = async {
+            let Some(workload) = Workload::owner(client.clone(), pod).await? else {
+                return Ok(None);
+            };
+
+            let replicas = workload.replicas(client).await?.unwrap_or(1);
+
+            Ok::<_, Report>(Some((workload, replicas)))
+        };
+
+        match owner.await {
+            Ok(Some((workload, replicas))) => {
+                actions.push(Self::Restart(workload.clone()));
+                actions.push(Self::Scale { workload, replicas });
+            }
+            Ok(None) => {}
+            Err(err) => tracing::debug!(pod = pod.name_any(), err = ?err, "unable to find owner"),
+        }
+
+        Ok(actions)
+    }
+
+    pub fn node(node: &Node) -> Vec<Self> {
+        let name = node.name_any();
+
+        let cordon = if node.unschedulable() {
+            Self::Uncordon(name.clone())
+        } else {
+            Self::Cordon(name.clone())
+        };
+
+        vec![cordon, Self::Drain(name)]
+    }
+
+    pub fn key(&self) -> char {
+        match self {
+            Self::Delete { .. } => 'd',
+            Self::Restart(_) => 'r',
+            Self::Scale { .. } => 's',
+            Self::Cordon(_) => 'c',
+            Self::Uncordon(_) => 'u',
+            Self::Drain(_) => 'D',
+        }
+    }
+
+    /// Whether the action is destructive enough to ask before running it.
+    pub fn confirm(&self) -> bool {
+        matches!(self, Self::Delete { .. } | Self::Drain(_))
+    }
+
+    /// What the user needs to be allowed to do for this action to work.
+    pub fn requirements(&self) -> Vec<ResourceAttributes> {
+        let attrs =
+            |group: &str, resource: &str, verb: &str, namespace: Option<&str>| ResourceAttributes {
+                group: Some(group.to_string()),
+                resource: Some(resource.to_string()),
+                verb: Some(verb.to_string()),
+                namespace: namespace.map(String::from),
+                ..Default::default()
+            };
+
+        match self {
+            Self::Delete { namespace, name } => vec![ResourceAttributes {
+                name: Some(name.clone()),
+                ..attrs("", "pods", "delete", Some(namespace))
+            }],
+            Self::Restart(workload) => vec![ResourceAttributes {
+                name: workload.name().map(String::from),
+                ..attrs(
+                    "apps",
+                    workload.plural(),
+                    "patch",
+                    Some(workload.namespace()),
+                )
+            }],
+            Self::Scale { workload, .. } => vec![ResourceAttributes {
+                name: workload.name().map(String::from),
+                subresource: Some("scale".to_string()),
+                ..attrs(
+                    "apps",
+                    workload.plural(),
+                    "patch",
+                    Some(workload.namespace()),
+                )
+            }],
+            Self::Cordon(name) | Self::Uncordon(name) => vec![ResourceAttributes {
+                name: Some(name.clone()),
+                ..attrs("", "nodes", "patch", None)
+            }],
+            Self::Drain(name) => vec![
+                ResourceAttributes {
+                    name: Some(name.clone()),
+                    ..attrs("", "nodes", "patch", None)
+                },
+                ResourceAttributes {
+                    subresource: Some("eviction".to_string()),
+                    ..attrs("", "pods", "create", None)
+                },
+                // Finding what runs on the node.
+                attrs("", "pods", "list", None),
+            ],
+        }
+    }
+
+    /// Run the action, returning a description of what happened.
+    pub async fn run(&self, client: kube::Client) -> Result<String> {
+        let params = PatchParams {
+            field_manager: Some(MANAGER.to_string()),
+            ..Default::default()
+        };
+
+        match self {
+            Self::Delete { namespace, name } => {
+                Api::<Pod>::namespaced(client, namespace)
+                    .delete(name, &DeleteParams::default())
+                    .await?;
+
+                Ok(format!("Deleted pods/{namespace}/{name}"))
+            }
+            Self::Restart(workload) => {
+                let patch = Patch::Merge(json!({
+                    "spec": { "template": { "metadata": { "annotations": {
+                        RESTARTED_AT: Utc::now().to_rfc3339(),
+                    }}}}
+                }));
+
+                match workload {
+                    Workload::Deployment { namespace, name } => {
+                        Api::<Deployment>::namespaced(client, namespace)
+                            .patch(name, &params, &patch)
+                            .await?;
+                    }
+                    Workload::StatefulSet { namespace, name } => {
+                        Api::<StatefulSet>::namespaced(client, namespace)
+                            .patch(name, &params, &patch)
+                            .await?;
+                    }
+                    _ => return Err(eyre!("{workload} cannot be restarted")),
+                }
+
+                Ok(format!("Restarted {workload}"))
+            }
+            Self::Scale { workload, replicas } => {
+                let patch = Patch::Merge(json!({ "spec": { "replicas": replicas } }));
+
+                match workload {
+                    Workload::Deployment { namespace, name } => {
+                        Api::<Deployment>::namespaced(client, namespace)
+                            .patch_scale(name, &params, &patch)
+                            .await?;
+                    }
+                    Workload::StatefulSet { namespace, name } => {
+                        Api::<StatefulSet>::namespaced(client, namespace)
+                            .patch_scale(name, &params, &patch)
+                            .await?;
+                    }
+                    _ => return Err(eyre!("{workload} cannot be scaled")),
+                }
+
+                Ok(format!("Scaled {workload} to {replicas}"))
+            }
+            Self::Cordon(name) => {
+                cordon(client, name, true).await?;
+
+                Ok(format!("Cordoned nodes/{name}"))
+            }
+            Self::Uncordon(name) => {
+                cordon(client, name, false).await?;
+
+                Ok(format!("Uncordoned nodes/{name}"))
+            }
+            Self::Drain(name) => drain(client, name).await,
+        }
+    }
+
+    /// A copy of the action with a different number of replicas, for scaling.
+    pub fn with_replicas(&self, replicas: i32) -> Self {
+        match self {
+            Self::Scale { workload, .. } => Self::Scale {
+                workload: workload.clone(),
+                replicas,
+            },
+            x => x.clone(),
+        }
+    }
+}
+
+async fn cordon(client: kube::Client, name: &str, unschedulable: bool) -> Result<()> {
+    Api::<Node>::all(client)
+        .patch(
+            name,
+            &PatchParams {
+                field_manager: Some(MANAGER.to_string()),
+                ..Default::default()
+            },
+            &Patch::Merge(json!({ "spec": { "unschedulable": unschedulable } })),
+        )
+        .await?;
+
+    Ok(())
+}
+
+// Like `kubectl drain --ignore-daemonsets`, pods managed by a daemonset would
+// be immediately rescheduled and mirror pods can't be evicted.
+// Evictions that would violate a `PodDisruptionBudget` are rejected with a 429
+// until enough replicas are available elsewhere, so they're retried for a while
+// like `kubectl drain` does.
+async fn evict(client: kube::Client, pod: &Pod) -> Result<(), kube::Error> {
+    let api = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default());
+    let deadline = Instant::now() + EVICTION_TIMEOUT;
+
+    loop {
+        match api.evict(&pod.name_any(), &EvictParams::default()).await {
+            Err(kube::Error::Api(resp)) if resp.code == 429 && Instant::now() < deadline => {
+                tokio::time::sleep(EVICTION_RETRY).await;
+            }
+            result => return result.map(|_| ()),
+        }
+    }
+}
+
+async fn drain(client: kube::Client, name: &str) -> Result<String> {
+    cordon(client.clone(), name, true).await?;
+
+    let pods = Api::<Pod>::all(client.clone())
+        .list(&ListParams::default().fields(&format!("spec.nodeName={name}")))
+        .await?
+        .items
+        .into_iter()
+        .filter(|pod| {
+            !pod.annotations().contains_key(MIRROR)
+                && !pod.owner_references().iter().any(|o| o.kind == "DaemonSet")
+        })
+        .collect::<Vec<_>>();
+
+    let results = join_all(pods.iter().map(|pod| {
+        let client = client.clone();
+
+        async move {
+            evict(client, pod).await.map_err(|err| {
+                format!(
+                    "{}/{}: {err}",
+                    pod.namespace().unwrap_or_default(),
+                    pod.name_any()
+                )
+            })
+        }
+    }))
+    .await;
+
+    let failures: Vec<_> = results.into_iter().filter_map(Result::err).collect();
+
+    if !failures.is_empty() {
+        return Err(eyre!(
+            "Cordoned nodes/{name} but unable to evict {} of {} pods:\n{}",
+            failures.len(),
+            pods.len(),
+            failures.join("\n")
+        ));
+    }
+
+    Ok(format!("Drained nodes/{name}, evicted {} pods", pods.len()))
+}
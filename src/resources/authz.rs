@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use futures::future::try_join_all;
+use itertools::Itertools;
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, ResourceRule, SelfSubjectRulesReview, SelfSubjectRulesReviewSpec,
+};
+use kube::{api::PostParams, Api};
+use lazy_static::lazy_static;
+use prometheus::{opts, register_int_counter_vec, IntCounterVec};
+
+use super::access;
+
+lazy_static! {
+    static ref AUTHZ_CHECKS: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "authz_checks_total",
+            "Number of permission checks, by how they were answered",
+        ),
+        &["source"],
+    )
+    .unwrap();
+}
+
+// RBAC doesn't change often, but when it does a session shouldn't need to be
+// restarted to pick it up.
+static TTL: Duration = Duration::from_secs(60);
+
+struct Entry<T> {
+    value: T,
+    at: Instant,
+}
+
+impl<T: Clone> Entry<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            at: Instant::now(),
+        }
+    }
+
+    fn fresh(&self) -> Option<T> {
+        (self.at.elapsed() < TTL).then(|| self.value.clone())
+    }
+}
+
+#[derive(Default)]
+struct Cache {
+    rules: HashMap<String, Entry<Arc<Vec<ResourceRule>>>>,
+    reviews: HashMap<String, Entry<bool>>,
+}
+
+/// Permissions for a single session, cached so that widgets can check what
+/// they need before starting. Rules are fetched per namespace with a
+/// `SelfSubjectRulesReview`. Anything they don't allow falls back to a
+/// `SelfSubjectAccessReview`, as rules can be incomplete depending on the
+/// authorizers the cluster uses.
+#[derive(Clone)]
+pub struct Authz {
+    client: kube::Client,
+    cache: Arc<Mutex<Cache>>,
+}
+
+impl Authz {
+    pub fn new(client: kube::Client) -> Self {
+        Self {
+            client,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Whether everything in `attrs` is allowed, checked as a batch.
+    pub async fn allowed(&self, attrs: &[ResourceAttributes]) -> Result<bool> {
+        Ok(self.missing(attrs).await?.is_empty())
+    }
+
+    /// The subset of `attrs` that isn't allowed.
+    pub async fn missing(&self, attrs: &[ResourceAttributes]) -> Result<Vec<ResourceAttributes>> {
+        let namespaces = attrs
+            .iter()
+            .filter_map(|a| a.namespace.clone())
+            .unique()
+            .collect::<Vec<_>>();
+
+        let rules = try_join_all(namespaces.into_iter().map(|ns| async move {
+            let rules = self.rules(&ns).await?;
+
+            Ok::<_, eyre::Report>((ns, rules))
+        }))
+        .await?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+        let results = try_join_all(attrs.iter().map(|attrs| {
+            let rules = attrs.namespace.as_ref().and_then(|ns| rules.get(ns));
+
+            async move {
+                if rules.map_or(false, |rules| rules.iter().any(|r| matches(r, attrs))) {
+                    AUTHZ_CHECKS.with_label_values(&["rules"]).inc();
+
+                    return Ok::<_, eyre::Report>((attrs, true));
+                }
+
+                Ok((attrs, self.review(attrs).await?))
+            }
+        }))
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .filter(|(_, allowed)| !allowed)
+            .map(|(attrs, _)| attrs.clone())
+            .collect())
+    }
+
+    async fn rules(&self, namespace: &str) -> Result<Arc<Vec<ResourceRule>>> {
+        if let Some(rules) = self
+            .cache
+            .lock()
+            .unwrap()
+            .rules
+            .get(namespace)
+            .and_then(Entry::fresh)
+        {
+            return Ok(rules);
+        }
+
+        let review = Api::<SelfSubjectRulesReview>::all(self.client.clone())
+            .create(
+                &PostParams::default(),
+                &SelfSubjectRulesReview {
+                    spec: SelfSubjectRulesReviewSpec {
+                        namespace: Some(namespace.to_string()),
+                    },
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let rules = Arc::new(
+            review
+                .status
+                .map(|status| status.resource_rules)
+                .unwrap_or_default(),
+        );
+
+        self.cache
+            .lock()
+            .unwrap()
+            .rules
+            .insert(namespace.to_string(), Entry::new(rules.clone()));
+
+        Ok(rules)
+    }
+
+    async fn review(&self, attrs: &ResourceAttributes) -> Result<bool> {
+        let key = serde_json::to_string(attrs)?;
+
+        if let Some(allowed) = self
+            .cache
+            .lock()
+            .unwrap()
+            .reviews
+            .get(&key)
+            .and_then(Entry::fresh)
+        {
+            AUTHZ_CHECKS.with_label_values(&["cache"]).inc();
+
+            return Ok(allowed);
+        }
+
+        AUTHZ_CHECKS.with_label_values(&["review"]).inc();
+
+        let allowed = access(self.client.clone(), attrs.clone()).await?;
+
+        self.cache
+            .lock()
+            .unwrap()
+            .reviews
+            .insert(key, Entry::new(allowed));
+
+        Ok(allowed)
+    }
+}
+
+fn any(values: Option<&Vec<String>>, want: &str) -> bool {
+    values.map_or(false, |values| values.iter().any(|v| v == "*" || v == want))
+}
+
+fn matches(rule: &ResourceRule, attrs: &ResourceAttributes) -> bool {
+    let resource = attrs.resource.as_deref().unwrap_or_default();
+    let full = match attrs.subresource.as_deref() {
+        Some(sub) => format!("{resource}/{sub}"),
+        None => resource.to_string(),
+    };
+
+    let resources = rule.resources.as_ref().map_or(false, |resources| {
+        resources.iter().any(|r| {
+            // `pods/*` and `*/log` only cover subresources, never `pods`
+            // itself.
+            r == "*"
+                || *r == full
+                || (attrs.subresource.is_some()
+                    && (r.strip_suffix("/*") == Some(resource)
+                        || r.strip_prefix("*/") == attrs.subresource.as_deref()))
+        })
+    });
+
+    let names = match (rule.resource_names.as_ref(), attrs.name.as_ref()) {
+        (None, _) => true,
+        (Some(names), _) if names.is_empty() => true,
+        (Some(names), Some(name)) => names.contains(name),
+        (Some(_), None) => false,
+    };
+
+    rule.verbs
+        .iter()
+        .any(|v| v == "*" || Some(v.as_str()) == attrs.verb.as_deref())
+        && any(
+            rule.api_groups.as_ref(),
+            attrs.group.as_deref().unwrap_or_default(),
+        )
+        && resources
+        && names
+}
+
+/// A short description of a permission, such as `pods/log get` in namespace
+/// `default`.
+pub fn describe(attrs: &ResourceAttributes) -> String {
+    let resource = match attrs.subresource.as_deref() {
+        Some(sub) => format!("{}/{sub}", attrs.resource.as_deref().unwrap_or_default()),
+        None => attrs.resource.clone().unwrap_or_default(),
+    };
+
+    let scope = attrs
+        .namespace
+        .as_ref()
+        .map_or("cluster-wide".to_string(), |ns| {
+            format!("in namespace `{ns}`")
+        });
+
+    format!(
+        "`{resource} {}` {scope}",
+        attrs.verb.as_deref().unwrap_or_default()
+    )
+}
+
+/// Shorthand for the attributes of a request against a namespaced resource.
+/// Subresources can be included in `resource`, as in `pods/log`.
+pub fn namespaced(group: &str, resource: &str, verb: &str, namespace: &str) -> ResourceAttributes {
+    let (resource, subresource) = match resource.split_once('/') {
+        Some((resource, sub)) => (resource, Some(sub.to_string())),
+        None => (resource, None),
+    };
+
+    ResourceAttributes {
+        group: Some(group.to_string()),
+        resource: Some(resource.to_string()),
+        subresource,
+        verb: Some(verb.to_string()),
+        namespace: Some(namespace.to_string()),
+        ..Default::default()
+    }
+}
@@ -13,7 +13,11 @@ use ratatui::{
     widgets::{Cell, Row},
 };
 
-use super::{age::Age, Compare};
+use super::{
+    age::Age,
+    query::{self, Field},
+    Compare, Filter,
+};
 use crate::widget::table;
 
 #[allow(clippy::module_name_repetitions)]
@@ -196,6 +200,18 @@ impl ContainerExt for Container {
     }
 }
 
+impl Filter for Container {
+    fn field(&self, field: &Field) -> Option<String> {
+        match field {
+            Field::Name => Some(self.name_any()),
+            Field::Status => Some(self.state().to_string()),
+            Field::Node => self.pod.spec.as_ref()?.node_name.clone(),
+            // Everything else comes from the pod the container is in.
+            field => query::metadata(&self.pod, field),
+        }
+    }
+}
+
 impl table::Row for Container {
     fn header<'a>() -> Option<Row<'a>> {
         Some(Row::new(vec![
@@ -219,12 +235,13 @@ impl table::Row for Container {
         ]
     }
 
-    fn row(&self, style: &table::RowStyle) -> Row {
+    fn row(&self, style: &table::RowStyle, matches: &table::Matches) -> Row {
         Row::new(vec![
-            Cell::from(self.name_any()),
+            Cell::from(matches.cell("Name", self.name_any())),
             Cell::from(self.image()),
             Cell::from(self.ready()),
-            Cell::from(self.state().to_string()),
+            // The state is what `status:` filters on.
+            Cell::from(matches.cell("Status", self.state().to_string())),
             Cell::from(self.restarts()),
             Cell::from(self.age()),
         ])
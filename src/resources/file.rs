@@ -4,11 +4,15 @@ use std::{
 };
 
 use eyre::{eyre, Result};
-use k8s_openapi::api::core::v1::{Namespace, Pod};
+use k8s_openapi::api::{
+    authorization::v1::ResourceAttributes,
+    core::v1::{Namespace, Pod},
+};
 use kube::{api::ListParams, Api, ResourceExt};
 use russh_sftp::protocol::{self, FileAttributes, FileMode};
 
 use super::{
+    authz,
     container::{Container, ContainerExt, ContainerFiles},
     pod::PodExt,
 };
@@ -104,6 +108,39 @@ impl<'a> File<'a> {
         }
     }
 
+    /// What's needed to list or read this path. Anything inside a container
+    /// goes through `exec`.
+    pub fn requirements(&self) -> Vec<ResourceAttributes> {
+        let Some(ns) = self.namespace.as_deref() else {
+            return vec![ResourceAttributes {
+                resource: Some("namespaces".to_string()),
+                verb: Some("list".to_string()),
+                ..Default::default()
+            }];
+        };
+
+        let Some(pod) = self.pod.as_deref() else {
+            return vec![authz::namespaced("", "pods", "list", ns)];
+        };
+
+        let get = ResourceAttributes {
+            name: Some(pod.to_string()),
+            ..authz::namespaced("", "pods", "get", ns)
+        };
+
+        if self.container.is_none() {
+            return vec![get];
+        }
+
+        vec![
+            get,
+            ResourceAttributes {
+                name: Some(pod.to_string()),
+                ..authz::namespaced("", "pods/exec", "create", ns)
+            },
+        ]
+    }
+
     pub async fn list(&self, client: kube::Client) -> Result<Vec<protocol::File>> {
         match self {
             File {
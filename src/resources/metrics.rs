@@ -0,0 +1,305 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use eyre::Result;
+use k8s_openapi::{
+    apimachinery::pkg::{api::resource::Quantity, apis::meta::v1::ObjectMeta},
+    ClusterResourceScope, ListableResource, Metadata, NamespaceResourceScope, Resource,
+};
+use kube::{api::ListParams, Api, ResourceExt};
+use ringbuffer::{AllocRingBuffer, RingBuffer};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use super::quantity;
+
+// metrics-server scrapes every 15s by default, there's no reason to ask more
+// often than that.
+static INTERVAL: Duration = Duration::from_secs(15);
+// The longest to wait between attempts while metrics aren't available.
+static MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Number of samples kept for each object, about 15 minutes.
+pub static RANGE: usize = 60;
+
+#[derive(Clone, Debug, Deserialize)]
+struct ContainerMetrics {
+    usage: BTreeMap<String, Quantity>,
+}
+
+/// `metrics.k8s.io/v1beta1` isn't part of k8s-openapi, this is the subset of it
+/// that is needed.
+#[derive(Clone, Debug, Deserialize)]
+struct PodMetrics {
+    metadata: ObjectMeta,
+    containers: Vec<ContainerMetrics>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct NodeMetrics {
+    metadata: ObjectMeta,
+    usage: BTreeMap<String, Quantity>,
+}
+
+impl Resource for PodMetrics {
+    const API_VERSION: &'static str = "metrics.k8s.io/v1beta1";
+    const GROUP: &'static str = "metrics.k8s.io";
+    const KIND: &'static str = "PodMetrics";
+    const VERSION: &'static str = "v1beta1";
+    const URL_PATH_SEGMENT: &'static str = "pods";
+    type Scope = NamespaceResourceScope;
+}
+
+impl ListableResource for PodMetrics {
+    const LIST_KIND: &'static str = "PodMetricsList";
+}
+
+impl Metadata for PodMetrics {
+    type Ty = ObjectMeta;
+
+    fn metadata(&self) -> &ObjectMeta {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut ObjectMeta {
+        &mut self.metadata
+    }
+}
+
+impl Resource for NodeMetrics {
+    const API_VERSION: &'static str = "metrics.k8s.io/v1beta1";
+    const GROUP: &'static str = "metrics.k8s.io";
+    const KIND: &'static str = "NodeMetrics";
+    const VERSION: &'static str = "v1beta1";
+    const URL_PATH_SEGMENT: &'static str = "nodes";
+    type Scope = ClusterResourceScope;
+}
+
+impl ListableResource for NodeMetrics {
+    const LIST_KIND: &'static str = "NodeMetricsList";
+}
+
+impl Metadata for NodeMetrics {
+    type Ty = ObjectMeta;
+
+    fn metadata(&self) -> &ObjectMeta {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut ObjectMeta {
+        &mut self.metadata
+    }
+}
+
+/// CPU in cores and memory in bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Usage {
+    pub cpu: f64,
+    pub memory: f64,
+}
+
+impl From<&BTreeMap<String, Quantity>> for Usage {
+    fn from(usage: &BTreeMap<String, Quantity>) -> Self {
+        let get = |key| {
+            usage
+                .get(key)
+                .and_then(|q| quantity::parse(q).ok())
+                .unwrap_or_default()
+        };
+
+        Self {
+            cpu: get("cpu"),
+            memory: get("memory"),
+        }
+    }
+}
+
+impl std::ops::Add for Usage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            cpu: self.cpu + other.cpu,
+            memory: self.memory + other.memory,
+        }
+    }
+}
+
+type History = HashMap<String, AllocRingBuffer<Usage>>;
+
+#[derive(Default)]
+struct State {
+    pods_available: bool,
+    nodes_available: bool,
+    pods: History,
+    nodes: History,
+}
+
+impl State {
+    fn record(history: &mut History, samples: HashMap<String, Usage>) {
+        history.retain(|key, _| samples.contains_key(key));
+
+        for (key, usage) in samples {
+            history
+                .entry(key)
+                .or_insert_with(|| AllocRingBuffer::new(RANGE))
+                .push(usage);
+        }
+    }
+}
+
+thread_local! {
+    // Rows are drawn without any context, so they look usage up here. Each
+    // dashboard runs on its own thread, making this per session.
+    static CURRENT: RefCell<Option<Arc<Mutex<State>>>> = const { RefCell::new(None) };
+}
+
+fn with<T: Default>(f: impl FnOnce(&State) -> T) -> T {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(|state| f(&state.lock().unwrap()))
+            .unwrap_or_default()
+    })
+}
+
+/// Polls the metrics API, when it exists, for as long as this is around.
+pub struct Metrics {
+    task: JoinHandle<()>,
+}
+
+impl Metrics {
+    /// Start polling and make the results available to everything drawn on
+    /// this thread.
+    pub fn start(client: kube::Client) -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+
+        CURRENT.with(|current| current.replace(Some(state.clone())));
+
+        Self {
+            task: tokio::spawn(poll(client, state)),
+        }
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn poll(client: kube::Client, state: Arc<Mutex<State>>) {
+    let mut delay = Duration::ZERO;
+
+    loop {
+        tokio::time::sleep(delay).await;
+
+        // Pods and nodes are separate permissions, someone that can only see
+        // their own namespace still gets usage for their pods.
+        let (pods, nodes) = tokio::join!(fetch_pods(client.clone()), fetch_nodes(client.clone()));
+
+        let mut state = state.lock().unwrap();
+
+        state.pods_available = record(&mut state.pods, pods, "pod");
+        state.nodes_available = record(&mut state.nodes, nodes, "node");
+
+        // Either metrics-server isn't installed, isn't ready yet or the user
+        // can't read from it. There's nothing to show until it works, so check
+        // less often the longer that takes.
+        delay = if state.pods_available || state.nodes_available {
+            INTERVAL
+        } else {
+            (delay * 2).clamp(INTERVAL, MAX_BACKOFF)
+        };
+    }
+}
+
+fn record(history: &mut History, samples: Result<HashMap<String, Usage>>, kind: &str) -> bool {
+    match samples {
+        Ok(samples) => {
+            State::record(history, samples);
+
+            true
+        }
+        Err(err) => {
+            tracing::debug!("{kind} metrics unavailable: {err:?}");
+
+            false
+        }
+    }
+}
+
+async fn fetch_pods(client: kube::Client) -> Result<HashMap<String, Usage>> {
+    Ok(Api::<PodMetrics>::all(client)
+        .list(&ListParams::default())
+        .await?
+        .into_iter()
+        .map(|m| {
+            let usage = m
+                .containers
+                .iter()
+                .map(|c| Usage::from(&c.usage))
+                .fold(Usage::default(), |acc, u| acc + u);
+
+            (
+                pod_key(&m.namespace().unwrap_or_default(), &m.name_any()),
+                usage,
+            )
+        })
+        .collect())
+}
+
+async fn fetch_nodes(client: kube::Client) -> Result<HashMap<String, Usage>> {
+    Ok(Api::<NodeMetrics>::all(client)
+        .list(&ListParams::default())
+        .await?
+        .into_iter()
+        .map(|m| (m.name_any(), Usage::from(&m.usage)))
+        .collect())
+}
+
+fn pod_key(namespace: &str, name: &str) -> String {
+    format!("{namespace}/{name}")
+}
+
+/// Whether the metrics API has answered for pods, columns depending on it
+/// should be hidden otherwise.
+pub fn pods_available() -> bool {
+    with(|state| state.pods_available)
+}
+
+/// Whether the metrics API has answered for nodes.
+pub fn nodes_available() -> bool {
+    with(|state| state.nodes_available)
+}
+
+/// The latest usage of a pod, summed across containers.
+pub fn pod(namespace: &str, name: &str) -> Option<Usage> {
+    with(|state| {
+        state
+            .pods
+            .get(&pod_key(namespace, name))
+            .and_then(|h| h.back().copied())
+    })
+}
+
+/// Every sample for a pod, oldest first.
+pub fn pod_history(namespace: &str, name: &str) -> Vec<Usage> {
+    with(|state| {
+        state
+            .pods
+            .get(&pod_key(namespace, name))
+            .map(|h| h.iter().copied().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// The latest usage of a node.
+pub fn node(name: &str) -> Option<Usage> {
+    with(|state| state.nodes.get(name).and_then(|h| h.back().copied()))
+}
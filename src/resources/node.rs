@@ -1,17 +1,30 @@
-use std::{cmp::Ordering, str::FromStr, sync::Arc};
+pub mod shell;
+
+use std::{cmp::Ordering, collections::BTreeMap, str::FromStr, sync::Arc};
 
 use chrono::{TimeDelta, Utc};
+use eyre::{eyre, Result};
 use itertools::Itertools;
-use k8s_openapi::api::core::v1::{Node, NodeSpec};
-use kube::ResourceExt;
-use ratatui::{
-    layout::Constraint,
-    widgets::{Cell, Row},
+use k8s_openapi::{
+    api::core::v1::{self, Node, NodeSpec, Pod},
+    apimachinery::pkg::api::resource::Quantity,
 };
+use kube::{runtime::watcher, Api, ResourceExt};
+use ratatui::{layout::Constraint, style::Style};
 use strum::{Display, EnumString};
+use tokio::sync::oneshot;
 
-use super::{age::Age, Compare, Filter};
-use crate::widget::table;
+use super::{
+    age::Age,
+    metrics, pod, quantity,
+    query::{self, Field},
+    store::Store,
+    Compare, Filter,
+};
+use crate::widget::{
+    columns::{Column, Key, Order},
+    table,
+};
 
 #[derive(EnumString, Display)]
 pub enum Status {
@@ -30,10 +43,18 @@ pub trait NodeExt {
     fn instance_type(&self) -> String;
     fn roles(&self) -> Vec<String>;
     fn status(&self) -> Vec<Status>;
+    fn unschedulable(&self) -> bool;
     fn version(&self) -> String;
 }
 
 impl NodeExt for Node {
+    fn unschedulable(&self) -> bool {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.unschedulable)
+            .unwrap_or_default()
+    }
+
     fn age(&self) -> TimeDelta {
         let Some(creation) = self.creation_timestamp() else {
             return TimeDelta::zero();
@@ -121,49 +142,73 @@ impl NodeExt for Node {
 }
 
 impl table::Row for Arc<Node> {
-    fn header<'a>() -> Option<Row<'a>> {
-        Some(Row::new(vec![
-            Cell::from("Name"),
-            Cell::from("Status"),
-            Cell::from("Roles"),
-            Cell::from("Type"),
-            Cell::from("Version"),
-            Cell::from("Age"),
-        ]))
+    fn columns() -> Vec<Column> {
+        let mut columns = vec![
+            Column::new("Name", Constraint::Max(20)),
+            Column::new("Status", Constraint::Max(30)).sortable(Order::Ascending),
+            Column::new("Roles", Constraint::Fill(1)),
+            Column::new("Type", Constraint::Max(10)).sortable(Order::Ascending),
+            Column::new("Version", Constraint::Max(10)).sortable(Order::Ascending),
+            Column::new("Age", Constraint::Max(10)).sortable(Order::Ascending),
+        ];
+
+        if metrics::nodes_available() {
+            columns.extend([
+                Column::new("CPU", Constraint::Max(8)).sortable(Order::Descending),
+                Column::new("Memory", Constraint::Max(8)).sortable(Order::Descending),
+            ]);
+        }
+
+        columns
     }
 
-    fn constraints() -> Vec<Constraint> {
-        vec![
-            Constraint::Max(20),
-            Constraint::Max(30),
-            Constraint::Fill(1),
-            Constraint::Max(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-        ]
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "Name" => self.name_any(),
+            "Status" => self.status().iter().join(", "),
+            "Roles" => self.roles().join(", "),
+            "Type" => self.instance_type(),
+            "Version" => self.version(),
+            "Age" => self.age().to_age(),
+            "CPU" => pod::usage(metrics::node(&self.name_any()))[0].clone(),
+            "Memory" => pod::usage(metrics::node(&self.name_any()))[1].clone(),
+            _ => String::new(),
+        }
     }
 
-    fn row(&self, style: &table::RowStyle) -> Row {
-        let status = self.status();
-
-        Row::new(vec![
-            self.name_any(),
-            status.iter().join(", "),
-            self.roles().join(", "),
-            self.instance_type(),
-            self.version(),
-            self.age().to_age(),
-        ])
-        .style(status.iter().fold(style.normal, |acc, s| match s {
+    fn style(&self, style: &table::RowStyle) -> Style {
+        self.status().iter().fold(style.normal, |acc, s| match s {
             Status::Ready => style.healthy,
             _ => acc,
-        }))
+        })
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn key(&self, column: &str) -> Option<Key> {
+        match column {
+            "Status" => Some(Key::Text(self.status().iter().join(", "))),
+            "Type" => Some(Key::Text(self.instance_type())),
+            "Version" => Some(Key::Text(self.version())),
+            "Age" => Some(Key::Number(self.age().num_seconds() as f64)),
+            "CPU" => metrics::node(&self.name_any()).map(|usage| Key::Number(usage.cpu)),
+            "Memory" => metrics::node(&self.name_any()).map(|usage| Key::Number(usage.memory)),
+            _ => None,
+        }
+    }
+
+    fn path(&self) -> Vec<String> {
+        vec![self.name_any()]
     }
 }
 
 impl Filter for Node {
-    fn matches(&self, filter: &str) -> bool {
-        self.name_any().contains(filter)
+    fn field(&self, field: &Field) -> Option<String> {
+        match field {
+            Field::Status => Some(self.status().iter().join(", ")),
+            // Nodes are themselves, so that `node:` filters work everywhere.
+            Field::Node => Some(self.name_any()),
+            field => query::metadata(self, field),
+        }
     }
 }
 
@@ -181,3 +226,122 @@ impl Compare for Arc<Node> {
         self.name_any().cmp(&other.name_any())
     }
 }
+
+/// Watch the pods that have been scheduled on `node`.
+pub fn pods(client: kube::Client, node: &str) -> (Arc<Store<Pod>>, oneshot::Receiver<()>) {
+    Store::watch(
+        Api::all(client),
+        watcher::Config::default().fields(&format!("spec.nodeName={node}")),
+    )
+}
+
+/// How much of a resource, such as `cpu` or `memory`, pods on a node have asked
+/// for compared to what the node has available for them.
+#[derive(Default)]
+pub struct Usage {
+    pub allocatable: f64,
+    pub requests: f64,
+    pub limits: f64,
+}
+
+impl Usage {
+    pub fn new(node: &Node, pods: &[Arc<Pod>], resource: &str) -> Self {
+        let allocatable = node
+            .status
+            .as_ref()
+            .and_then(|status| status.allocatable.as_ref())
+            .and_then(|allocatable| allocatable.get(resource))
+            .and_then(|q| quantity::parse(q).ok())
+            .unwrap_or_default();
+
+        let (requests, limits) = pods
+            .iter()
+            .filter(|pod| is_active(pod))
+            .map(|pod| requested(pod, resource))
+            .fold((0.0, 0.0), |(r, l), (pr, pl)| (r + pr, l + pl));
+
+        Self {
+            allocatable,
+            requests,
+            limits,
+        }
+    }
+}
+
+/// Pods that have finished don't count against the node's resources.
+pub fn is_active(pod: &Pod) -> bool {
+    !matches!(
+        pod.status.as_ref().and_then(|s| s.phase.as_deref()),
+        Some("Succeeded" | "Failed")
+    )
+}
+
+// This is the same thing the scheduler does, the larger of all the containers
+// together or any single init container as those run one after the other.
+fn requested(pod: &Pod, resource: &str) -> (f64, f64) {
+    let Some(spec) = pod.spec.as_ref() else {
+        return (0.0, 0.0);
+    };
+
+    let get = |c: &v1::Container| {
+        let value = |map: Option<&BTreeMap<String, Quantity>>| {
+            map.and_then(|m| m.get(resource))
+                .and_then(|q| quantity::parse(q).ok())
+                .unwrap_or_default()
+        };
+
+        let resources = c.resources.as_ref();
+
+        (
+            value(resources.and_then(|r| r.requests.as_ref())),
+            value(resources.and_then(|r| r.limits.as_ref())),
+        )
+    };
+
+    let (requests, limits) = spec
+        .containers
+        .iter()
+        .map(get)
+        .fold((0.0, 0.0), |(r, l), (cr, cl)| (r + cr, l + cl));
+
+    spec.init_containers
+        .iter()
+        .flatten()
+        .map(get)
+        .fold((requests, limits), |(r, l), (ir, il)| {
+            (f64::max(r, ir), f64::max(l, il))
+        })
+}
+
+/// Fetch the most recent `tail` lines for `service`, such as `kubelet`, from a
+/// node's journal. This uses the kubelet's log query endpoint, which requires
+/// the `NodeLogQuery` feature gate and `enableSystemLogQuery` in the kubelet
+/// config.
+pub async fn logs(client: kube::Client, node: &str, service: &str, tail: i64) -> Result<String> {
+    let request = http::Request::get(format!(
+        "/api/v1/nodes/{node}/proxy/logs/?query={service}&tailLines={tail}"
+    ))
+    .body(Vec::new())?;
+
+    let body = match client.request_text(request).await {
+        Ok(body) => body,
+        Err(kube::Error::Api(resp)) if resp.code == 403 => {
+            return Err(eyre!(
+                "You need `nodes/proxy get` to read node logs: {}",
+                resp.message
+            ));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    // Without the feature gate, the kubelet ignores the query and returns the
+    // html listing of `/var/log` (or the file itself on some distributions).
+    if body.trim_start().starts_with('<') {
+        return Err(eyre!(
+            "{node} doesn't support log queries. The kubelet needs the `NodeLogQuery` feature \
+             gate and `enableSystemLogQuery: true` in its config."
+        ));
+    }
+
+    Ok(body)
+}
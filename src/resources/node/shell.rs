@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use eyre::{eyre, Report, Result};
+use k8s_openapi::api::{authorization::v1::ResourceAttributes, core::v1::Pod};
+use kube::{
+    api::{DeleteParams, PostParams},
+    runtime::wait::{await_condition, conditions::is_pod_running},
+    Api, ResourceExt,
+};
+use serde_json::json;
+
+use crate::resources::{authz, pod::debug::DEFAULT_IMAGE, MANAGER};
+
+/// Run in the pod to get into the host's mount, uts, ipc and net namespaces.
+/// Without a command, `nsenter` starts the host's `$SHELL`.
+pub static COMMAND: [&str; 7] = ["nsenter", "-t", "1", "-m", "-u", "-i", "-n"];
+
+static TIMEOUT: Duration = Duration::from_secs(120);
+
+// If the session goes away without cleaning up, the pod is stopped after this.
+// It'll still need to be deleted, but won't be privileged and running forever.
+static DEADLINE: i64 = 12 * 60 * 60;
+
+/// Permissions needed to get a shell on a node from `namespace`.
+pub fn requirements(namespace: &str) -> Vec<ResourceAttributes> {
+    vec![
+        authz::namespaced("", "pods", "create", namespace),
+        authz::namespaced("", "pods/exec", "create", namespace),
+        authz::namespaced("", "pods", "delete", namespace),
+    ]
+}
+
+/// A privileged pod, scheduled on `node`, that shares the host's PID
+/// namespace. This is what `COMMAND` needs to work and is effectively root on
+/// the node.
+pub fn pod(node: &str, namespace: &str) -> Result<Pod> {
+    Ok(serde_json::from_value(json!({
+        "apiVersion": "v1",
+        "kind": "Pod",
+        "metadata": {
+            // Every user and session shares the namespace, the server picks a
+            // name that is unique.
+            "generateName": "node-shell-",
+            "namespace": namespace,
+            "labels": {
+                "app.kubernetes.io/managed-by": MANAGER,
+            },
+        },
+        "spec": {
+            "nodeName": node,
+            "hostPID": true,
+            "hostNetwork": true,
+            "hostIPC": true,
+            "restartPolicy": "Never",
+            "activeDeadlineSeconds": DEADLINE,
+            "terminationGracePeriodSeconds": 0,
+            "tolerations": [{ "operator": "Exists" }],
+            "containers": [{
+                "name": "shell",
+                "image": DEFAULT_IMAGE,
+                "command": ["sleep", "infinity"],
+                "stdin": true,
+                "tty": true,
+                "securityContext": {
+                    "privileged": true,
+                },
+            }],
+        },
+    }))?)
+}
+
+/// A pod created by `start`. It is deleted when this is dropped, so that it
+/// doesn't outlive the session. Use `stop` to wait for that to happen.
+pub struct Running {
+    client: kube::Client,
+    pod: Option<Pod>,
+}
+
+impl Running {
+    pub fn pod(&self) -> Option<&Pod> {
+        self.pod.as_ref()
+    }
+
+    /// Remove the pod.
+    pub async fn stop(mut self) -> Result<()> {
+        let Some(pod) = self.pod.take() else {
+            return Ok(());
+        };
+
+        stop(self.client.clone(), &pod).await
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let Some(pod) = self.pod.take() else {
+            return;
+        };
+
+        let client = self.client.clone();
+
+        // This is usually dropped because the session went away, taking the
+        // dashboard's runtime with it. Anything spawned there would never run.
+        std::thread::spawn(move || {
+            let result = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(Report::from)
+                .and_then(|rt| rt.block_on(stop(client, &pod)));
+
+            if let Err(err) = result {
+                tracing::error!(pod = pod.name_any(), err = ?err, "unable to remove node shell");
+            }
+        });
+    }
+}
+
+/// Create `pod` and wait for it to be running.
+pub async fn start(client: kube::Client, pod: &Pod) -> Result<Running> {
+    let api = Api::<Pod>::namespaced(
+        client.clone(),
+        &pod.namespace()
+            .ok_or_else(|| eyre!("pods have namespaces"))?,
+    );
+
+    let created = api
+        .create(
+            &PostParams {
+                field_manager: Some(MANAGER.to_string()),
+                ..Default::default()
+            },
+            pod,
+        )
+        .await?;
+
+    let name = created.name_any();
+
+    let mut running = Running {
+        client,
+        pod: Some(created),
+    };
+
+    let result =
+        match tokio::time::timeout(TIMEOUT, await_condition(api, &name, is_pod_running())).await {
+            Ok(Ok(Some(pod))) => {
+                running.pod = Some(pod);
+
+                return Ok(running);
+            }
+            Ok(Ok(None)) => {
+                running.pod = None;
+
+                Err(eyre!("{name} was deleted before it started"))
+            }
+            Ok(Err(err)) => Err(err.into()),
+            Err(_) => Err(eyre!("timed out waiting for {name} to start")),
+        };
+
+    // Why it didn't start is more useful than any problem cleaning it up.
+    if let Err(err) = running.stop().await {
+        tracing::error!(pod = name, err = ?err, "unable to remove node shell");
+    }
+
+    result
+}
+
+async fn stop(client: kube::Client, pod: &Pod) -> Result<()> {
+    Api::<Pod>::namespaced(
+        client,
+        &pod.namespace()
+            .ok_or_else(|| eyre!("pods have namespaces"))?,
+    )
+    .delete(&pod.name_any(), &DeleteParams::default().grace_period(0))
+    .await?;
+
+    Ok(())
+}
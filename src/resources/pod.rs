@@ -1,3 +1,4 @@
+pub mod debug;
 pub mod graph;
 pub mod proc;
 
@@ -10,17 +11,19 @@ use k8s_openapi::api::core::v1::{
 };
 use kube::ResourceExt;
 pub use proc::Proc;
-use ratatui::{
-    layout::Constraint,
-    widgets::{Cell, Row},
-};
+use ratatui::{layout::Constraint, style::Style};
 
 use super::{
     age::Age,
     container::{Container, ContainerExt},
-    Compare, Filter,
+    metrics, quantity,
+    query::{self, Field},
+    Compare, Filter, Query,
+};
+use crate::widget::{
+    columns::{Column, Key, Order},
+    table,
 };
-use crate::widget::table;
 
 pub enum Phase {
     Pending,
@@ -59,9 +62,12 @@ pub trait PodExt {
     fn age(&self) -> TimeDelta;
     fn ready(&self) -> String;
     fn restarts(&self) -> String;
+    fn restart_count(&self) -> i32;
+    fn node(&self) -> Option<String>;
     fn status(&self) -> Phase;
     fn containers(&self, filter: Option<String>) -> Vec<Container>;
     fn ip(&self) -> Option<IpAddr>;
+    fn usage(&self) -> Option<metrics::Usage>;
 }
 
 impl PodExt for Pod {
@@ -98,7 +104,7 @@ impl PodExt for Pod {
             return "0".to_string();
         };
 
-        let total = containers.iter().fold(0, |a, c| a + c.restart_count);
+        let total = self.restart_count();
 
         let recent = containers
             .iter()
@@ -125,6 +131,19 @@ impl PodExt for Pod {
         format!("{total} ({})", (Utc::now() - recent).to_age())
     }
 
+    fn restart_count(&self) -> i32 {
+        self.status
+            .as_ref()
+            .and_then(|status| status.container_statuses.as_ref())
+            .map_or(0, |containers| {
+                containers.iter().map(|c| c.restart_count).sum()
+            })
+    }
+
+    fn node(&self) -> Option<String> {
+        self.spec.as_ref()?.node_name.clone()
+    }
+
     fn status(&self) -> Phase {
         let Some(status) = &self.status else {
             return Some(String::new()).borrow().into();
@@ -202,13 +221,15 @@ impl PodExt for Pod {
             }
         }
 
-        if filter.is_none() {
+        let Some(filter) = filter else {
             return containers;
-        }
+        };
+
+        let query = Query::parse(&filter);
 
         containers
             .into_iter()
-            .filter(|c| filter.as_ref().map_or(true, |f| c.name_any().contains(f)))
+            .filter(|c| query.matches(c))
             .collect()
     }
 
@@ -223,51 +244,93 @@ impl PodExt for Pod {
 
         pod_ip.parse().ok()
     }
+
+    fn usage(&self) -> Option<metrics::Usage> {
+        metrics::pod(&self.namespace().unwrap_or_default(), &self.name_any())
+    }
 }
 
 impl table::Row for Arc<Pod> {
-    fn header<'a>() -> Option<Row<'a>> {
-        Some(Row::new(vec![
-            Cell::from("Namespace"),
-            Cell::from("Name"),
-            Cell::from("Ready"),
-            Cell::from("Status"),
-            Cell::from("Restarts"),
-            Cell::from("Age"),
-        ]))
+    fn columns() -> Vec<Column> {
+        let mut columns = vec![
+            Column::new("Namespace", Constraint::Max(20)),
+            Column::new("Name", Constraint::Min(10)),
+            Column::new("Ready", Constraint::Max(10)),
+            Column::new("Status", Constraint::Max(10)).sortable(Order::Ascending),
+            Column::new("Restarts", Constraint::Max(10)).sortable(Order::Descending),
+            Column::new("Age", Constraint::Max(10)).sortable(Order::Ascending),
+            Column::new("Node", Constraint::Max(20))
+                .hidden()
+                .sortable(Order::Ascending),
+            Column::new("IP", Constraint::Max(16)).hidden(),
+        ];
+
+        if metrics::pods_available() {
+            columns.extend([
+                Column::new("CPU", Constraint::Max(8)).sortable(Order::Descending),
+                Column::new("Memory", Constraint::Max(8)).sortable(Order::Descending),
+            ]);
+        }
+
+        columns
     }
 
-    fn constraints() -> Vec<Constraint> {
-        vec![
-            Constraint::Max(20),
-            Constraint::Min(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-        ]
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "Namespace" => self.namespace().unwrap_or_default(),
+            "Name" => self.name_any(),
+            "Ready" => self.ready(),
+            "Status" => self.status().to_string(),
+            "Restarts" => self.restarts(),
+            "Age" => self.age().to_age(),
+            "Node" => self.node().unwrap_or_default(),
+            "IP" => self.ip().map(|ip| ip.to_string()).unwrap_or_default(),
+            "CPU" => usage(self.usage())[0].clone(),
+            "Memory" => usage(self.usage())[1].clone(),
+            _ => String::new(),
+        }
     }
 
-    fn row(&self, style: &table::RowStyle) -> Row {
-        Row::new(vec![
-            self.namespace().unwrap_or_default(),
-            self.name_any(),
-            self.ready(),
-            self.status().to_string(),
-            self.restarts(),
-            self.age().to_age(),
-        ])
-        .style(match self.status() {
+    fn style(&self, style: &table::RowStyle) -> Style {
+        match self.status() {
             Phase::Pending | Phase::Running => style.normal,
             Phase::Succeeded => style.healthy,
             Phase::Unknown(_) => style.unhealthy,
-        })
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn key(&self, column: &str) -> Option<Key> {
+        match column {
+            "Status" => Some(Key::Text(self.status().to_string())),
+            "Restarts" => Some(Key::Number(f64::from(self.restart_count()))),
+            "Age" => Some(Key::Number(self.age().num_seconds() as f64)),
+            "Node" => self.node().map(Key::Text),
+            "CPU" => self.usage().map(|usage| Key::Number(usage.cpu)),
+            "Memory" => self.usage().map(|usage| Key::Number(usage.memory)),
+            _ => None,
+        }
+    }
+
+    fn path(&self) -> Vec<String> {
+        vec![self.namespace().unwrap_or_default(), self.name_any()]
     }
 }
 
+/// CPU and memory cells for a table, empty until there's a sample.
+pub fn usage(usage: Option<metrics::Usage>) -> [String; 2] {
+    usage.map_or([String::new(), String::new()], |usage| {
+        [quantity::cpu(usage.cpu), quantity::memory(usage.memory)]
+    })
+}
+
 impl Filter for Pod {
-    fn matches(&self, filter: &str) -> bool {
-        self.name_any().contains(filter)
+    fn field(&self, field: &Field) -> Option<String> {
+        match field {
+            Field::Status => Some(self.status().to_string()),
+            Field::Node => self.node(),
+            field => query::metadata(self, field),
+        }
     }
 }
 
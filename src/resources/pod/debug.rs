@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Patch, PatchParams},
+    runtime::wait::await_condition,
+    Api, ResourceExt,
+};
+use rand::{seq::SliceRandom, thread_rng};
+use serde_json::json;
+
+use crate::resources::MANAGER;
+
+/// Image used for debug containers unless something else is picked.
+pub static DEFAULT_IMAGE: &str = "busybox";
+
+// Pulling an image can take a while, this is mostly a guard against something
+// like an image that doesn't exist.
+static TIMEOUT: Duration = Duration::from_secs(120);
+
+// The same alphabet Kubernetes uses for generated names, no vowels so that
+// nothing unfortunate gets spelled out.
+static ALPHABET: &[u8] = b"bcdfghjklmnpqrstvwxz2456789";
+
+// Ephemeral containers can't be removed, so the names need to be unique for
+// the lifetime of the pod. Multiple users can be debugging the same pod at the
+// same time.
+fn name() -> String {
+    let mut rng = thread_rng();
+
+    let suffix: String = (0..5)
+        .filter_map(|_| ALPHABET.choose(&mut rng).copied().map(char::from))
+        .collect();
+
+    format!("debug-{suffix}")
+}
+
+/// Add an ephemeral container to `pod`, sharing the process namespace of
+/// `target`, and wait for it to start. This is the same thing that `kubectl
+/// debug` does and is useful for containers that don't have a shell. Returns
+/// the name of the new container.
+pub async fn ephemeral(
+    client: kube::Client,
+    pod: &Pod,
+    target: &str,
+    image: &str,
+) -> Result<String> {
+    let api = Api::<Pod>::namespaced(
+        client,
+        &pod.namespace()
+            .ok_or_else(|| eyre!("pods have namespaces"))?,
+    );
+
+    let name = name();
+
+    api.patch_ephemeral_containers(
+        &pod.name_any(),
+        &PatchParams {
+            field_manager: Some(MANAGER.to_string()),
+            ..Default::default()
+        },
+        &Patch::Strategic(json!({
+            "spec": {
+                "ephemeralContainers": [{
+                    "name": name,
+                    "image": image,
+                    "targetContainerName": target,
+                    "stdin": true,
+                    "tty": true,
+                }],
+            },
+        })),
+    )
+    .await?;
+
+    let started = {
+        let name = name.clone();
+
+        move |pod: Option<&Pod>| {
+            pod.and_then(|pod| pod.status.as_ref())
+                .and_then(|status| status.ephemeral_container_statuses.as_ref())
+                .and_then(|statuses| statuses.iter().find(|s| s.name == name))
+                .and_then(|status| status.state.as_ref())
+                .map_or(false, |state| {
+                    state.running.is_some() || state.terminated.is_some()
+                })
+        }
+    };
+
+    let pod = tokio::time::timeout(TIMEOUT, await_condition(api, &pod.name_any(), started))
+        .await
+        .map_err(|_| eyre!("timed out waiting for {name} to start"))??;
+
+    let terminated = pod
+        .as_ref()
+        .and_then(|pod| pod.status.as_ref())
+        .and_then(|status| status.ephemeral_container_statuses.as_ref())
+        .and_then(|statuses| statuses.iter().find(|s| s.name == name))
+        .and_then(|status| status.state.as_ref())
+        .and_then(|state| state.terminated.as_ref());
+
+    if let Some(terminated) = terminated {
+        return Err(eyre!(
+            "{name} exited with {}: {}",
+            terminated.exit_code,
+            terminated.message.clone().unwrap_or_default()
+        ));
+    }
+
+    Ok(name)
+}
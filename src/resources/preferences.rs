@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use eyre::{Result, WrapErr};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{Api, ResourceExt};
+use serde::Deserialize;
+
+use crate::{
+    identity::Key,
+    widget::{
+        keymap::{self, Action, Keymap},
+        theme::{self, Theme},
+    },
+};
+
+// Annotations on a user's `Key` with this prefix override what the server's
+// `ConfigMap` has, such as `kty.dev/keymap: vim`.
+static ANNOTATION_PREFIX: &str = "kty.dev/";
+
+// Both the `ConfigMap` and annotations only have strings, `bindings` is itself
+// yaml, such as `{down: [j, ctrl-n]}`.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Raw {
+    keymap: keymap::Preset,
+    theme: theme::Preset,
+    bindings: Option<String>,
+}
+
+/// The keymap and theme for a session.
+#[derive(Clone, Debug, Default)]
+pub struct Preferences {
+    pub keymap: Keymap,
+    pub theme: Theme,
+}
+
+impl Preferences {
+    /// Parse `keymap`, `theme` and `bindings` out of string data. Unknown keys
+    /// are ignored, so that the same `ConfigMap` can hold other things.
+    pub fn parse(data: BTreeMap<String, String>) -> Result<Self> {
+        let raw: Raw = serde_json::from_value(serde_json::to_value(data)?)?;
+
+        let mut keymap = Keymap::preset(raw.keymap);
+
+        if let Some(bindings) = raw.bindings {
+            let bindings: BTreeMap<Action, Vec<String>> =
+                serde_yaml::from_str(&bindings).wrap_err("invalid bindings")?;
+
+            for (action, keys) in bindings {
+                keymap.bind(action, &keys)?;
+            }
+        }
+
+        Ok(Self {
+            keymap,
+            theme: raw.theme.into(),
+        })
+    }
+
+    /// Load the server's defaults from `config_map` and the user's overrides
+    /// from the annotations on their `Key`. Both live in the client's default
+    /// namespace.
+    pub async fn load(
+        client: kube::Client,
+        config_map: Option<&str>,
+        key: Option<&str>,
+    ) -> Result<Self> {
+        let mut data = BTreeMap::new();
+
+        if let Some(name) = config_map {
+            if let Some(cm) = Api::<ConfigMap>::default_namespaced(client.clone())
+                .get_opt(name)
+                .await?
+            {
+                data.extend(cm.data.unwrap_or_default());
+            }
+        }
+
+        if let Some(name) = key {
+            if let Some(key) = Api::<Key>::default_namespaced(client).get_opt(name).await? {
+                data.extend(key.annotations().iter().filter_map(|(k, v)| {
+                    k.strip_prefix(ANNOTATION_PREFIX)
+                        .map(|k| (k.to_string(), v.clone()))
+                }));
+            }
+        }
+
+        Self::parse(data)
+    }
+
+    /// Use these for everything on the current thread.
+    pub fn apply(self) {
+        keymap::set(self.keymap);
+        theme::set(self.theme);
+    }
+}
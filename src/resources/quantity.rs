@@ -0,0 +1,59 @@
+use eyre::{eyre, Result};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+static BINARY: [(&str, f64); 6] = [
+    ("Ki", 1024.0),
+    ("Mi", 1_048_576.0),
+    ("Gi", 1_073_741_824.0),
+    ("Ti", 1_099_511_627_776.0),
+    ("Pi", 1_125_899_906_842_624.0),
+    ("Ei", 1_152_921_504_606_846_976.0),
+];
+
+static DECIMAL: [(&str, f64); 9] = [
+    ("n", 1e-9),
+    ("u", 1e-6),
+    ("m", 1e-3),
+    ("k", 1e3),
+    ("M", 1e6),
+    ("G", 1e9),
+    ("T", 1e12),
+    ("P", 1e15),
+    ("E", 1e18),
+];
+
+/// The value of a quantity in base units, cores for CPU and bytes for memory.
+/// Precision isn't important for showing usage, so this doesn't bother with
+/// the arbitrary precision that the API server uses.
+pub fn parse(quantity: &Quantity) -> Result<f64> {
+    let value = quantity.0.trim();
+
+    let (number, scale) = BINARY
+        .iter()
+        .chain(DECIMAL.iter())
+        .find_map(|(suffix, scale)| value.strip_suffix(suffix).map(|n| (n, *scale)))
+        .unwrap_or((value, 1.0));
+
+    number
+        .parse::<f64>()
+        .map(|n| n * scale)
+        .map_err(|_| eyre!("invalid quantity: {value}"))
+}
+
+/// Show cores the way they're usually written, `250m` or `2`.
+pub fn cpu(cores: f64) -> String {
+    if cores < 1.0 {
+        format!("{:.0}m", cores * 1000.0)
+    } else {
+        format!("{}", (cores * 100.0).round() / 100.0)
+    }
+}
+
+/// Show bytes with the largest binary suffix that fits, `512Mi` or `1.5Gi`.
+pub fn memory(bytes: f64) -> String {
+    let Some((suffix, scale)) = BINARY.iter().rev().find(|(_, scale)| bytes >= *scale) else {
+        return format!("{bytes:.0}");
+    };
+
+    format!("{}{suffix}", ((bytes / scale) * 10.0).round() / 10.0)
+}
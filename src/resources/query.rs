@@ -0,0 +1,285 @@
+/// Something that objects can be filtered on. Everything other than the name
+/// is picked with a prefix, such as `ns:kube-system`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Namespace,
+    Status,
+    Node,
+    Label(String),
+}
+
+impl Field {
+    /// The column that matches of this field are highlighted in.
+    pub fn column(&self) -> Option<&'static str> {
+        match self {
+            Field::Name => Some("Name"),
+            Field::Namespace => Some("Namespace"),
+            Field::Status => Some("Status"),
+            Field::Node => Some("Node"),
+            Field::Label(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Term {
+    field: Field,
+    // Labels can be filtered on without a value, `l:app` matches anything that
+    // has the label at all.
+    value: Option<String>,
+    negated: bool,
+}
+
+impl Term {
+    fn parse(token: &str) -> Self {
+        let (field, rest) = match token.split_once(':') {
+            Some(("name", rest)) => (Some(Field::Name), rest),
+            Some(("ns" | "namespace", rest)) => (Some(Field::Namespace), rest),
+            Some(("status", rest)) => (Some(Field::Status), rest),
+            Some(("node", rest)) => (Some(Field::Node), rest),
+            Some(("l" | "label", rest)) => (None, rest),
+            // Anything else is part of a name, names can't have colons in them
+            // but other things filtered on, such as templates, might.
+            _ => (Some(Field::Name), token),
+        };
+
+        let (negated, rest) = match rest.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+
+        let (field, value) = match field {
+            Some(field) => (field, Some(rest.to_string())),
+            None => match rest.split_once('=') {
+                Some((key, value)) => (Field::Label(key.to_string()), Some(value.to_string())),
+                None => (Field::Label(rest.to_string()), None),
+            },
+        };
+
+        Self {
+            field,
+            value,
+            negated,
+        }
+    }
+
+    fn matches(&self, obj: &impl Filter) -> bool {
+        let found = obj.field(&self.field);
+
+        let matched = match (&self.value, found) {
+            (None, found) => found.is_some(),
+            (Some(_), None) => false,
+            (Some(value), Some(found)) => {
+                // Names are searched for, everything else has to be exact.
+                if self.field == Field::Name {
+                    smart_case(&found, value).contains(value.as_str())
+                } else {
+                    smart_case(&found, value) == *value
+                }
+            }
+        };
+
+        matched != self.negated
+    }
+}
+
+/// Values without any upper case letters match regardless of case, like `rg
+/// --smart-case`. Only ASCII is folded, so that offsets into the result are
+/// offsets into `found` as well.
+pub fn smart_case(found: &str, value: &str) -> String {
+    if value.chars().any(char::is_uppercase) {
+        found.to_string()
+    } else {
+        found.to_ascii_lowercase()
+    }
+}
+
+/// A parsed filter, such as `web ns:default status:!Running l:app=web`. Terms
+/// are separated by whitespace and objects have to match all of them. Terms
+/// without a prefix search the name and a leading `!` negates a term.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    pub fn parse(filter: &str) -> Self {
+        Self {
+            terms: filter.split_whitespace().map(Term::parse).collect(),
+        }
+    }
+
+    pub fn matches(&self, obj: &impl Filter) -> bool {
+        self.terms.iter().all(|term| term.matches(obj))
+    }
+
+    /// What to highlight in `column`. Negated terms don't match anything that
+    /// is shown, so they're left out.
+    pub fn highlights(&self, column: &str) -> Vec<&str> {
+        self.terms
+            .iter()
+            .filter(|term| !term.negated && term.field.column() == Some(column))
+            .filter_map(|term| term.value.as_deref())
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+}
+
+/// Objects that can be filtered with a `Query`.
+pub trait Filter {
+    /// The value of `field` for this object. Objects without a value never
+    /// match terms for that field, unless they're negated.
+    fn field(&self, field: &Field) -> Option<String>;
+}
+
+/// Name, namespace and labels, which every resource has in its metadata.
+pub fn metadata<K: kube::ResourceExt>(obj: &K, field: &Field) -> Option<String> {
+    match field {
+        Field::Name => Some(obj.name_any()),
+        Field::Namespace => obj.namespace(),
+        Field::Label(key) => obj.labels().get(key).cloned(),
+        Field::Status | Field::Node => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Object {
+        name: String,
+        namespace: Option<String>,
+        status: Option<String>,
+        labels: BTreeMap<String, String>,
+    }
+
+    impl Filter for Object {
+        fn field(&self, field: &Field) -> Option<String> {
+            match field {
+                Field::Name => Some(self.name.clone()),
+                Field::Namespace => self.namespace.clone(),
+                Field::Status => self.status.clone(),
+                Field::Node => None,
+                Field::Label(key) => self.labels.get(key).cloned(),
+            }
+        }
+    }
+
+    fn term(field: Field, value: Option<&str>, negated: bool) -> Term {
+        Term {
+            field,
+            value: value.map(ToString::to_string),
+            negated,
+        }
+    }
+
+    fn web() -> Object {
+        Object {
+            name: "web-7d4b9".to_string(),
+            namespace: Some("default".to_string()),
+            status: Some("Running".to_string()),
+            labels: BTreeMap::from([("app".to_string(), "web".to_string())]),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Query::parse("web ns:default status:!Running l:app=web label:tier node:n1").terms,
+            vec![
+                term(Field::Name, Some("web"), false),
+                term(Field::Namespace, Some("default"), false),
+                term(Field::Status, Some("Running"), true),
+                term(Field::Label("app".to_string()), Some("web"), false),
+                term(Field::Label("tier".to_string()), None, false),
+                term(Field::Node, Some("n1"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_whitespace() {
+        assert_eq!(Query::parse("").terms, Vec::new());
+        assert_eq!(Query::parse("  \t ").terms, Vec::new());
+        assert_eq!(
+            Query::parse("  a   namespace:b ").terms,
+            vec![
+                term(Field::Name, Some("a"), false),
+                term(Field::Namespace, Some("b"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unknown_prefix() {
+        // Only known prefixes pick a field, anything else is searched for in
+        // the name as is.
+        assert_eq!(
+            Query::parse("Deployment:apps name:!x:y").terms,
+            vec![
+                term(Field::Name, Some("Deployment:apps"), false),
+                term(Field::Name, Some("x:y"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_negated_label() {
+        assert_eq!(
+            Query::parse("l:!app l:!app=web").terms,
+            vec![
+                term(Field::Label("app".to_string()), None, true),
+                term(Field::Label("app".to_string()), Some("web"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn matches() {
+        let obj = web();
+
+        for (filter, expected) in [
+            ("", true),
+            ("web", true),
+            ("7D4", false),
+            ("WEB", false),
+            ("ns:default", true),
+            ("ns:def", false),
+            ("ns:!default", false),
+            ("status:running", true),
+            ("status:Running", true),
+            ("status:!Pending", true),
+            ("l:app", true),
+            ("l:app=web", true),
+            ("l:app=api", false),
+            ("l:!tier", true),
+            ("node:n1", false),
+            ("node:!n1", true),
+            ("web ns:default l:app=web", true),
+            ("web ns:kube-system", false),
+        ] {
+            assert_eq!(Query::parse(filter).matches(&obj), expected, "{filter}");
+        }
+    }
+
+    #[test]
+    fn highlights() {
+        let query = Query::parse("web b ns:default status:!Running l:app=web name:");
+
+        assert_eq!(query.highlights("Name"), vec!["web", "b"]);
+        assert_eq!(query.highlights("Namespace"), vec!["default"]);
+        assert_eq!(query.highlights("Status"), Vec::<&str>::new());
+        assert_eq!(query.highlights("Age"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn smart_case_offsets() {
+        assert_eq!(smart_case("Web-Ä", "web"), "web-Ä");
+        assert_eq!(smart_case("Web-Ä", "Web"), "Web-Ä");
+        assert_eq!(smart_case("Web-Ä", "web").len(), "Web-Ä".len());
+    }
+}
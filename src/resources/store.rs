@@ -9,7 +9,7 @@ use kube::{
 use serde::de::DeserializeOwned;
 use tokio::{sync::oneshot, task::JoinSet};
 
-use super::{Compare, Filter};
+use super::{Compare, Filter, Query};
 use crate::widget::table;
 
 async fn is_ready<K>(reader: reflector::Store<K>, tx: oneshot::Sender<()>) -> Result<()>
@@ -58,8 +58,14 @@ where
     // TODO: need to have a way to filter stuff out (with some defaults) to keep
     // from memory going nuts.
     pub fn new(client: kube::Client) -> (Arc<Self>, oneshot::Receiver<()>) {
+        Self::watch(Api::all(client), Config::default())
+    }
+
+    /// Only keep the objects from `api` that match `config`, for example the
+    /// pods with a specific label in a single namespace.
+    pub fn watch(api: Api<K>, config: Config) -> (Arc<Self>, oneshot::Receiver<()>) {
         let (reader, writer) = reflector::store();
-        let stream = runtime::watcher(Api::<K>::all(client), Config::default())
+        let stream = runtime::watcher(api, config)
             .default_backoff()
             .modify(|obj| {
                 ResourceExt::managed_fields_mut(obj).clear();
@@ -85,10 +91,12 @@ where
     pub fn items(&self, filter: Option<String>) -> Vec<Arc<K>> {
         let mut items = filter
             .map(|filter| {
+                let query = Query::parse(&filter);
+
                 self.reader
                     .state()
                     .into_iter()
-                    .filter(|obj| obj.matches(filter.as_str()))
+                    .filter(|obj| query.matches(obj.as_ref()))
                     .collect()
             })
             .unwrap_or(self.reader.state());
@@ -101,6 +109,12 @@ where
     pub fn get(&self, idx: usize, filter: Option<String>) -> Option<Arc<K>> {
         self.items(filter).get(idx).cloned()
     }
+
+    pub async fn wait_until_ready(&self) -> Result<()> {
+        self.reader.wait_until_ready().await?;
+
+        Ok(())
+    }
 }
 
 impl<K> Drop for Store<K>
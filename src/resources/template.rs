@@ -0,0 +1,243 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use eyre::{eyre, Result};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+    CustomResourceDefinition, JSONSchemaProps,
+};
+use kube::ResourceExt;
+use ratatui::{
+    layout::Constraint,
+    widgets::{Cell, Row},
+};
+use serde_json::{json, Map, Value};
+
+use super::{query::Field, Filter, Query};
+use crate::widget::table;
+
+// Past this, only empty objects are generated. Schemas can be recursive and
+// there isn't much value in a huge skeleton.
+static MAX_DEPTH: usize = 5;
+
+/// Starting points for creating new resources from the dashboard.
+#[derive(Clone)]
+pub enum Template {
+    DebugPod,
+    Job,
+    ConfigMap,
+    Secret,
+    Custom(Arc<CustomResourceDefinition>),
+}
+
+impl Template {
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::DebugPod, Self::Job, Self::ConfigMap, Self::Secret]
+    }
+
+    pub fn kind(&self) -> String {
+        match self {
+            Self::DebugPod => "Pod".to_string(),
+            Self::Job => "Job".to_string(),
+            Self::ConfigMap => "ConfigMap".to_string(),
+            Self::Secret => "Secret".to_string(),
+            Self::Custom(crd) => crd.spec.names.kind.clone(),
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Self::DebugPod => "A busybox pod to poke around with".to_string(),
+            Self::Job => "Run a command to completion".to_string(),
+            Self::ConfigMap => "Key/value configuration".to_string(),
+            Self::Secret => "Key/value configuration, kept secret".to_string(),
+            Self::Custom(crd) => crd.name_any(),
+        }
+    }
+
+    /// Yaml for a new object, ready to be edited and then created in
+    /// `namespace`.
+    pub fn render(&self, namespace: &str) -> Result<String> {
+        let value = match self {
+            Self::DebugPod => json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": { "generateName": "debug-", "namespace": namespace },
+                "spec": {
+                    "containers": [{
+                        "name": "debug",
+                        "image": "busybox",
+                        "command": ["sleep", "infinity"],
+                        "stdin": true,
+                        "tty": true,
+                    }],
+                    "restartPolicy": "Never",
+                    "terminationGracePeriodSeconds": 0,
+                },
+            }),
+            Self::Job => json!({
+                "apiVersion": "batch/v1",
+                "kind": "Job",
+                "metadata": { "generateName": "job-", "namespace": namespace },
+                "spec": {
+                    "backoffLimit": 0,
+                    "template": {
+                        "spec": {
+                            "containers": [{
+                                "name": "job",
+                                "image": "busybox",
+                                "command": ["sh", "-c", "echo hello"],
+                            }],
+                            "restartPolicy": "Never",
+                        },
+                    },
+                },
+            }),
+            Self::ConfigMap => json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": { "name": "my-config", "namespace": namespace },
+                "data": { "key": "value" },
+            }),
+            Self::Secret => json!({
+                "apiVersion": "v1",
+                "kind": "Secret",
+                "metadata": { "name": "my-secret", "namespace": namespace },
+                "type": "Opaque",
+                "stringData": { "key": "value" },
+            }),
+            Self::Custom(crd) => custom(crd, namespace)?,
+        };
+
+        serde_yaml::to_string(&value).map_err(Into::into)
+    }
+}
+
+// Generate a skeleton object from the CRD's OpenAPI schema, using the storage
+// version.
+fn custom(crd: &CustomResourceDefinition, namespace: &str) -> Result<Value> {
+    let version = crd
+        .spec
+        .versions
+        .iter()
+        .find(|v| v.storage)
+        .or_else(|| crd.spec.versions.iter().find(|v| v.served))
+        .ok_or_else(|| eyre!("{} has no served versions", crd.name_any()))?;
+
+    let api_version = format!("{}/{}", crd.spec.group, version.name);
+
+    let mut metadata = Map::new();
+    metadata.insert(
+        "name".to_string(),
+        format!(
+            "my-{}",
+            crd.spec.names.singular.as_deref().unwrap_or("resource")
+        )
+        .into(),
+    );
+
+    if crd.spec.scope == "Namespaced" {
+        metadata.insert("namespace".to_string(), namespace.into());
+    }
+
+    let mut obj = Map::new();
+    obj.insert("apiVersion".to_string(), api_version.into());
+    obj.insert("kind".to_string(), crd.spec.names.kind.clone().into());
+    obj.insert("metadata".to_string(), metadata.into());
+
+    let properties = version
+        .schema
+        .as_ref()
+        .and_then(|s| s.open_api_v3_schema.as_ref())
+        .and_then(|s| s.properties.as_ref());
+
+    for (key, schema) in properties.into_iter().flatten() {
+        if matches!(key.as_str(), "apiVersion" | "kind" | "metadata" | "status") {
+            continue;
+        }
+
+        obj.insert(key.clone(), skeleton(schema, 0));
+    }
+
+    Ok(obj.into())
+}
+
+// Every property is included at the top level so that what's available can be
+// discovered. Below that, only required properties are included.
+fn skeleton(schema: &JSONSchemaProps, depth: usize) -> Value {
+    if let Some(default) = schema.default.as_ref() {
+        return default.0.clone();
+    }
+
+    if let Some(first) = schema.enum_.as_ref().and_then(|e| e.first()) {
+        return first.0.clone();
+    }
+
+    match schema.type_.as_deref() {
+        Some("object") => {
+            let Some(properties) = schema.properties.as_ref() else {
+                return Value::Object(Map::new());
+            };
+
+            if depth >= MAX_DEPTH {
+                return Value::Object(Map::new());
+            }
+
+            let required = schema.required.clone().unwrap_or_default();
+
+            properties
+                .iter()
+                .filter(|(key, _)| depth == 0 || required.contains(key))
+                .map(|(key, prop)| (key.clone(), skeleton(prop, depth + 1)))
+                .collect::<Map<_, _>>()
+                .into()
+        }
+        Some("array") => Value::Array(Vec::new()),
+        Some("integer" | "number") => 0.into(),
+        Some("boolean") => false.into(),
+        _ => Value::String(String::new()),
+    }
+}
+
+impl table::Row for Template {
+    fn header<'a>() -> Option<Row<'a>> {
+        Some(Row::new(vec![
+            Cell::from("Kind"),
+            Cell::from("Description"),
+        ]))
+    }
+
+    fn constraints() -> Vec<Constraint> {
+        vec![Constraint::Max(30), Constraint::Fill(0)]
+    }
+
+    fn row(&self, style: &table::RowStyle, matches: &table::Matches) -> Row {
+        Row::new(vec![
+            // Templates are filtered by their kind.
+            Cell::from(matches.cell("Name", self.kind())),
+            Cell::from(self.description()),
+        ])
+        .style(style.normal)
+    }
+}
+
+impl Filter for Template {
+    fn field(&self, field: &Field) -> Option<String> {
+        match field {
+            Field::Name => Some(self.kind()),
+            _ => None,
+        }
+    }
+}
+
+impl table::Items for Rc<RefCell<Vec<Template>>> {
+    type Item = Template;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let query = filter.as_deref().map(Query::parse);
+
+        self.borrow()
+            .iter()
+            .filter(|t| query.as_ref().map_or(true, |query| query.matches(*t)))
+            .cloned()
+            .collect()
+    }
+}
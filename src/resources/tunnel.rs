@@ -162,7 +162,7 @@ impl table::Row for Tunnel {
         ]
     }
 
-    fn row(&self, style: &table::RowStyle) -> Row {
+    fn row(&self, style: &table::RowStyle, _: &table::Matches) -> Row {
         Row::new(vec![
             self.kind.to_string().to_lowercase(),
             format!("{}:{}", self.host, self.port),
@@ -2,14 +2,15 @@ use std::time::Duration;
 
 use eyre::{eyre, Result};
 use k8s_openapi::api::{
-    authorization::v1::{ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec},
+    authorization::v1::ResourceAttributes,
     core::v1::{Node, Pod, Service},
 };
-use kube::{api::PostParams, core::ErrorResponse, Api, Resource};
+use kube::{core::ErrorResponse, Api, Resource};
 use russh::server::{self};
 use tokio::net::TcpStream;
 
 use super::{stream, StreamMetrics};
+use crate::resources::access;
 
 static CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 
@@ -132,23 +133,6 @@ impl Host {
     }
 }
 
-async fn access(client: kube::Client, attrs: ResourceAttributes) -> Result<bool> {
-    let access = Api::<SelfSubjectAccessReview>::all(client)
-        .create(
-            &PostParams::default(),
-            &SelfSubjectAccessReview {
-                spec: SelfSubjectAccessReviewSpec {
-                    resource_attributes: Some(attrs),
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-        )
-        .await?;
-
-    Ok(access.status.map_or(false, |status| status.allowed))
-}
-
 trait Proxy {
     async fn get_host(client: kube::Client, segments: &[String]) -> Result<String>;
 }
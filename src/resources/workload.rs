@@ -0,0 +1,219 @@
+use std::{fmt, str::FromStr};
+
+use eyre::{eyre, Result};
+use itertools::Itertools;
+use k8s_openapi::{
+    api::{
+        apps::v1::{Deployment, ReplicaSet, StatefulSet},
+        core::v1::{Pod, Service},
+    },
+    apimachinery::pkg::apis::meta::v1::LabelSelector,
+};
+use kube::{Api, ResourceExt};
+
+/// Something that selects a set of pods. The format is
+/// `<resource>/<namespace>/<name>`. For `labels`, the name is a label selector,
+/// such as `labels/default/app=web,tier!=db`.
+#[derive(Clone, Debug)]
+pub enum Workload {
+    Deployment { namespace: String, name: String },
+    StatefulSet { namespace: String, name: String },
+    Service { namespace: String, name: String },
+    Labels { namespace: String, selector: String },
+}
+
+impl FromStr for Workload {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let format = "format is <resource>/<namespace>/<name>";
+
+        let mut segments = s.splitn(3, '/');
+
+        let (Some(resource), Some(namespace), Some(name)) =
+            (segments.next(), segments.next(), segments.next())
+        else {
+            return Err(eyre!("invalid workload: {format}"));
+        };
+
+        if namespace.is_empty() || name.is_empty() {
+            return Err(eyre!("invalid workload: {format}"));
+        }
+
+        let namespace = namespace.to_string();
+        let name = name.to_string();
+
+        Ok(match resource {
+            "deployments" | "deployment" | "deploy" => Self::Deployment { namespace, name },
+            "statefulsets" | "statefulset" | "sts" => Self::StatefulSet { namespace, name },
+            "services" | "service" | "svc" => Self::Service { namespace, name },
+            "labels" | "label" | "l" => Self::Labels {
+                namespace,
+                selector: name,
+            },
+            x => return Err(eyre!("resource {x} not supported")),
+        })
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deployment { namespace, name } => write!(f, "deployments/{namespace}/{name}"),
+            Self::StatefulSet { namespace, name } => write!(f, "statefulsets/{namespace}/{name}"),
+            Self::Service { namespace, name } => write!(f, "services/{namespace}/{name}"),
+            Self::Labels {
+                namespace,
+                selector,
+            } => write!(f, "labels/{namespace}/{selector}"),
+        }
+    }
+}
+
+impl Workload {
+    pub fn namespace(&self) -> &str {
+        match self {
+            Self::Deployment { namespace, .. }
+            | Self::StatefulSet { namespace, .. }
+            | Self::Service { namespace, .. }
+            | Self::Labels { namespace, .. } => namespace.as_str(),
+        }
+    }
+
+    /// The object's name, label selectors don't have one.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Deployment { name, .. }
+            | Self::StatefulSet { name, .. }
+            | Self::Service { name, .. } => Some(name.as_str()),
+            Self::Labels { .. } => None,
+        }
+    }
+
+    pub fn plural(&self) -> &'static str {
+        match self {
+            Self::Deployment { .. } => "deployments",
+            Self::StatefulSet { .. } => "statefulsets",
+            Self::Service { .. } => "services",
+            Self::Labels { .. } => "pods",
+        }
+    }
+
+    /// Label selector for the pods that are part of this workload.
+    pub async fn selector(&self, client: kube::Client) -> Result<String> {
+        let ns = self.namespace();
+
+        let selector = match self {
+            Self::Deployment { name, .. } => {
+                let deploy = Api::<Deployment>::namespaced(client, ns).get(name).await?;
+
+                to_selector(&deploy.spec.unwrap_or_default().selector)
+            }
+            Self::StatefulSet { name, .. } => {
+                let sts = Api::<StatefulSet>::namespaced(client, ns).get(name).await?;
+
+                to_selector(&sts.spec.unwrap_or_default().selector)
+            }
+            Self::Service { name, .. } => {
+                let svc = Api::<Service>::namespaced(client, ns).get(name).await?;
+
+                svc.spec
+                    .and_then(|spec| spec.selector)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .join(",")
+            }
+            Self::Labels { selector, .. } => selector.clone(),
+        };
+
+        // An empty selector matches everything in the namespace, which is never
+        // what is wanted here.
+        if selector.is_empty() {
+            return Err(eyre!("{self} does not select any pods"));
+        }
+
+        Ok(selector)
+    }
+}
+
+impl Workload {
+    /// The workload that controls `pod`, if it is a deployment (via its
+    /// replicaset) or a statefulset.
+    pub async fn owner(client: kube::Client, pod: &Pod) -> Result<Option<Self>> {
+        let Some(namespace) = pod.namespace() else {
+            return Ok(None);
+        };
+
+        let Some(owner) = pod
+            .owner_references()
+            .iter()
+            .find(|o| o.controller == Some(true))
+        else {
+            return Ok(None);
+        };
+
+        match owner.kind.as_str() {
+            "StatefulSet" => Ok(Some(Self::StatefulSet {
+                namespace,
+                name: owner.name.clone(),
+            })),
+            "ReplicaSet" => {
+                let rs = Api::<ReplicaSet>::namespaced(client, &namespace)
+                    .get(&owner.name)
+                    .await?;
+
+                Ok(rs
+                    .owner_references()
+                    .iter()
+                    .find(|o| o.controller == Some(true) && o.kind == "Deployment")
+                    .map(|o| Self::Deployment {
+                        namespace,
+                        name: o.name.clone(),
+                    }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Number of replicas that the workload wants, for workloads that can be
+    /// scaled.
+    pub async fn replicas(&self, client: kube::Client) -> Result<Option<i32>> {
+        let ns = self.namespace();
+
+        Ok(match self {
+            Self::Deployment { name, .. } => Api::<Deployment>::namespaced(client, ns)
+                .get(name)
+                .await?
+                .spec
+                .and_then(|s| s.replicas),
+            Self::StatefulSet { name, .. } => Api::<StatefulSet>::namespaced(client, ns)
+                .get(name)
+                .await?
+                .spec
+                .and_then(|s| s.replicas),
+            Self::Service { .. } | Self::Labels { .. } => None,
+        })
+    }
+}
+
+fn to_selector(selector: &LabelSelector) -> String {
+    let labels = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{k}={v}"));
+
+    let expressions = selector.match_expressions.iter().flatten().map(|expr| {
+        let values = expr.values.iter().flatten().join(",");
+
+        match expr.operator.as_str() {
+            "In" => format!("{} in ({values})", expr.key),
+            "NotIn" => format!("{} notin ({values})", expr.key),
+            "DoesNotExist" => format!("!{}", expr.key),
+            _ => expr.key.clone(),
+        }
+    });
+
+    labels.chain(expressions).join(",")
+}
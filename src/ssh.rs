@@ -7,7 +7,7 @@ use std::{
 
 use clap::ValueEnum;
 use derive_builder::Builder;
-use eyre::Result;
+use eyre::{Report, Result};
 use k8s_openapi::{
     api::core::v1::{ObjectReference, Pod, PodStatus},
     apimachinery::pkg::apis::meta::v1,
@@ -15,11 +15,20 @@ use k8s_openapi::{
 use kube::runtime::events::{Event, Recorder, Reporter};
 use lazy_static::lazy_static;
 use prometheus::{register_int_counter, IntCounter};
-use russh::server::{Config, Handler, Server};
+use russh::{
+    keys::key::PublicKey,
+    server::{Config, Handler, Server},
+};
 use session::{Session, SessionBuilder};
 use tracing::error;
 
-use crate::{identity::Identity, openid};
+use crate::{
+    cluster::Cluster,
+    exports::Exports,
+    identity::Identity,
+    openid,
+    resources::{preferences::Preferences, KubeID},
+};
 
 lazy_static! {
     static ref CLIENT_COUNTER: IntCounter = register_int_counter!(
@@ -73,29 +82,61 @@ impl From<CurrentPod> for Pod {
 
 #[derive(Builder)]
 pub struct Controller {
-    config: kube::Config,
+    // The first cluster is the one the server runs in. It has the `Key`
+    // resources, preferences and anything else the server itself needs.
+    #[builder(setter(custom))]
+    clusters: Vec<Cluster>,
     #[allow(dead_code)]
     #[builder(default)]
     reporter: Option<Reporter>,
     #[builder(default)]
     server: CurrentPod,
+    #[builder(default)]
+    exports: Exports,
+    // Name of a `ConfigMap`, in the server's namespace, with the default
+    // keymap and theme for every session.
+    #[builder(default)]
+    preferences: Option<String>,
+}
+
+impl ControllerBuilder {
+    /// The cluster the server runs in, see `remote` for any others.
+    pub fn cluster(&mut self, cluster: Cluster) -> &mut Self {
+        self.clusters
+            .get_or_insert_with(Vec::new)
+            .insert(0, cluster);
+        self
+    }
+
+    /// Other clusters that users can pick with their SSH username, `KTY_CLUSTER`
+    /// or the dashboard.
+    pub fn remote(&mut self, clusters: Vec<Cluster>) -> &mut Self {
+        self.clusters.get_or_insert_with(Vec::new).extend(clusters);
+        self
+    }
 }
 
 impl Controller {
+    pub fn exports(&self) -> &Exports {
+        &self.exports
+    }
+
+    /// A client for the cluster the server runs in, with the server's own
+    /// credentials.
     pub fn client(&self) -> Result<kube::Client, kube::Error> {
-        kube::Client::try_from(self.config.clone())
+        self.home().client()
     }
 
-    pub fn impersonate(
-        &self,
-        user: String,
-        groups: Vec<String>,
-    ) -> Result<kube::Client, kube::Error> {
-        let mut cfg = self.config.clone();
-        cfg.auth_info.impersonate = Some(user);
-        cfg.auth_info.impersonate_groups = (!groups.is_empty()).then_some(groups);
+    pub fn home(&self) -> &Cluster {
+        &self.clusters[0]
+    }
+
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
 
-        kube::Client::try_from(cfg)
+    pub fn cluster(&self, name: &str) -> Option<&Cluster> {
+        self.clusters.iter().find(|cluster| cluster.name() == name)
     }
 
     #[allow(dead_code)]
@@ -109,17 +150,46 @@ impl Controller {
         Ok(())
     }
 
+    /// Keymap and theme for a session, optionally customized by annotations on
+    /// the `Key` they authenticated with. These aren't worth failing a session
+    /// over, the defaults are used when something is wrong.
+    pub async fn preferences(&self, key: Option<&PublicKey>) -> Preferences {
+        let load = async {
+            let key = key.map(KubeID::kube_id).transpose()?;
+
+            Preferences::load(self.client()?, self.preferences.as_deref(), key.as_deref()).await
+        };
+
+        load.await.unwrap_or_else(|err: Report| {
+            tracing::warn!("unable to load preferences: {err:?}");
+
+            Preferences::default()
+        })
+    }
+
     pub fn server(&self) -> Pod {
         self.server.clone().into()
     }
 }
 
-#[derive(Clone, Debug, PartialEq, ValueEnum, strum::VariantArray)]
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
 pub enum Features {
     Pty,
     IngressTunnel,
     EgressTunnel,
     Sftp,
+    NodeShell,
+}
+
+impl Features {
+    /// Everything that is safe to enable without thinking about it.
+    /// `NodeShell` is effectively root on nodes and must be asked for.
+    pub const DEFAULT: &'static [Features] = &[
+        Features::Pty,
+        Features::IngressTunnel,
+        Features::EgressTunnel,
+        Features::Sftp,
+    ];
 }
 
 #[derive(Clone, Builder)]
@@ -164,5 +234,6 @@ impl Server for UIServer {
 
 #[async_trait::async_trait]
 pub trait Authenticate {
-    async fn authenticate(&self, ctrl: &Controller) -> Result<Option<Identity>>;
+    /// The identity to use in `cluster`, if it is allowed in at all.
+    async fn authenticate(&self, ctrl: &Controller, cluster: &Cluster) -> Result<Option<Identity>>;
 }
@@ -2,12 +2,13 @@ mod metrics;
 mod sftp;
 mod state;
 
-use std::{borrow::Cow, collections::HashMap, str, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, str, sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use eyre::{eyre, Report, Result};
 use fast_qr::QRBuilder;
+use futures::future::join_all;
 use metrics::{
     ACTIVE_SESSIONS, AUTH_ATTEMPTS, AUTH_RESULTS, AUTH_SUCEEDED, CHANNELS, CODE_CHECKED,
     CODE_GENERATED, REQUESTS, SESSION_DURATION, TOTAL_BYTES, TOTAL_SESSIONS, UNEXPECTED_STATE,
@@ -25,15 +26,31 @@ use tracing::debug;
 use super::Features;
 use crate::{
     broadcast::Broadcast,
+    cluster::{Cluster, Connection},
     dashboard::Dashboard,
     events::Event,
-    identity::Key,
+    identity::{Identity, Key},
     io::Channel,
     openid,
-    resources::tunnel::{self, EgressBuilder, Ingress, Tunnel, TunnelBuilder},
+    recording::{self, Recorder},
+    resources::{
+        authz::Authz,
+        tunnel::{self, EgressBuilder, Ingress, Tunnel, TunnelBuilder},
+    },
     ssh::{Authenticate, Controller},
+    widget,
 };
 
+// Set with `ssh -o SetEnv=KTY_ROUTE=pods/default/nginx/logs` to start the
+// dashboard somewhere other than the pod list.
+static ROUTE_ENV: &str = "KTY_ROUTE";
+// Set with `ssh -o SetEnv=KTY_CLUSTER=staging` to use a cluster other than the
+// one picked with the username.
+static CLUSTER_ENV: &str = "KTY_CLUSTER";
+// Other clusters are checked before the dashboard starts, one that is slow to
+// answer is left out instead of holding the session up.
+static CLUSTER_TIMEOUT: Duration = Duration::from_secs(2);
+
 fn token_response(error: Report) -> Result<Auth> {
     let http_error = match error.downcast::<reqwest::Error>() {
         Err(err) => return Err(err),
@@ -99,6 +116,36 @@ pub struct Session {
     // window resize event.
     #[builder(default)]
     tunnel: Option<Tunnel>,
+
+    // Set by the `KTY_ROUTE` environment variable when it arrives before the
+    // pty, the dashboard starts there instead of at the pod list.
+    #[builder(default)]
+    route: Option<Vec<String>>,
+
+    // The key used to authenticate, when there was one. The matching `Key`
+    // resource can have annotations that customize the dashboard.
+    #[builder(default)]
+    key: Option<PublicKey>,
+
+    // Name of the cluster picked with the SSH username or `KTY_CLUSTER`. When
+    // it isn't set, the cluster the server runs in is used.
+    #[builder(default)]
+    cluster: Option<String>,
+
+    // A `KTY_CLUSTER` that doesn't exist or the user isn't allowed into, when
+    // it arrives before the pty. The dashboard lets them know once it starts.
+    #[builder(default)]
+    unavailable: Option<String>,
+
+    // Permissions are cached for the whole session, by cluster, so that the
+    // dashboard and SFTP share the same view of what the user is allowed to do.
+    #[builder(default)]
+    authz: HashMap<String, Authz>,
+
+    // Dashboards being recorded, by channel, so that changes to the window size
+    // end up in the recording.
+    #[builder(default)]
+    recorders: HashMap<ChannelId, Recorder>,
 }
 
 impl Session {
@@ -106,6 +153,73 @@ impl Session {
         self.features.contains(feature)
     }
 
+    fn cluster(&self) -> Cluster {
+        self.cluster
+            .as_deref()
+            .and_then(|name| self.controller.cluster(name))
+            .unwrap_or_else(|| self.controller.home())
+            .clone()
+    }
+
+    // Usernames are otherwise ignored, so anything that isn't the name of a
+    // cluster keeps working the way it always has.
+    fn pick(&mut self, user: &str) {
+        if self.controller.cluster(user).is_some() {
+            self.cluster = Some(user.to_string());
+        }
+    }
+
+    fn connect(&mut self, identity: &Identity, cluster: &Cluster) -> Result<Connection> {
+        let client = identity.client(cluster)?;
+        let authz = self
+            .authz
+            .entry(cluster.name().to_string())
+            .or_insert_with(|| Authz::new(client.clone()))
+            .clone();
+
+        Ok(Connection {
+            name: cluster.name().to_string(),
+            client,
+            authz,
+        })
+    }
+
+    // Every cluster `identity` is allowed into, for switching between them in
+    // the dashboard. The current cluster was checked when authenticating.
+    async fn connections(&mut self, identity: &Identity) -> Result<Vec<Connection>> {
+        let current = self.cluster().name().to_string();
+        let controller = self.controller.clone();
+        let (current, ctrl) = (&current, &controller);
+
+        let allowed = join_all(controller.clusters().iter().map(|cluster| async move {
+            if cluster.name() == current {
+                return Some(cluster);
+            }
+
+            match tokio::time::timeout(CLUSTER_TIMEOUT, identity.authenticate(ctrl, cluster)).await
+            {
+                Ok(Ok(allowed)) => allowed.map(|_| cluster),
+                Ok(Err(err)) => {
+                    tracing::warn!(cluster = cluster.name(), "unable to authenticate: {err:?}");
+
+                    None
+                }
+                Err(_) => {
+                    tracing::warn!(cluster = cluster.name(), "timed out authenticating");
+
+                    None
+                }
+            }
+        }))
+        .await;
+
+        allowed
+            .into_iter()
+            .flatten()
+            .map(|cluster| self.connect(identity, cluster))
+            .collect()
+    }
+
     #[tracing::instrument(skip_all)]
     async fn send_code(&mut self) -> Result<Auth> {
         CODE_GENERATED.inc();
@@ -168,7 +282,7 @@ impl Session {
         // try again (3 times by default).
         self.state.code_used();
 
-        let Some(ident) = id.authenticate(&self.controller).await? else {
+        let Some(ident) = id.authenticate(&self.controller, &self.cluster()).await? else {
             AUTH_RESULTS.interactive.reject.inc();
 
             self.state.invalid_identity(id);
@@ -181,15 +295,58 @@ impl Session {
         self.state.authenticated(ident);
 
         if let Some(user_key) = key {
-            Key::from_identity(user_key, &id, expiration)?
+            Key::from_identity(user_key.clone(), &id, expiration)?
                 .update(self.controller.client()?)
                 .await?;
+
+            self.key = Some(user_key);
         }
 
         AUTH_RESULTS.publickey.accept.inc();
 
         Ok(Auth::Accept)
     }
+
+    // Users have to be allowed into the cluster they're switching to. A
+    // dashboard that is already running only has the clusters that they were
+    // allowed into when it started, it is sent there with a route. Clusters
+    // that can't be used are routed to as well, so that the dashboard can say
+    // so.
+    async fn switch(&mut self, id: ChannelId, name: &str) -> Result<()> {
+        let State::Authenticated(identity) = &self.state else {
+            return Err(eyre!("Unexpected state: {:?}", self.state));
+        };
+
+        let allowed = if let Some(cluster) = self.controller.cluster(name) {
+            identity
+                .authenticate(&self.controller, cluster)
+                .await?
+                .is_some()
+        } else {
+            false
+        };
+
+        if allowed {
+            self.cluster = Some(name.to_string());
+        } else {
+            tracing::debug!(cluster = name, "unknown cluster or not allowed in it");
+        }
+
+        if matches!(self.channels.get(&id), Some(Some(_))) {
+            if !allowed {
+                self.unavailable = Some(name.to_string());
+            }
+
+            return Ok(());
+        }
+
+        self.broadcast
+            .send(
+                &id,
+                Event::Goto(vec!["clusters".to_string(), name.to_string()]),
+            )
+            .await
+    }
 }
 
 // TODO(thomas): return valid errors back to the client.
@@ -202,12 +359,14 @@ impl server::Handler for Session {
         AUTH_ATTEMPTS.publickey.inc();
         tracing::debug!("publickey");
 
+        self.pick(user);
         self.state.key_offered(key);
 
-        if let Some(ident) = key.authenticate(&self.controller).await? {
+        if let Some(ident) = key.authenticate(&self.controller, &self.cluster()).await? {
             AUTH_RESULTS.publickey.accept.inc();
 
             self.state.authenticated(ident);
+            self.key = Some(key.clone());
 
             return Ok(Auth::Accept);
         }
@@ -229,6 +388,8 @@ impl server::Handler for Session {
         AUTH_ATTEMPTS.interactive.inc();
         tracing::debug!("keyboard-interactive");
 
+        self.pick(user);
+
         match self.state {
             State::Unauthenticated | State::KeyOffered(_) | State::InvalidIdentity(_, _) => {
                 self.send_code().await
@@ -293,6 +454,8 @@ impl server::Handler for Session {
             writer.send(Event::Shutdown)?;
         }
 
+        self.recorders.remove(&id);
+
         Ok(())
     }
 
@@ -357,7 +520,7 @@ impl server::Handler for Session {
         let id = channel.id();
         let handle = session.handle();
         let broadcast = self.broadcast.clone();
-        let client = identity.client(&self.controller)?;
+        let client = identity.client(&self.cluster())?;
 
         #[allow(clippy::cast_possible_truncation)]
         let ingress = Ingress::new(host_to_connect, port_to_connect as u16)?;
@@ -413,6 +576,11 @@ impl server::Handler for Session {
         REQUESTS.window_resize.inc();
         tracing::debug!("window change");
 
+        #[allow(clippy::cast_possible_truncation)]
+        if let Some(recorder) = self.recorders.get(&id) {
+            recorder.resize(cx as u16, cy as u16);
+        }
+
         #[allow(clippy::cast_possible_truncation)]
         self.broadcast
             .send(
@@ -446,6 +614,36 @@ impl server::Handler for Session {
         Ok(false)
     }
 
+    // Only `KTY_ROUTE` and `KTY_CLUSTER` are used, everything else is ignored.
+    // OpenSSH sends the environment after the pty request, so the dashboard is
+    // usually already running by the time this shows up.
+    #[tracing::instrument(skip(self, _session))]
+    async fn env_request(
+        &mut self,
+        id: ChannelId,
+        name: &str,
+        value: &str,
+        _session: &mut server::Session,
+    ) -> Result<()> {
+        if name == CLUSTER_ENV {
+            return self.switch(id, value).await;
+        }
+
+        if name != ROUTE_ENV {
+            return Ok(());
+        }
+
+        let route = widget::palette::route(value);
+
+        if let Some(Some(_)) = self.channels.get(&id) {
+            self.route = Some(route);
+        } else {
+            self.broadcast.send(&id, Event::Goto(route)).await?;
+        }
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self, _modes, session))]
     async fn pty_request(
         &mut self,
@@ -481,12 +679,40 @@ impl server::Handler for Session {
             return Err(eyre!("channel {id} already consumed"));
         };
 
+        let identity = identity.clone();
+        let connection = self.connect(&identity, &self.cluster())?;
+        let clusters = self.connections(&identity).await?;
+
+        let preferences = self.controller.preferences(self.key.as_ref()).await;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let recorder = recording::DIR
+            .get()
+            .map(|dir| {
+                Recorder::new(
+                    dir,
+                    &identity.name,
+                    &connection.name,
+                    term,
+                    (cx as u16, cy as u16),
+                )
+            })
+            .transpose()?;
+
+        if let Some(recorder) = recorder.as_ref() {
+            self.recorders.insert(id, recorder.clone());
+        }
+
         let writer = Dashboard::builder()
-            .client(identity.client(&self.controller)?)
+            .connection(connection)
+            .clusters(clusters)
+            .preferences(preferences)
+            .exports(self.controller.exports().owner(identity.name.clone()))
+            .maybe_recorder(recorder.clone())
             .build()
             .start(
                 channel.into_stream(),
-                Channel::new(id, session.handle().clone()),
+                Channel::new(id, session.handle().clone()).record(recorder),
             )?;
 
         #[allow(clippy::cast_possible_truncation)]
@@ -505,6 +731,14 @@ impl server::Handler for Session {
             writer.send(Event::Tunnel(Ok(tunnel.clone())))?;
         }
 
+        if let Some(name) = self.unavailable.take() {
+            writer.send(Event::Goto(vec!["clusters".to_string(), name]))?;
+        }
+
+        if let Some(route) = self.route.take() {
+            writer.send(Event::Goto(route))?;
+        }
+
         self.broadcast.add(id, writer).await?;
         session.channel_success(id);
 
@@ -556,7 +790,15 @@ impl server::Handler for Session {
 
         self.channels.insert(id, None);
 
-        let handler = sftp::Handler::new(identity.client(&self.controller)?);
+        let identity = identity.clone();
+        let Connection { client, authz, .. } = self.connect(&identity, &self.cluster())?;
+
+        let handler = sftp::Handler::new(
+            client,
+            authz,
+            self.controller.exports().clone(),
+            identity.name.clone(),
+        );
         russh_sftp::server::run(channel.into_stream(), handler).await;
 
         session.channel_success(id);
@@ -616,7 +858,9 @@ impl server::Handler for Session {
             .meta(meta.clone())
             .broadcast(broadcast.clone())
             .build()?;
-        let client = identity.client(&self.controller)?;
+        // The `EndpointSlice` points at, and is owned by, the server's pod. That
+        // only makes sense in the cluster the server is running in.
+        let client = identity.client(self.controller.home())?;
 
         self.tasks.spawn(async move {
             match egress.run(client, handle.clone()).await {
@@ -8,11 +8,16 @@ use prometheus::{
 };
 use prometheus_static_metric::make_static_metric;
 use russh_sftp::{
-    protocol::{self, Attrs, Data, FileAttributes, Handle, Name, OpenFlags, Status, StatusCode},
+    protocol::{
+        self, Attrs, Data, FileAttributes, FileMode, Handle, Name, OpenFlags, Status, StatusCode,
+    },
     server,
 };
 
-use crate::resources::File;
+use crate::{
+    exports::{Exports, PREFIX},
+    resources::{authz::Authz, File},
+};
 
 make_static_metric! {
     pub struct DirectionVec: IntCounter {
@@ -64,21 +69,94 @@ impl Default for State {
     }
 }
 
+// Buffers saved from the dashboard live outside of the cluster's file tree, in
+// `/.kty/exports`.
+enum Local<'a> {
+    Root,
+    Exports,
+    Export(&'a str),
+}
+
+impl<'a> Local<'a> {
+    fn new(path: &'a str) -> Option<Self> {
+        let path = path.trim_end_matches('/');
+
+        if path == "/.kty" {
+            return Some(Self::Root);
+        }
+
+        if path == PREFIX {
+            return Some(Self::Exports);
+        }
+
+        path.strip_prefix(PREFIX)
+            .and_then(|name| name.strip_prefix('/'))
+            .map(Self::Export)
+    }
+}
+
+fn dir(name: &str) -> protocol::File {
+    protocol::File {
+        filename: name.to_string(),
+        longname: name.to_string(),
+        attrs: FileAttributes {
+            permissions: Some(FileMode::DIR.bits()),
+            ..Default::default()
+        },
+    }
+}
+
+fn export_attrs(size: usize) -> FileAttributes {
+    FileAttributes {
+        size: Some(size as u64),
+        permissions: Some(FileMode::REG.bits() | 0o444),
+        ..Default::default()
+    }
+}
+
 pub struct Handler {
     client: kube::Client,
+    authz: Authz,
+    exports: Exports,
+    owner: String,
     state: State,
 }
 
 // TODO: would it be better to add a `Store<Pod>` to this?
 impl Handler {
-    pub fn new(client: kube::Client) -> Self {
+    pub fn new(client: kube::Client, authz: Authz, exports: Exports, owner: String) -> Self {
         SFTP_ACTIVE.inc();
 
         Self {
             client,
+            authz,
+            exports,
+            owner,
             state: State::default(),
         }
     }
+
+    fn has_exports(&self) -> bool {
+        !self.exports.list(&self.owner).is_empty()
+    }
+
+    // Clients get a clear permission error instead of the path looking like it
+    // doesn't exist. If the check itself fails, the request goes ahead.
+    async fn preflight(&self, file: &File<'_>) -> Result<(), StatusCode> {
+        match self.authz.missing(&file.requirements()).await {
+            Ok(missing) if !missing.is_empty() => {
+                tracing::debug!(?missing, "permission denied");
+
+                Err(StatusCode::PermissionDenied)
+            }
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::debug!("unable to check permissions: {err:?}");
+
+                Ok(())
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -122,7 +200,26 @@ impl server::Handler for Handler {
 
         tracing::debug!("read");
 
-        let result = File::new(Path::new(handle.as_str()))
+        if let Some(local) = Local::new(handle.as_str()) {
+            let Local::Export(name) = local else {
+                return Err(StatusCode::Failure);
+            };
+
+            let data = self
+                .exports
+                .get(&self.owner, name)
+                .ok_or(StatusCode::NoSuchFile)?;
+
+            SFTP_BYTES.sent.inc_by(data.len() as u64);
+
+            return Ok(Data { id, data });
+        }
+
+        let file = File::new(Path::new(handle.as_str()));
+
+        self.preflight(&file).await?;
+
+        let result = file
             .read(self.client.clone())
             .await
             .map(|data| Data { id, data })
@@ -174,12 +271,50 @@ impl server::Handler for Handler {
 
         self.state = State::DirComplete;
 
+        match Local::new(handle.as_str()) {
+            Some(Local::Root) => {
+                return Ok(Name {
+                    id,
+                    files: vec![dir("exports")],
+                })
+            }
+            Some(Local::Exports) => {
+                return Ok(Name {
+                    id,
+                    files: self
+                        .exports
+                        .list(&self.owner)
+                        .into_iter()
+                        .map(|(name, size)| protocol::File {
+                            filename: name.clone(),
+                            longname: name,
+                            attrs: export_attrs(size),
+                        })
+                        .collect(),
+                })
+            }
+            Some(Local::Export(_)) => return Err(StatusCode::NoSuchFile),
+            None => {}
+        }
+
         let path = Path::new(handle.as_str());
+        let file = File::new(path);
+
+        // Only show `/.kty` when there's something in it, it'd be confusing
+        // otherwise.
+        let root = file.namespace.is_none() && self.has_exports();
+
+        self.preflight(&file).await?;
 
-        File::new(path)
-            .list(self.client.clone())
+        file.list(self.client.clone())
             .await
-            .map(|files| Name { id, files })
+            .map(|mut files| {
+                if root {
+                    files.push(dir(".kty"));
+                }
+
+                Name { id, files }
+            })
             .map_err(|e| {
                 tracing::debug!("readdir: {:?}", e);
                 StatusCode::NoSuchFile
@@ -202,6 +337,26 @@ impl server::Handler for Handler {
         SFTP_STAT.inc();
         tracing::debug!("stat");
 
+        match Local::new(path.as_str()) {
+            Some(Local::Root | Local::Exports) => {
+                return Ok(Attrs {
+                    id,
+                    attrs: dir("").attrs,
+                })
+            }
+            Some(Local::Export(name)) => {
+                return self
+                    .exports
+                    .get(&self.owner, name)
+                    .map(|data| Attrs {
+                        id,
+                        attrs: export_attrs(data.len()),
+                    })
+                    .ok_or(StatusCode::NoSuchFile)
+            }
+            None => {}
+        }
+
         File::new(Path::new(path.as_str()))
             .stat(self.client.clone())
             .await
@@ -1,15 +1,26 @@
+pub mod action;
 pub mod apex;
+pub mod cluster;
+pub mod columns;
+pub mod create;
 pub mod debug;
+pub mod denied;
+pub mod editor;
 pub mod error;
+pub mod export;
 pub mod graph;
 pub mod input;
+pub mod keymap;
 pub mod loading;
 pub mod log;
 pub mod nav;
 pub mod node;
+pub mod palette;
 pub mod pod;
+pub mod search;
 pub mod table;
 pub mod tabs;
+pub mod theme;
 pub mod tunnel;
 pub mod view;
 pub mod viewport;
@@ -37,9 +48,11 @@ make_static_metric! {
             container,
             pod,
             node,
+            workload,
         },
         "type" => {
             cmd,
+            debug,
             detail,
             exec,
             list,
@@ -0,0 +1,403 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use eyre::{eyre, Report, Result};
+use futures::{future::try_join_all, FutureExt};
+use k8s_openapi::api::core::v1::{Node, Pod};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{self, Block, Borders, Cell, Clear, Paragraph},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    error::Error,
+    input::{self, Content, ContentExt},
+    keymap, propagate, table, Widget,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        action::Action,
+        authz::{describe, Authz},
+        store::Store,
+    },
+};
+
+#[derive(Clone)]
+pub struct Entry {
+    action: Action,
+    allowed: bool,
+}
+
+impl table::Row for Entry {
+    fn constraints() -> Vec<Constraint> {
+        vec![Constraint::Length(5), Constraint::Fill(0)]
+    }
+
+    fn row(&self, style: &table::RowStyle, _: &table::Matches) -> widgets::Row {
+        let style = if self.allowed {
+            style.normal
+        } else {
+            Style::default().fg(tailwind::GRAY.c600)
+        };
+
+        widgets::Row::new(vec![
+            Cell::from(format!("<{}>", self.action.key())),
+            Cell::from(self.action.to_string()),
+        ])
+        .style(style)
+    }
+}
+
+type Entries = Rc<RefCell<Vec<Entry>>>;
+
+impl table::Items for Entries {
+    type Item = Entry;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.borrow().clone()
+    }
+}
+
+enum State {
+    Menu,
+    Confirm(Action),
+    Replicas(Action, input::Text),
+    Running(JoinHandle<Result<String>>),
+    Done,
+}
+
+/// A menu of the actions that can be taken on a resource. Each action is
+/// checked ahead of time and the ones the user isn't allowed to do are greyed
+/// out. The outcome is shown as a notice or error.
+pub struct Actions {
+    client: kube::Client,
+
+    entries: Entries,
+    picker: table::Table<Entries>,
+    task: Option<JoinHandle<Result<Vec<Entry>>>>,
+
+    state: State,
+    error: Option<Error>,
+}
+
+impl Actions {
+    fn new<F>(client: kube::Client, authz: Authz, actions: F) -> Self
+    where
+        F: std::future::Future<Output = Result<Vec<Action>>> + Send + 'static,
+    {
+        let entries = Rc::new(RefCell::new(Vec::new()));
+
+        let task = tokio::spawn(async move {
+            try_join_all(actions.await?.into_iter().map(|action| {
+                let authz = authz.clone();
+
+                async move {
+                    let allowed = authz.allowed(&action.requirements()).await?;
+
+                    Ok::<_, Report>(Entry { action, allowed })
+                }
+            }))
+            .await
+        });
+
+        Self {
+            client,
+            picker: table::Table::builder()
+                .title("Actions")
+                .items(entries.clone())
+                .build(),
+            entries,
+            task: Some(task),
+            state: State::Menu,
+            error: None,
+        }
+    }
+
+    pub fn pod(client: kube::Client, authz: Authz, pod: Arc<Pod>) -> Self {
+        let lookup = client.clone();
+
+        Self::new(
+            client,
+            authz,
+            async move { Action::pod(lookup, &pod).await },
+        )
+    }
+
+    pub fn node(client: kube::Client, authz: Authz, node: &Arc<Node>) -> Self {
+        let actions = Action::node(node);
+
+        Self::new(client, authz, async move { Ok(actions) })
+    }
+
+    pub fn from_pods(client: kube::Client, authz: Authz, pods: Arc<Store<Pod>>) -> table::DetailFn {
+        Box::new(move |idx, filter| {
+            let pod = pods
+                .get(idx, filter)
+                .ok_or_else(|| eyre!("pod not found"))?;
+
+            Ok(Self::pod(client.clone(), authz.clone(), pod).boxed())
+        })
+    }
+
+    pub fn from_nodes(
+        client: kube::Client,
+        authz: Authz,
+        nodes: Arc<Store<Node>>,
+    ) -> table::DetailFn {
+        Box::new(move |idx, filter| {
+            let node = nodes
+                .get(idx, filter)
+                .ok_or_else(|| eyre!("node not found"))?;
+
+            Ok(Self::node(client.clone(), authz.clone(), &node).boxed())
+        })
+    }
+
+    fn update(&mut self) {
+        if self.task.as_ref().map_or(false, JoinHandle::is_finished) {
+            if let Some(Some(result)) = self.task.take().map(FutureExt::now_or_never) {
+                match result.map_err(Report::from).and_then(|r| r) {
+                    Ok(entries) => *self.entries.borrow_mut() = entries,
+                    Err(err) => {
+                        self.error = Some(Error::from(err));
+                        self.state = State::Done;
+                    }
+                }
+            }
+        }
+
+        let State::Running(task) = &mut self.state else {
+            return;
+        };
+
+        if !task.is_finished() {
+            return;
+        }
+
+        let Some(result) = task.now_or_never() else {
+            return;
+        };
+
+        self.error = Some(match result.map_err(Report::from).and_then(|r| r) {
+            Ok(msg) => Error::notice(&msg),
+            Err(err) => Error::from(err),
+        });
+        self.state = State::Done;
+    }
+
+    fn select(&mut self, entry: Entry) {
+        if !entry.allowed {
+            let needs = entry
+                .action
+                .requirements()
+                .iter()
+                .map(describe)
+                .collect::<Vec<_>>()
+                .join(" and ");
+
+            self.error = Some(Error::from(format!(" You need {needs}.")));
+
+            return;
+        }
+
+        self.state = match entry.action {
+            Action::Scale { replicas, .. } => State::Replicas(
+                entry.action,
+                input::Text::builder()
+                    .title("Replicas")
+                    .content(Content::from_string(replicas.to_string()))
+                    .border_style(Style::default().fg(tailwind::BLUE.c500))
+                    .build(),
+            ),
+            action if action.confirm() => State::Confirm(action),
+            action => self.run(action),
+        };
+    }
+
+    fn run(&self, action: Action) -> State {
+        let client = self.client.clone();
+
+        State::Running(tokio::spawn(async move { action.run(client).await }))
+    }
+
+    fn scale(&mut self, action: &Action, content: &Content) -> Result<()> {
+        let replicas: i32 = content
+            .borrow()
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .parse()
+            .map_err(|_| eyre!("replicas must be a number"))?;
+
+        if replicas < 0 {
+            return Err(eyre!("replicas cannot be negative"));
+        }
+
+        self.state = self.run(action.with_replicas(replicas));
+
+        Ok(())
+    }
+
+    fn dispatch_menu(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(Keypress::Printable(key)) = event.key() {
+            let entry = self
+                .entries
+                .borrow()
+                .iter()
+                .find(|e| e.action.key() == *key)
+                .cloned();
+
+            if let Some(entry) = entry {
+                self.select(entry);
+
+                return Ok(Broadcast::Consumed);
+            }
+        }
+
+        match self.picker.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                let entry = self.entries.borrow().get(idx).cloned();
+
+                if let Some(entry) = entry {
+                    self.select(entry);
+                }
+            }
+            Broadcast::Ignored if event.key().is_some_and(keymap::exit) => {
+                return Ok(Broadcast::Exited);
+            }
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn popup(area: Rect, height: u16) -> Rect {
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        area
+    }
+}
+
+impl Widget for Actions {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(error) = self.error.as_mut() {
+            propagate!(error.dispatch(event, buffer, area), {
+                self.error = None;
+
+                // Once an action has run, there's nothing left to do here.
+                if let State::Done = self.state {
+                    return Ok(Broadcast::Exited);
+                }
+            });
+        }
+
+        match &mut self.state {
+            State::Menu => return self.dispatch_menu(event, buffer, area),
+            State::Confirm(action) => {
+                let action = action.clone();
+
+                self.state = match event.key() {
+                    Some(Keypress::Printable('y')) => self.run(action),
+                    Some(_) => State::Menu,
+                    None => State::Confirm(action),
+                };
+            }
+            State::Replicas(action, input) => {
+                let action = action.clone();
+                let content = input.content();
+
+                match input.dispatch(event, buffer, area)? {
+                    Broadcast::Exited => self.state = State::Menu,
+                    Broadcast::Ignored if matches!(event.key(), Some(Keypress::Enter)) => {
+                        if let Err(err) = self.scale(&action, &content) {
+                            self.error = Some(Error::from(err));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            State::Running(_) | State::Done => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.update();
+
+        match &mut self.state {
+            State::Menu | State::Done => {
+                let height = u16::try_from(self.entries.borrow().len())
+                    .unwrap_or(u16::MAX)
+                    .saturating_add(2)
+                    .max(3);
+                let area = Self::popup(area, height);
+
+                frame.render_widget(Clear, area);
+
+                if self.task.is_some() {
+                    frame.render_widget(
+                        Paragraph::new("Checking permissions...")
+                            .block(Block::default().borders(Borders::ALL).title("Actions")),
+                        area,
+                    );
+                } else {
+                    self.picker.draw(frame, area)?;
+                }
+            }
+            State::Confirm(action) => {
+                let area = Self::popup(area, 3);
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(Line::from(format!("{action}? <y> to confirm")))
+                        .style(Style::default().fg(tailwind::RED.c300))
+                        .block(Block::default().borders(Borders::ALL).title("Confirm")),
+                    area,
+                );
+            }
+            State::Replicas(_, input) => {
+                let area = Self::popup(area, 3);
+
+                frame.render_widget(Clear, area);
+                input.draw(frame, area)?;
+            }
+            State::Running(_) => {
+                let area = Self::popup(area, 3);
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new("Running...")
+                        .block(Block::default().borders(Borders::ALL).title("Actions")),
+                    area,
+                );
+            }
+        }
+
+        if let Some(error) = self.error.as_mut() {
+            error.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        2
+    }
+}
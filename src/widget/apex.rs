@@ -5,7 +5,7 @@ use eyre::Result;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{palette::tailwind, Style, Stylize},
+    style::{Style, Stylize},
     text::Text,
     widgets::{Block, Borders, Clear, Row, Table},
     Frame,
@@ -14,38 +14,84 @@ use tachyonfx::{fx, EffectTimer, Interpolation};
 use tracing::{metadata::LevelFilter, Level};
 
 use super::{
+    cluster,
+    create::Create,
     debug::Debug,
     error::Error,
-    node, pod,
+    keymap::{self, Action},
+    log::workload::Workload,
+    node,
+    palette::Palette,
+    pod,
     tabs::TabbedView,
+    theme,
     tunnel::Tunnel,
     view::{Element, View},
     Placement, Widget,
 };
 use crate::{
+    cluster::Connection,
     events::{Broadcast, Event, Keypress},
     fx::Animated,
+    recording,
+    resources::metrics::Metrics,
 };
 
 pub struct Apex {
+    client: kube::Client,
     view: View,
     top_idx: Rc<RefCell<u16>>,
+    palette: Option<Palette>,
+    _metrics: Metrics,
+
+    current: String,
+    clusters: Vec<Connection>,
+    switch: cluster::Switch,
 }
 
 impl Apex {
-    pub fn new(client: kube::Client) -> Self {
+    /// Show `current`, with a tab to switch to any of `clusters` when there is
+    /// more than one.
+    pub fn new(current: Connection, clusters: Vec<Connection>) -> Self {
+        let Connection {
+            name,
+            client,
+            authz,
+            ..
+        } = current;
+
         let top_idx = Rc::new(RefCell::new(0));
+        let switch = cluster::Switch::default();
+
+        let mut tabs = vec![
+            pod::List::tab("Pods".to_string(), client.clone(), authz.clone(), true),
+            node::List::tab("Nodes".to_string(), client.clone(), authz.clone(), true),
+            Workload::tab("Logs".to_string(), client.clone(), true),
+        ];
+
+        if clusters.len() > 1 {
+            tabs.push(cluster::List::tab(
+                "Clusters".to_string(),
+                clusters
+                    .iter()
+                    .map(|cluster| cluster.name.clone())
+                    .collect(),
+                name.clone(),
+                switch.clone(),
+            ));
+        }
 
-        let tabs = TabbedView::builder()
-            .tabs(vec![
-                pod::List::tab("Pods".to_string(), client.clone(), true),
-                node::List::tab("Nodes".to_string(), client, true),
-            ])
-            .build();
+        let tabs = TabbedView::builder().tabs(tabs).authz(authz).build();
 
         let mut widgets = vec![
             Element::builder()
-                .widget(Banner::builder().idx(top_idx.clone()).build().boxed())
+                .widget(
+                    Banner::builder()
+                        .idx(top_idx.clone())
+                        .maybe_cluster((clusters.len() > 1).then(|| name.clone()))
+                        .build()
+                        .boxed(),
+                )
                 .ignore(true)
                 .build(),
             Element::builder()
@@ -80,8 +126,35 @@ impl Apex {
         }
 
         Self {
+            _metrics: Metrics::start(client.clone()),
+            client,
             view: View::builder().widgets(widgets).show_all(true).build(),
             top_idx,
+            palette: None,
+
+            current: name,
+            clusters,
+            switch,
+        }
+    }
+
+    // Everything is built for a single client, so switching clusters starts
+    // over with a new view.
+    fn switch(&mut self) {
+        let Some(name) = self.switch.lock().ok().and_then(|mut switch| switch.take()) else {
+            return;
+        };
+
+        if name == self.current {
+            return;
+        }
+
+        if let Some(connection) = self.clusters.iter().find(|cluster| cluster.name == name) {
+            if let Some(recorder) = recording::current() {
+                recorder.cluster(&name);
+            }
+
+            *self = Self::new(connection.clone(), self.clusters.clone());
         }
     }
 }
@@ -93,13 +166,67 @@ impl Widget for Apex {
             self.view.push(Error::from(err.message()).boxed().into());
         }
 
-        Ok(match self.view.dispatch(event, buffer, area)? {
+        if let Some(name) = cluster_route(event) {
+            if !self.clusters.iter().any(|cluster| cluster.name == name) {
+                self.view.push(
+                    Error::from(format!(
+                        " Unable to switch to {name}, it either doesn't exist or you aren't \
+                         allowed into it."
+                    ))
+                    .boxed()
+                    .into(),
+                );
+
+                return Ok(Broadcast::Consumed);
+            }
+
+            // There's no clusters tab when this is the only one.
+            if name == self.current {
+                return Ok(Broadcast::Consumed);
+            }
+        }
+
+        if let Some(palette) = self.palette.as_mut() {
+            match palette.dispatch(event, buffer, area)? {
+                Broadcast::Exited => {
+                    let route = palette.route();
+                    self.palette = None;
+
+                    if let Some(route) = route {
+                        self.view.dispatch(&Event::Goto(route), buffer, area)?;
+                    }
+
+                    return Ok(Broadcast::Consumed);
+                }
+                Broadcast::Ignored => {}
+                x => return Ok(x),
+            }
+        }
+
+        let broadcast = self.view.dispatch(event, buffer, area)?;
+
+        self.switch();
+
+        Ok(match broadcast {
             Broadcast::Ignored => match event.key() {
                 Some(Keypress::Printable('?')) => {
                     self.view.push(Help::builder().build().boxed().into());
 
                     Broadcast::Consumed
                 }
+                Some(Keypress::Printable(':')) => {
+                    self.palette = Some(Palette::new(self.client.clone()));
+
+                    Broadcast::Consumed
+                }
+                // `n` would be easier to remember, but it is used to jump
+                // between search matches.
+                Some(Keypress::Printable('+')) => {
+                    self.view
+                        .push(Create::new(self.client.clone()).boxed().into());
+
+                    Broadcast::Consumed
+                }
                 _ => Broadcast::Ignored,
             },
             x => x,
@@ -109,15 +236,36 @@ impl Widget for Apex {
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         *self.top_idx.borrow_mut() = self.view.zindex();
 
-        self.view.draw(frame, area)
+        self.view.draw(frame, area)?;
+
+        if let Some(palette) = self.palette.as_mut() {
+            palette.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The cluster a route goes to, such as `clusters/staging`. These come from
+// `KTY_CLUSTER` as well as the palette.
+fn cluster_route(event: &Event) -> Option<&str> {
+    let Event::Goto(route) = event else {
+        return None;
+    };
+
+    match route.as_slice() {
+        [tab, name, ..] if tab.eq_ignore_ascii_case("clusters") => Some(name),
+        _ => None,
     }
 }
 
 #[derive(Builder)]
 struct Banner {
     idx: Rc<RefCell<u16>>,
+    // Only shown when there's more than one cluster to pick from.
+    cluster: Option<String>,
 
-    #[builder(default = Style::default().fg(tailwind::GRAY.c200).bg(tailwind::SKY.c700))]
+    #[builder(default = theme::current().banner)]
     style: Style,
 }
 
@@ -134,7 +282,12 @@ impl Widget for Banner {
             Block::default().style(self.style).borders(Borders::TOP),
             area,
         );
-        frame.render_widget(Text::from("kty >_"), logo);
+        let logo_text = match &self.cluster {
+            Some(cluster) => format!("kty >_ {cluster}"),
+            None => "kty >_".to_string(),
+        };
+
+        frame.render_widget(Text::from(logo_text), logo);
         frame.render_widget(Text::from("<?> help").alignment(Alignment::Right), help);
 
         Ok(())
@@ -154,7 +307,7 @@ impl Widget for Banner {
 
 #[derive(Builder)]
 struct Help {
-    #[builder(default = Style::default().bold().fg(tailwind::INDIGO.c300))]
+    #[builder(default = Style::default().bold().fg(theme::current().header))]
     header_style: Style,
 }
 
@@ -173,24 +326,70 @@ impl Widget for Help {
 
         let widths = [Constraint::Percentage(25), Constraint::Fill(0)];
 
-        let rows = [
-            Row::new(["<ctrl-c>", "Quit"]),
-            Row::new(["<ctrl-d> | <esc>", "Close"]),
-            Row::new(["<?>", "Help page"]),
-            Row::new(["<enter>", "Select row or submit input"]),
-            Row::new(["</>", "Filter rows or search content"]),
-            Row::new(["<left> | <h>", "Switch tabs or scroll view left"]),
-            Row::new(["<right> | <l>", "Switch tabs or scroll view right"]),
-            Row::new(["<up> | <k>", "Navigate or scroll up one row"]),
-            Row::new(["<down> | <j>", "Navigate or scroll down one row"]),
-            Row::new(["<H>", "Navigate or scroll to the beginning"]),
-            Row::new(["<L>", "Navigate or scroll to the end"]),
-            Row::new(["<ctrl-b> | <b>", "Navigate or scroll up one page"]),
-            Row::new(["< > | <f>", "Navigate or scroll down one page"]),
-            Row::new(["<ctrl-a> | <^>", "Jump to the beginning of the line"]),
-            Row::new(["<ctrl-e> | <$>", "Jump to the end of the line"]),
-            Row::new(["<ctrl-k>", "Delete from the cursor to the end of the line"]),
-        ];
+        // Close is first, everything else the keymap has is about moving around
+        // and goes at the end.
+        let (close, moves): (Vec<_>, Vec<_>) = keymap::current()
+            .help()
+            .into_iter()
+            .map(|(action, keys)| (action, (keys, action.description())))
+            .partition(|(action, _)| *action == Action::Exit);
+
+        let rows = [("<ctrl-c>".to_string(), "Quit")]
+            .into_iter()
+            .chain(close.into_iter().map(|(_, row)| row))
+            .chain(
+                [
+                    ("<?>", "Help page"),
+                    (
+                        "<:>",
+                        "Go to a path, such as `pods/default/nginx/logs`, <tab> completes",
+                    ),
+                    ("<enter>", "Select row or submit input"),
+                    ("</>", "Filter rows or search content"),
+                    ("<n> | <N>", "Jump to the next or previous search match"),
+                    ("<&>", "Toggle only showing lines that match the search"),
+                    (
+                        "<c> | <C>",
+                        "Pick a container or show all containers in logs",
+                    ),
+                    ("<t>", "Toggle timestamps in logs"),
+                    ("<p>", "Switch between current and previous logs"),
+                    ("<s>", "Toggle pretty printing of JSON and logfmt logs"),
+                    (
+                        "<|>",
+                        "Filter logs by field, such as `level>=warn user_id=123`",
+                    ),
+                    (
+                        "<tab>",
+                        "In a shell, switch to debugging with an ephemeral container",
+                    ),
+                    ("<+>", "Create a new resource from a template"),
+                    ("<o> | <O>", "Order rows by the next column, or reverse the order"),
+                    ("<v>", "Show or hide columns"),
+                    (
+                        "<a>",
+                        "Actions for the selected pod or node: delete, restart, scale, cordon, drain",
+                    ),
+                    ("<e>", "Edit yaml, if you're allowed to patch the resource"),
+                    (
+                        "<ctrl-s>",
+                        "Review the diff of edited yaml before applying it",
+                    ),
+                    (
+                        "<F>",
+                        "Apply edited yaml, taking ownership of conflicting fields",
+                    ),
+                    ("<y>", "Copy logs or yaml to the clipboard"),
+                    ("<S>", "Save logs or yaml for download via SFTP"),
+                ]
+                .map(|(key, action)| (key.to_string(), action)),
+            )
+            .chain(moves.into_iter().map(|(_, row)| row))
+            .chain([(
+                "<ctrl-k>".to_string(),
+                "Delete from the cursor to the end of the line",
+            )])
+            .map(|(key, action)| Row::new([key, action.to_string()]));
 
         let table = Table::new(rows, widths)
             .block(Block::default().borders(Borders::ALL))
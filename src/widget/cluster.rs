@@ -0,0 +1,166 @@
+use std::sync::{Arc, Mutex};
+
+use eyre::Result;
+use ratatui::{buffer::Buffer, layout::Constraint, layout::Rect, style::Style, Frame};
+
+use super::{
+    columns::Column,
+    keymap, table,
+    tabs::Tab,
+    view::{Element, View},
+    Widget,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        query::{Field, Filter},
+        Query,
+    },
+};
+
+/// The name of a cluster that the dashboard should switch to. Tabs need to be
+/// `Send`, so this can't be an `Rc` like the rest of the shared state.
+pub type Switch = Arc<Mutex<Option<String>>>;
+
+#[derive(Clone)]
+pub struct Entry {
+    name: String,
+    current: bool,
+}
+
+impl Filter for Entry {
+    fn field(&self, field: &Field) -> Option<String> {
+        match field {
+            Field::Name => Some(self.name.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl table::Row for Entry {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("Name", Constraint::Fill(1)),
+            Column::new("Current", Constraint::Length(7)),
+        ]
+    }
+
+    fn cell(&self, column: &str) -> String {
+        match column {
+            "Name" => self.name.clone(),
+            "Current" if self.current => "*".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn style(&self, style: &table::RowStyle) -> Style {
+        if self.current {
+            style.healthy
+        } else {
+            style.normal
+        }
+    }
+
+    fn path(&self) -> Vec<String> {
+        vec![self.name.clone()]
+    }
+}
+
+impl table::Items for Vec<Entry> {
+    type Item = Entry;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let query = filter.as_deref().map(Query::parse);
+
+        self.iter()
+            .filter(|entry| query.as_ref().map_or(true, |query| query.matches(*entry)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Every cluster the user can switch to, selecting one rebuilds the dashboard
+/// for it.
+pub struct List {
+    entries: Vec<Entry>,
+    switch: Switch,
+    view: View,
+}
+
+impl List {
+    pub fn new(names: Vec<String>, current: &str, switch: Switch) -> Self {
+        let entries = names
+            .into_iter()
+            .map(|name| Entry {
+                current: name == current,
+                name,
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            view: View::builder()
+                .widgets(vec![table::Table::builder()
+                    .items(entries.clone())
+                    .build()
+                    .boxed()
+                    .into()])
+                .build(),
+            entries,
+            switch,
+        }
+    }
+
+    pub fn tab(name: String, names: Vec<String>, current: String, switch: Switch) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || {
+                Element::builder()
+                    .widget(Self::new(names.clone(), &current, switch.clone()).boxed())
+                    .terminal(true)
+                    .build()
+            }))
+            .build()
+    }
+
+    fn select(&self, name: &str) {
+        if let Ok(mut switch) = self.switch.lock() {
+            *switch = Some(name.to_string());
+        }
+    }
+}
+
+impl Widget for List {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Goto(route) = event {
+            if let Some(entry) = route
+                .first()
+                .and_then(|name| self.entries.iter().find(|entry| entry.name == *name))
+            {
+                self.select(&entry.name);
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match self.view.dispatch(event, buffer, area)? {
+            // The table has no filter, so rows are in the same order as
+            // `entries`.
+            Broadcast::Selected(idx) => {
+                if let Some(entry) = self.entries.get(idx) {
+                    self.select(&entry.name);
+                }
+
+                Ok(Broadcast::Consumed)
+            }
+            Broadcast::Ignored => match event.key().unwrap_or(&Keypress::Null) {
+                key if keymap::exit(key) => Ok(Broadcast::Exited),
+                _ => Ok(Broadcast::Ignored),
+            },
+            broadcast => Ok(broadcast),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.view.draw(frame, area)
+    }
+}
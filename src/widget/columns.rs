@@ -0,0 +1,282 @@
+use std::{cell::RefCell, cmp::Ordering, collections::HashSet, rc::Rc};
+
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{self, Cell, Clear},
+    Frame,
+};
+
+use super::{keymap, table, Widget};
+use crate::events::{Broadcast, Event};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+impl Order {
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        match self {
+            Order::Ascending => Order::Descending,
+            Order::Descending => Order::Ascending,
+        }
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Order::Ascending => "▲",
+            Order::Descending => "▼",
+        }
+    }
+}
+
+/// A column that rows describe themselves with, so that tables can hide it or
+/// order rows by it.
+#[derive(Clone, Copy, Debug)]
+pub struct Column {
+    pub name: &'static str,
+    pub constraint: Constraint,
+    // Hidden columns have to be picked with `<v>` to be shown.
+    pub hidden: bool,
+    // The order rows are put in when first sorting by this column, columns
+    // without one can't be sorted by.
+    pub order: Option<Order>,
+}
+
+impl Column {
+    pub const fn new(name: &'static str, constraint: Constraint) -> Self {
+        Self {
+            name,
+            constraint,
+            hidden: false,
+            order: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn sortable(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+}
+
+/// The value of a column that rows are sorted by.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum Key {
+    Number(f64),
+    Text(String),
+}
+
+/// Which columns a table shows and what its rows are sorted by. This is shared
+/// between the table and the picker that changes it.
+#[derive(Debug, Default)]
+pub struct Columns {
+    // Columns that have been shown or hidden, the opposite of how they start.
+    toggled: HashSet<&'static str>,
+    sort: Option<(&'static str, Order)>,
+}
+
+impl Columns {
+    pub fn visible(&self, column: &Column) -> bool {
+        column.hidden == self.toggled.contains(column.name)
+    }
+
+    /// Show or hide `column`, rows stop being sorted by it once it is hidden.
+    pub fn toggle(&mut self, column: &Column) {
+        if !self.toggled.remove(column.name) {
+            self.toggled.insert(column.name);
+        }
+
+        if !self.visible(column) && self.sort.is_some_and(|(name, _)| name == column.name) {
+            self.sort = None;
+        }
+    }
+
+    pub fn sort(&self) -> Option<(&'static str, Order)> {
+        self.sort
+    }
+
+    /// Sort by the next visible column that can be sorted by, after the last
+    /// one going back to the natural order.
+    pub fn next(&mut self, columns: &[Column]) {
+        let sortable = columns
+            .iter()
+            .filter(|column| column.order.is_some() && self.visible(column))
+            .collect::<Vec<_>>();
+
+        let next = match self.sort {
+            Some((current, _)) => sortable
+                .iter()
+                .position(|column| column.name == current)
+                .and_then(|idx| sortable.get(idx + 1)),
+            None => sortable.first(),
+        };
+
+        self.sort = next.and_then(|column| Some((column.name, column.order?)));
+    }
+
+    pub fn reverse(&mut self) {
+        if let Some((_, order)) = self.sort.as_mut() {
+            *order = order.reverse();
+        }
+    }
+}
+
+/// Positions of `items` in the order they should be drawn. This is stable, so
+/// rows with the same value stay in their natural order. Rows without a value
+/// always go last.
+pub fn order<T: table::Row>(items: &[T], sort: Option<(&str, Order)>) -> Vec<usize> {
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+
+    let Some((column, direction)) = sort else {
+        return order;
+    };
+
+    let keys = items
+        .iter()
+        .map(|item| item.key(column))
+        .collect::<Vec<_>>();
+
+    order.sort_by(|a, b| match (&keys[*a], &keys[*b]) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(b).unwrap_or(Ordering::Equal);
+
+            match direction {
+                Order::Ascending => ordering,
+                Order::Descending => ordering.reverse(),
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    order
+}
+
+#[derive(Clone)]
+struct Entry {
+    column: Column,
+    shown: bool,
+    sort: Option<Order>,
+}
+
+impl table::Row for Entry {
+    fn constraints() -> Vec<Constraint> {
+        vec![
+            Constraint::Length(3),
+            Constraint::Fill(0),
+            Constraint::Length(1),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle, _: &table::Matches) -> widgets::Row {
+        widgets::Row::new(vec![
+            Cell::from(if self.shown { "[x]" } else { "[ ]" }),
+            Cell::from(self.column.name),
+            Cell::from(self.sort.map(Order::arrow).unwrap_or_default()),
+        ])
+        .style(style.normal)
+    }
+}
+
+struct Entries {
+    available: fn() -> Vec<Column>,
+    state: Rc<RefCell<Columns>>,
+}
+
+impl table::Items for Entries {
+    type Item = Entry;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        let state = self.state.borrow();
+
+        (self.available)()
+            .into_iter()
+            .map(|column| Entry {
+                shown: state.visible(&column),
+                sort: state
+                    .sort()
+                    .filter(|(name, _)| *name == column.name)
+                    .map(|(_, order)| order),
+                column,
+            })
+            .collect()
+    }
+}
+
+/// Pick which columns of a table are shown, `<enter>` toggles the selected
+/// column.
+pub struct Picker {
+    available: fn() -> Vec<Column>,
+    state: Rc<RefCell<Columns>>,
+    table: table::Table<Entries>,
+}
+
+impl Picker {
+    pub fn new(available: fn() -> Vec<Column>, state: Rc<RefCell<Columns>>) -> Self {
+        Self {
+            available,
+            state: state.clone(),
+            table: table::Table::builder()
+                .title("Columns")
+                .items(Entries { available, state })
+                .build(),
+        }
+    }
+}
+
+impl Widget for Picker {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                if let Some(column) = (self.available)().get(idx) {
+                    self.state.borrow_mut().toggle(column);
+                }
+            }
+            Broadcast::Ignored if event.key().is_some_and(keymap::exit) => {
+                return Ok(Broadcast::Exited);
+            }
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let height = u16::try_from((self.available)().len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(2);
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(30),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        frame.render_widget(Clear, area);
+
+        self.table.draw(frame, area)
+    }
+
+    fn zindex(&self) -> u16 {
+        2
+    }
+}
@@ -0,0 +1,167 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use eyre::{Report, Result};
+use futures::FutureExt;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::{api::ListParams, Api};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{error::Error, keymap, propagate, table, yaml::Yaml, Widget};
+use crate::{
+    events::{Broadcast, Event},
+    resources::template::Template,
+};
+
+type Templates = Rc<RefCell<Vec<Template>>>;
+
+/// Pick a template and then edit it before creating a new object in the
+/// client's default namespace. Custom resources show up as templates once their
+/// definitions have been listed, if the user is allowed to.
+pub struct Create {
+    client: kube::Client,
+
+    templates: Templates,
+    picker: table::Table<Templates>,
+    task: Option<JoinHandle<Result<Vec<CustomResourceDefinition>>>>,
+
+    yaml: Option<Yaml>,
+    error: Option<Error>,
+}
+
+impl Create {
+    pub fn new(client: kube::Client) -> Self {
+        let templates = Rc::new(RefCell::new(Template::builtin()));
+
+        let crds = Api::<CustomResourceDefinition>::all(client.clone());
+        let task = tokio::spawn(async move { Ok(crds.list(&ListParams::default()).await?.items) });
+
+        Self {
+            client,
+            picker: table::Table::builder()
+                .title("New")
+                .items(templates.clone())
+                .build(),
+            templates,
+            task: Some(task),
+            yaml: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self) {
+        if !self.task.as_ref().map_or(false, JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(Some(result)) = self.task.take().map(FutureExt::now_or_never) else {
+            return;
+        };
+
+        match result.map_err(Report::from).and_then(|r| r) {
+            Ok(mut crds) => {
+                crds.sort_by(|a, b| a.spec.names.kind.cmp(&b.spec.names.kind));
+
+                self.templates
+                    .borrow_mut()
+                    .extend(crds.into_iter().map(|crd| Template::Custom(Arc::new(crd))));
+            }
+            // Not being able to list CRDs is common and the built-in templates still
+            // work fine.
+            Err(err) => tracing::debug!("unable to list CRDs: {err:?}"),
+        }
+    }
+
+    fn select(&mut self, idx: usize) -> Result<()> {
+        let Some(template) = self.templates.borrow().get(idx).cloned() else {
+            return Ok(());
+        };
+
+        let raw = template.render(self.client.default_namespace())?;
+
+        self.yaml = Some(Yaml::template(self.client.clone(), template.kind(), &raw));
+
+        Ok(())
+    }
+
+    fn draw_picker(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let height = u16::try_from(self.templates.borrow().len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(3);
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        frame.render_widget(Clear, area);
+
+        self.picker.draw(frame, area)
+    }
+}
+
+impl Widget for Create {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(error) = self.error.as_mut() {
+            propagate!(error.dispatch(event, buffer, area), self.error = None);
+        }
+
+        if let Some(yaml) = self.yaml.as_mut() {
+            propagate!(yaml.dispatch(event, buffer, area), self.yaml = None);
+
+            if event.key().is_some_and(keymap::exit) {
+                self.yaml = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match self.picker.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                if let Err(err) = self.select(idx) {
+                    self.error = Some(Error::from(err));
+                }
+
+                Ok(Broadcast::Consumed)
+            }
+            Broadcast::Ignored if event.key().is_some_and(keymap::exit) => Ok(Broadcast::Exited),
+            // This is modal, nothing should make it through to widgets underneath.
+            _ => Ok(Broadcast::Consumed),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.update();
+
+        if let Some(yaml) = self.yaml.as_mut() {
+            frame.render_widget(Clear, area);
+
+            yaml.draw(frame, area)?;
+        } else {
+            self.draw_picker(frame, area)?;
+        }
+
+        if let Some(error) = self.error.as_mut() {
+            error.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        4
+    }
+}
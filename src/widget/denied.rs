@@ -0,0 +1,55 @@
+use eyre::Result;
+use k8s_openapi::api::authorization::v1::ResourceAttributes;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::Widget;
+use crate::resources::authz::describe;
+
+/// Shown in place of a widget when the user is missing permissions it needs,
+/// instead of letting it fail part way through.
+pub struct Denied {
+    missing: Vec<ResourceAttributes>,
+}
+
+impl Denied {
+    pub fn new(missing: Vec<ResourceAttributes>) -> Self {
+        Self { missing }
+    }
+}
+
+impl Widget for Denied {
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = std::iter::once(Line::from("You're missing permissions for this:"))
+            .chain(std::iter::once(Line::default()))
+            .chain(
+                self.missing
+                    .iter()
+                    .map(|attrs| Line::from(format!("- {}", describe(attrs)))),
+            )
+            .collect::<Vec<_>>();
+
+        let pg = Paragraph::new(lines).style(Style::default().fg(tailwind::RED.c300));
+
+        let [area] = Layout::vertical([Constraint::Length(pg.line_count(area.width) as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Length(pg.line_width() as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        frame.render_widget(pg, area);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
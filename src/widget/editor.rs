@@ -0,0 +1,320 @@
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use super::Widget;
+use crate::events::{Broadcast, Event, Keypress};
+
+static TAB_WIDTH: usize = 2;
+
+fn byte_idx(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+/// A minimal multi-line text editor. Every printable key is inserted, so
+/// anything that needs to control the editor (saving, exiting) should be
+/// handled by the parent before dispatching here.
+pub struct Editor {
+    title: String,
+    lines: Vec<String>,
+    // What the editor started with, to tell whether anything has changed.
+    original: String,
+
+    // (row, col), where col is in chars and not bytes.
+    cursor: (usize, usize),
+    offset: Position,
+
+    // Shown below the content, for things like validation errors.
+    error: Option<String>,
+}
+
+#[bon::bon]
+impl Editor {
+    #[builder]
+    pub fn new(#[builder(into)] title: String, content: &str) -> Self {
+        let mut lines: Vec<_> = content.lines().map(String::from).collect();
+
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        let mut editor = Self {
+            title,
+            lines,
+            original: String::new(),
+            cursor: (0, 0),
+            offset: Position::default(),
+            error: None,
+        };
+
+        editor.original = editor.content();
+
+        editor
+    }
+
+    pub fn content(&self) -> String {
+        let mut content = self.lines.join("\n");
+        content.push('\n');
+
+        content
+    }
+
+    /// Whether the content is different from what the editor started with.
+    pub fn dirty(&self) -> bool {
+        self.content() != self.original
+    }
+
+    pub fn set_error(&mut self, error: Option<String>) {
+        self.error = error;
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines.get(row).map_or(0, |l| l.chars().count())
+    }
+
+    fn insert(&mut self, c: char) {
+        let (row, col) = self.cursor;
+        let line = &mut self.lines[row];
+
+        line.insert(byte_idx(line, col), c);
+        self.cursor.1 += 1;
+    }
+
+    // New lines keep the indentation of the current one, which is almost always
+    // what you want when editing yaml.
+    fn newline(&mut self) {
+        let (row, col) = self.cursor;
+        let line = &mut self.lines[row];
+
+        let rest = line.split_off(byte_idx(line, col));
+        let indent = line.chars().take_while(|c| *c == ' ').count();
+
+        self.lines
+            .insert(row + 1, format!("{}{rest}", " ".repeat(indent)));
+        self.cursor = (row + 1, indent);
+    }
+
+    // Pasted text is inserted as-is, without the indentation `newline` adds.
+    fn paste(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                let (row, col) = self.cursor;
+                let current = &mut self.lines[row];
+
+                let rest = current.split_off(byte_idx(current, col));
+                self.lines.insert(row + 1, rest);
+                self.cursor = (row + 1, 0);
+            }
+
+            line.chars().for_each(|c| self.insert(c));
+        }
+    }
+
+    fn backspace(&mut self) {
+        let (row, col) = self.cursor;
+
+        if col > 0 {
+            let line = &mut self.lines[row];
+            line.remove(byte_idx(line, col - 1));
+            self.cursor.1 -= 1;
+
+            return;
+        }
+
+        if row == 0 {
+            return;
+        }
+
+        let line = self.lines.remove(row);
+        let len = self.line_len(row - 1);
+        self.lines[row - 1].push_str(&line);
+        self.cursor = (row - 1, len);
+    }
+
+    fn delete(&mut self) {
+        let (row, col) = self.cursor;
+
+        if col < self.line_len(row) {
+            let line = &mut self.lines[row];
+            line.remove(byte_idx(line, col));
+        } else if row + 1 < self.lines.len() {
+            let next = self.lines.remove(row + 1);
+            self.lines[row].push_str(&next);
+        }
+    }
+
+    fn kill(&mut self) {
+        let (row, col) = self.cursor;
+
+        if col >= self.line_len(row) && row + 1 < self.lines.len() {
+            let next = self.lines.remove(row + 1);
+            self.lines[row].push_str(&next);
+
+            return;
+        }
+
+        let line = &mut self.lines[row];
+        line.truncate(byte_idx(line, col));
+    }
+
+    fn up(&mut self) {
+        self.cursor.0 = self.cursor.0.saturating_sub(1);
+        self.cursor.1 = self.cursor.1.min(self.line_len(self.cursor.0));
+    }
+
+    fn down(&mut self) {
+        self.cursor.0 = (self.cursor.0 + 1).min(self.lines.len() - 1);
+        self.cursor.1 = self.cursor.1.min(self.line_len(self.cursor.0));
+    }
+
+    fn left(&mut self) {
+        if self.cursor.1 > 0 {
+            self.cursor.1 -= 1;
+        } else if self.cursor.0 > 0 {
+            self.cursor.0 -= 1;
+            self.cursor.1 = self.line_len(self.cursor.0);
+        }
+    }
+
+    fn right(&mut self) {
+        if self.cursor.1 < self.line_len(self.cursor.0) {
+            self.cursor.1 += 1;
+        } else if self.cursor.0 + 1 < self.lines.len() {
+            self.cursor = (self.cursor.0 + 1, 0);
+        }
+    }
+
+    // Keep the cursor within the visible area.
+    #[allow(clippy::cast_possible_truncation)]
+    fn scroll(&mut self, area: Rect) {
+        let (row, col) = (self.cursor.0 as u16, self.cursor.1 as u16);
+
+        if row < self.offset.y {
+            self.offset.y = row;
+        } else if row >= self.offset.y.saturating_add(area.height) {
+            self.offset.y = row.saturating_sub(area.height.saturating_sub(1));
+        }
+
+        if col < self.offset.x {
+            self.offset.x = col;
+        } else if col >= self.offset.x.saturating_add(area.width) {
+            self.offset.x = col.saturating_sub(area.width.saturating_sub(1));
+        }
+    }
+
+    fn draw_error(&self, frame: &mut Frame, area: Rect) -> Rect {
+        let Some(error) = self.error.as_ref() else {
+            return area;
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let height = (error.lines().count() as u16).saturating_add(2).min(10);
+
+        let [area, error_area] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(height)]).areas(area);
+
+        frame.render_widget(Clear, error_area);
+        frame.render_widget(
+            Paragraph::new(error.as_str())
+                .wrap(Wrap { trim: false })
+                .style(Style::default().fg(tailwind::RED.c300))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Error")
+                        .border_style(Style::default().fg(tailwind::RED.c500)),
+                ),
+            error_area,
+        );
+
+        area
+    }
+}
+
+impl Widget for Editor {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match key {
+            Keypress::Printable(c) => self.insert(*c),
+            Keypress::HorizontalTab => (0..TAB_WIDTH).for_each(|_| self.insert(' ')),
+            Keypress::Enter => self.newline(),
+            Keypress::Paste(text) => self.paste(text),
+            Keypress::Backspace | Keypress::Delete => self.backspace(),
+            Keypress::Control('d') => self.delete(),
+            Keypress::Control('k') => self.kill(),
+            Keypress::CursorUp => self.up(),
+            Keypress::CursorDown => self.down(),
+            Keypress::CursorLeft => self.left(),
+            Keypress::CursorRight => self.right(),
+            Keypress::CursorHome | Keypress::Control('a') => self.cursor.1 = 0,
+            Keypress::End | Keypress::Control('e') => self.cursor.1 = self.line_len(self.cursor.0),
+            _ => return Ok(Broadcast::Ignored),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = self.draw_error(frame, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title.as_str())
+            .border_style(Style::default().fg(tailwind::BLUE.c500));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let gutter = (self.lines.len().to_string().len() + 1) as u16;
+        let [gutter_area, text_area] =
+            Layout::horizontal([Constraint::Length(gutter), Constraint::Fill(0)]).areas(inner);
+
+        self.scroll(text_area);
+
+        let start = usize::from(self.offset.y);
+        let visible = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(usize::from(text_area.height));
+
+        let (numbers, text): (Vec<_>, Vec<_>) = visible
+            .map(|(i, line)| {
+                (
+                    Line::from(Span::styled(
+                        format!("{:>width$} ", i + 1, width = usize::from(gutter) - 1),
+                        Style::default().fg(tailwind::GRAY.c600),
+                    )),
+                    Line::from(
+                        line.chars()
+                            .skip(usize::from(self.offset.x))
+                            .collect::<String>(),
+                    ),
+                )
+            })
+            .unzip();
+
+        frame.render_widget(Paragraph::new(numbers), gutter_area);
+        frame.render_widget(Paragraph::new(text), text_area);
+
+        frame.set_cursor_position(Position::new(
+            text_area.x + (self.cursor.1 as u16).saturating_sub(self.offset.x),
+            text_area.y + (self.cursor.0 as u16).saturating_sub(self.offset.y),
+        ));
+
+        Ok(())
+    }
+}
@@ -12,16 +12,32 @@ use ratatui::{
     Frame,
 };
 
-use super::{nav::move_cursor, viewport::Viewport, Placement, Widget};
+use super::{
+    nav::{move_cursor, wheel},
+    viewport::Viewport,
+    Placement, Widget,
+};
 use crate::events::{Broadcast, Event, StringError};
 
-#[derive(Default)]
 pub struct Error {
     msg: String,
+    border: Color,
 
     position: Position,
 }
 
+impl Error {
+    /// Show the outcome of something that worked, with the same interaction as
+    /// an error.
+    pub fn notice(msg: &str) -> Self {
+        Self {
+            msg: format!(" {msg}"),
+            border: Color::Green,
+            position: Position::default(),
+        }
+    }
+}
+
 impl From<Report> for Error {
     fn from(err: Report) -> Self {
         let Some(err) = err.downcast_ref::<StringError>() else {
@@ -36,13 +52,20 @@ impl From<String> for Error {
     fn from(msg: String) -> Self {
         Self {
             msg: format!("Error:{msg}"),
-            ..Default::default()
+            border: Color::Red,
+            position: Position::default(),
         }
     }
 }
 
 impl Widget for Error {
     fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(m) = wheel(event) {
+            self.position = m.saturating_adjust(self.position);
+
+            return Ok(Broadcast::Consumed);
+        }
+
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
@@ -60,7 +83,7 @@ impl Widget for Error {
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Red));
+            .border_style(Style::default().fg(self.border));
 
         let lines: Vec<_> = self
             .msg
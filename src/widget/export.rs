@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use ratatui::{
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::{Line, Text},
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Keypress},
+    exports::PREFIX,
+};
+
+static NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+/// Flatten rendered lines back into plain text, dropping any styling.
+pub fn to_string<'a>(lines: impl IntoIterator<Item = &'a Text<'a>>) -> String {
+    lines
+        .into_iter()
+        .flat_map(|text| text.lines.iter())
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Get content out of the dashboard. This is meant to be embedded in widgets
+/// that show a buffer of some kind.
+///
+/// - `y` copies the content to the client's clipboard (via OSC 52).
+/// - `S` saves the content so that it can be downloaded via SFTP from
+///   `/.kty/exports/<name>`.
+#[derive(Default)]
+pub struct Export {
+    notice: Option<(Instant, String)>,
+}
+
+impl Export {
+    /// Handle a keypress. `content` is only called if the key is one that
+    /// exports. `name` is used as the basis of the file name when saving and
+    /// has a timestamp appended to it.
+    pub fn dispatch(
+        &mut self,
+        key: &Keypress,
+        name: &str,
+        content: impl FnOnce() -> String,
+    ) -> Option<Broadcast> {
+        match key {
+            Keypress::Printable('y') => {
+                self.notice("Copied to clipboard".to_string());
+
+                Some(Broadcast::Copy(content()))
+            }
+            Keypress::Printable('S') => {
+                let (stem, ext) = name.rsplit_once('.').unwrap_or((name, "txt"));
+                let name = format!(
+                    "{}-{}.{ext}",
+                    stem.replace('/', "-"),
+                    Utc::now().format("%Y%m%dT%H%M%S")
+                );
+
+                self.notice(format!("Saved to {PREFIX}/{name}"));
+
+                Some(Broadcast::Save(name, content()))
+            }
+            _ => None,
+        }
+    }
+
+    fn notice(&mut self, msg: String) {
+        self.notice = Some((Instant::now(), msg));
+    }
+
+    /// Briefly show what happened over the bottom left of the parent's border.
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let Some((start, msg)) = self.notice.as_ref() else {
+            return;
+        };
+
+        if start.elapsed() > NOTICE_DURATION {
+            self.notice = None;
+
+            return;
+        }
+
+        let area = Rect {
+            x: area.x.saturating_add(1),
+            y: area.bottom().saturating_sub(1),
+            width: area.width.saturating_sub(2),
+            height: area.height.min(1),
+        };
+
+        frame.render_widget(
+            Line::from(format!(" {msg} ")).style(Style::default().fg(tailwind::GREEN.c400)),
+            area,
+        );
+    }
+}
@@ -10,7 +10,8 @@ use ratatui::{
 };
 
 use super::{
-    nav::{exit_keys, move_cursor, Movement, Shrink},
+    keymap,
+    nav::{move_cursor, Movement, Shrink},
     Widget,
 };
 use crate::events::{Broadcast, Event, Keypress};
@@ -64,7 +65,7 @@ impl Widget for Text {
         };
 
         match key {
-            exit_keys!() => {
+            key if keymap::exit(key) => {
                 self.content.try_borrow_mut()?.take();
 
                 return Ok(Broadcast::Exited);
@@ -78,6 +79,32 @@ impl Widget for Text {
 
                 return Ok(Broadcast::Consumed);
             }
+            // Newlines would submit half of what was pasted, they're dropped
+            // instead.
+            Keypress::Paste(text) => {
+                let text = text.replace(['\r', '\n'], "");
+
+                self.content
+                    .try_borrow_mut()?
+                    .get_or_insert_with(String::new)
+                    .insert_str(self.pos as usize, &text);
+                self.pos = self
+                    .pos
+                    .saturating_add(u16::try_from(text.len()).unwrap_or(u16::MAX));
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::ForwardDelete => {
+                let mut opt = self.content.try_borrow_mut()?;
+
+                if let Some(content) = opt.as_mut() {
+                    if (self.pos as usize) < content.len() {
+                        content.remove(self.pos as usize);
+                    }
+                }
+
+                return Ok(Broadcast::Consumed);
+            }
             Keypress::Backspace | Keypress::Delete => 'outer: {
                 if self.pos == 0 {
                     break 'outer;
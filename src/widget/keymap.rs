@@ -0,0 +1,312 @@
+use std::{cell::RefCell, rc::Rc};
+
+use eyre::{eyre, Result};
+use serde::Deserialize;
+
+use crate::events::{control, Keypress, Modifiers};
+
+/// Everything that can be bound to a key. Widgets with keys of their own, such
+/// as `<y>` to copy, aren't part of this.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Exit,
+    Left,
+    Right,
+    Up,
+    Down,
+    Top,
+    Bottom,
+    PageUp,
+    PageDown,
+    LineStart,
+    LineEnd,
+}
+
+impl Action {
+    const ALL: [Action; 11] = [
+        Action::Exit,
+        Action::Left,
+        Action::Right,
+        Action::Up,
+        Action::Down,
+        Action::Top,
+        Action::Bottom,
+        Action::PageUp,
+        Action::PageDown,
+        Action::LineStart,
+        Action::LineEnd,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Exit => "Close, or quit from the top",
+            Action::Left => "Switch tabs or scroll view left",
+            Action::Right => "Switch tabs or scroll view right",
+            Action::Up => "Navigate or scroll up one row",
+            Action::Down => "Navigate or scroll down one row",
+            Action::Top => "Navigate or scroll to the beginning",
+            Action::Bottom => "Navigate or scroll to the end",
+            Action::PageUp => "Navigate or scroll up one page",
+            Action::PageDown => "Navigate or scroll down one page",
+            Action::LineStart => "Jump to the beginning of the line",
+            Action::LineEnd => "Jump to the end of the line",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl Preset {
+    fn bindings(self) -> Vec<(Action, &'static [&'static str])> {
+        match self {
+            Preset::Default => vec![
+                (Action::Exit, &["esc", "ctrl-c", "ctrl-d"]),
+                (Action::Left, &["left", "h"]),
+                (Action::Right, &["right", "l"]),
+                (Action::Up, &["up", "k"]),
+                (Action::Down, &["down", "j"]),
+                (Action::Top, &["H"]),
+                (Action::Bottom, &["L"]),
+                (Action::PageUp, &["ctrl-b", "b", "pgup"]),
+                (Action::PageDown, &["space", "f", "ctrl-f", "pgdn"]),
+                (Action::LineStart, &["ctrl-a", "^", "home"]),
+                (Action::LineEnd, &["ctrl-e", "$", "end"]),
+            ],
+            Preset::Vim => vec![
+                (Action::Exit, &["esc", "ctrl-c"]),
+                (Action::Left, &["left", "h"]),
+                (Action::Right, &["right", "l"]),
+                (Action::Up, &["up", "k"]),
+                (Action::Down, &["down", "j"]),
+                (Action::Top, &["g"]),
+                (Action::Bottom, &["G"]),
+                (Action::PageUp, &["ctrl-b", "ctrl-u", "pgup"]),
+                (Action::PageDown, &["ctrl-f", "ctrl-d", "pgdn"]),
+                (Action::LineStart, &["0", "^", "home"]),
+                (Action::LineEnd, &["$", "end"]),
+            ],
+            Preset::Emacs => vec![
+                (Action::Exit, &["esc", "ctrl-g", "ctrl-c"]),
+                (Action::Left, &["left", "ctrl-b"]),
+                (Action::Right, &["right", "ctrl-f"]),
+                (Action::Up, &["up", "ctrl-p"]),
+                (Action::Down, &["down", "ctrl-n"]),
+                (Action::Top, &["alt-<"]),
+                (Action::Bottom, &["alt->"]),
+                (Action::PageUp, &["alt-v", "pgup"]),
+                (Action::PageDown, &["ctrl-v", "pgdn"]),
+                (Action::LineStart, &["ctrl-a", "home"]),
+                (Action::LineEnd, &["ctrl-e", "end"]),
+            ],
+        }
+    }
+}
+
+static NAMED: [(&str, Keypress); 15] = [
+    ("space", Keypress::Printable(' ')),
+    ("esc", Keypress::Escape),
+    ("enter", Keypress::Enter),
+    ("tab", Keypress::HorizontalTab),
+    ("backspace", Keypress::Delete),
+    ("delete", Keypress::ForwardDelete),
+    ("insert", Keypress::Insert),
+    ("up", Keypress::CursorUp),
+    ("down", Keypress::CursorDown),
+    ("left", Keypress::CursorLeft),
+    ("right", Keypress::CursorRight),
+    ("home", Keypress::CursorHome),
+    ("end", Keypress::End),
+    ("pgup", Keypress::PageUp),
+    ("pgdn", Keypress::PageDown),
+];
+
+/// Parse the name of a key, such as `j`, `ctrl-a`, `alt-v`, `pgdn` or `f5`.
+pub fn parse(name: &str) -> Result<Keypress> {
+    let mut chars = name.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(Keypress::Printable(c));
+    }
+
+    if let Some((_, key)) = NAMED.iter().find(|(n, _)| *n == name) {
+        return Ok(key.clone());
+    }
+
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        if (1..=12).contains(&n) {
+            return Ok(Keypress::Function(n));
+        }
+    }
+
+    let modified = |modifiers: Modifiers, rest: &str| {
+        parse(rest).map(|key| Keypress::Modified(modifiers, Box::new(key)))
+    };
+
+    if let Some(rest) = name.strip_prefix("ctrl-") {
+        let mut chars = rest.chars();
+
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => control(c).ok_or_else(|| eyre!("unknown key: {name}")),
+            _ => modified(Modifiers::CONTROL, rest),
+        };
+    }
+
+    if let Some(rest) = name.strip_prefix("alt-") {
+        return modified(Modifiers::ALT, rest);
+    }
+
+    if let Some(rest) = name.strip_prefix("shift-") {
+        return modified(Modifiers::SHIFT, rest);
+    }
+
+    Err(eyre!("unknown key: {name}"))
+}
+
+/// The name of a key, the reverse of `parse`.
+pub fn name(key: &Keypress) -> String {
+    if let Some((name, _)) = NAMED.iter().find(|(_, k)| k == key) {
+        return (*name).to_string();
+    }
+
+    match key {
+        Keypress::Printable(c) => c.to_string(),
+        Keypress::Control(c) => format!("ctrl-{c}"),
+        Keypress::Function(n) => format!("f{n}"),
+        Keypress::Modified(modifiers, key) => {
+            let prefix = [
+                (modifiers.control, "ctrl-"),
+                (modifiers.alt, "alt-"),
+                (modifiers.shift, "shift-"),
+                (modifiers.meta, "meta-"),
+            ]
+            .into_iter()
+            .filter_map(|(held, prefix)| held.then_some(prefix))
+            .collect::<String>();
+
+            format!("{prefix}{}", name(key))
+        }
+        key => ('a'..='z')
+            .find(|c| control(*c).as_ref() == Some(key))
+            .map_or_else(
+                || format!("{key:?}").to_lowercase(),
+                |c| format!("ctrl-{c}"),
+            ),
+    }
+}
+
+/// Which keys do what, see `Preset` for the defaults.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Keypress>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(Preset::default())
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        Self {
+            bindings: preset
+                .bindings()
+                .into_iter()
+                .map(|(action, keys)| {
+                    (
+                        action,
+                        keys.iter()
+                            .map(|key| parse(key).expect("presets are valid"))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Replace the keys for an action. Keys are only ever bound to one action,
+    /// so they're removed from anything else they were bound to.
+    pub fn bind(&mut self, action: Action, names: &[String]) -> Result<()> {
+        let keys = names
+            .iter()
+            .map(|name| parse(name))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Exiting is checked before text inputs get a chance to see the key,
+        // anything printable would make it impossible to type.
+        if action == Action::Exit && keys.iter().any(|k| matches!(k, Keypress::Printable(_))) {
+            return Err(eyre!("exit can't be bound to printable keys"));
+        }
+
+        for (_, bound) in &mut self.bindings {
+            bound.retain(|key| !keys.contains(key));
+        }
+
+        if let Some((_, bound)) = self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            *bound = keys;
+        }
+
+        Ok(())
+    }
+
+    pub fn action(&self, key: &Keypress) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(key))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys for the help page, such as `<ctrl-b> | <b>`, in the same order as
+    /// `Action`. Actions without any keys are left out.
+    pub fn help(&self) -> Vec<(Action, String)> {
+        Action::ALL
+            .iter()
+            .filter_map(|action| {
+                let (_, keys) = self.bindings.iter().find(|(a, _)| a == action)?;
+
+                (!keys.is_empty()).then(|| {
+                    (
+                        *action,
+                        keys.iter()
+                            .map(|key| format!("<{}>", name(key)))
+                            .collect::<Vec<_>>()
+                            .join(" | "),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+thread_local! {
+    // Keys are handled deep inside of widgets without any context, so they look
+    // the keymap up here. Each dashboard runs on its own thread, making this per
+    // session.
+    static CURRENT: RefCell<Rc<Keymap>> = RefCell::new(Rc::new(Keymap::default()));
+}
+
+/// Use `keymap` for everything dispatched on this thread.
+pub fn set(keymap: Keymap) {
+    CURRENT.with(|current| current.replace(Rc::new(keymap)));
+}
+
+pub fn current() -> Rc<Keymap> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+pub fn action(key: &Keypress) -> Option<Action> {
+    current().action(key)
+}
+
+/// Whether `key` closes the current widget.
+pub fn exit(key: &Keypress) -> bool {
+    action(key) == Some(Action::Exit)
+}
@@ -1,57 +1,411 @@
-use std::sync::Arc;
+mod structured;
+pub mod workload;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
 
 use ansi_to_tui::IntoText;
+use chrono::{DateTime, SecondsFormat, SubsecRound, TimeDelta, Utc};
 use color_eyre::{Section, SectionExt};
 use eyre::{eyre, Report, Result};
 use futures::{
     future::{try_join_all, BoxFuture},
     io::AsyncBufRead,
-    stream, AsyncBufReadExt, FutureExt, TryStreamExt,
+    stream, AsyncBufReadExt, FutureExt, StreamExt, TryStreamExt,
+};
+use k8s_openapi::api::{
+    authorization::v1::ResourceAttributes,
+    core::v1::{Node, Pod},
 };
-use k8s_openapi::api::core::v1::Pod;
 use kube::{api::LogParams, Api, ResourceExt};
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge, IntGauge};
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    style::{palette::tailwind, Style},
-    text::Text,
-    widgets::{Block, Borders, Paragraph},
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Color, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 use tokio::{
     sync::{mpsc, mpsc::UnboundedSender},
     task::JoinHandle,
 };
+use tokio_util::task::AbortOnDropHandle;
 
+use self::structured::{Fields, Filter};
 use super::{
-    nav::{move_cursor, BigPosition, Movement, Shrink},
+    export::{self, Export},
+    input::{self, Content},
+    keymap,
+    nav::{move_cursor, wheel, BigPosition, Movement, Shrink},
+    search::Search,
+    table,
     tabs::Tab,
     viewport::Viewport,
     Widget, WIDGET_VIEWS,
 };
 use crate::{
-    events::{Broadcast, Event},
+    events::{Broadcast, Event, Keypress},
     resources::{
+        authz,
         container::{Container, ContainerExt},
+        node,
         pod::PodExt,
+        store::Store,
     },
 };
 
+lazy_static! {
+    static ref LOG_BUFFER_BYTES: IntGauge = register_int_gauge!(
+        "log_buffer_bytes",
+        "Number of bytes held in log buffers across all sessions"
+    )
+    .unwrap();
+}
+
+// Maximum number of lines kept by a single log view. Once this is reached, the
+// oldest lines are dropped.
+static CAPACITY: usize = 10_000;
+// Number of lines that are always kept by a view, even when the memory limit
+// across all sessions has been reached.
+static MINIMUM: usize = 100;
+// Number of lines to fetch when scrolling back past the start of the buffer.
+static SCROLLBACK: usize = 500;
+static SCROLLBACK_BYTES: i64 = 16 * 1024 * 1024;
+// The only service on a node that is always there.
+static SERVICE: &str = "kubelet";
+
+static SOURCE_COLORS: [Color; 8] = [
+    tailwind::BLUE.c400,
+    tailwind::GREEN.c400,
+    tailwind::YELLOW.c400,
+    tailwind::PURPLE.c400,
+    tailwind::CYAN.c400,
+    tailwind::PINK.c400,
+    tailwind::ORANGE.c400,
+    tailwind::TEAL.c400,
+];
+
+pub struct Config {
+    pub tail_lines: Option<i64>,
+    pub since_seconds: Option<i64>,
+    pub memory_limit: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tail_lines: Some(1000),
+            since_seconds: None,
+            memory_limit: 256 * 1024 * 1024,
+        }
+    }
+}
+
+pub static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+fn bytes(size: usize) -> i64 {
+    i64::try_from(size).unwrap_or(i64::MAX)
+}
+
+// Lines are always fetched with timestamps so that it is possible to fetch the
+// lines that came before the start of the buffer.
+fn parse(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    line.split_once(' ')
+        .and_then(|(ts, content)| {
+            DateTime::parse_from_rfc3339(ts)
+                .ok()
+                .map(|ts| (Some(ts.with_timezone(&Utc)), content))
+        })
+        .unwrap_or((None, line))
+}
+
+// Pick a stable color for a source so that lines from the same source are easy
+// to pick out when they're interleaved.
+fn color(source: &str) -> Color {
+    let hash = source
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b.into()));
+
+    SOURCE_COLORS[hash % SOURCE_COLORS.len()]
+}
+
+/// A line of logs and where it came from.
+struct Record {
+    source: String,
+    line: String,
+}
+
+#[derive(Clone, Copy)]
+struct Format {
+    prefix: bool,
+    timestamps: bool,
+    structured: bool,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            prefix: false,
+            timestamps: false,
+            structured: true,
+        }
+    }
+}
+
+struct Entry {
+    record: Record,
+    timestamp: Option<DateTime<Utc>>,
+    // Parsed once, both rendering and filtering need them.
+    fields: Option<Fields>,
+}
+
+impl Entry {
+    fn size(&self) -> usize {
+        self.record.source.len()
+            + self.record.line.len()
+            + self.fields.as_ref().map_or(0, Fields::size)
+    }
+
+    fn content(&self) -> &str {
+        parse(self.record.line.as_str()).1
+    }
+
+    fn render(&self, format: Format) -> Text<'static> {
+        let content = self.content();
+
+        let structured = self.fields.as_ref().filter(|_| format.structured);
+
+        let mut text = if let Some(fields) = structured {
+            Text::from(fields.render())
+        } else {
+            content.into_text().unwrap_or_else(|err| {
+                tracing::debug!(err = ?err, "failed to parse log line");
+
+                Text::from(content.to_string())
+            })
+        };
+
+        let mut prefix = Vec::new();
+
+        if format.prefix {
+            prefix.push(Span::styled(
+                format!("{} ", self.record.source),
+                Style::default().fg(color(self.record.source.as_str())),
+            ));
+        }
+
+        if let Some(ts) = self.timestamp.filter(|_| format.timestamps) {
+            prefix.push(Span::styled(
+                format!("{} ", ts.to_rfc3339_opts(SecondsFormat::Millis, true)),
+                Style::default().fg(tailwind::GRAY.c500),
+            ));
+        }
+
+        if let Some(line) = text.lines.first_mut() {
+            line.spans.splice(0..0, prefix);
+        }
+
+        text
+    }
+}
+
+// The rendered lines are kept separately from their metadata so that they can
+// be handed directly to the `Viewport`. Only the entries that match the filter
+// are rendered, `shown` maps each rendered line back to its entry. Every entry
+// is accounted for in `LOG_BUFFER_BYTES` to enforce the memory limit across all
+// sessions.
+#[derive(Default)]
+struct Lines<'a> {
+    text: Vec<Text<'a>>,
+    shown: Vec<usize>,
+    entries: Vec<Entry>,
+    bytes: usize,
+    format: Format,
+    filter: Option<Filter>,
+}
+
+impl Lines<'_> {
+    /// Number of entries, regardless of whether they're shown.
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Index of the entry that is rendered at `line`.
+    fn entry_at(&self, line: usize) -> usize {
+        self.shown.get(line).copied().unwrap_or(self.len())
+    }
+
+    fn entry(record: Record) -> Entry {
+        let (timestamp, content) = parse(record.line.as_str());
+        let fields = Fields::parse(content);
+
+        Entry {
+            record,
+            timestamp,
+            fields,
+        }
+    }
+
+    fn accept(&self, entry: &Entry) -> bool {
+        self.filter.as_ref().map_or(true, |filter| {
+            filter.matches(entry.content(), entry.fields.as_ref())
+        })
+    }
+
+    fn push(&mut self, record: Record) {
+        let entry = Self::entry(record);
+
+        self.bytes += entry.size();
+        LOG_BUFFER_BYTES.add(bytes(entry.size()));
+
+        if self.accept(&entry) {
+            self.text.push(entry.render(self.format));
+            self.shown.push(self.entries.len());
+        }
+
+        self.entries.push(entry);
+    }
+
+    /// Returns the number of lines that are shown from `records`.
+    fn prepend(&mut self, records: Vec<Record>) -> usize {
+        let entries: Vec<_> = records.into_iter().map(Self::entry).collect();
+
+        let size = entries.iter().map(Entry::size).sum();
+        self.bytes += size;
+        LOG_BUFFER_BYTES.add(bytes(size));
+
+        let len = entries.len();
+
+        let (text, shown): (Vec<_>, Vec<_>) = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.accept(e))
+            .map(|(i, e)| (e.render(self.format), i))
+            .unzip();
+
+        let added = text.len();
+
+        for idx in &mut self.shown {
+            *idx += len;
+        }
+
+        self.text.splice(0..0, text);
+        self.shown.splice(0..0, shown);
+        self.entries.splice(0..0, entries);
+
+        added
+    }
+
+    /// Drop the first `n` entries. Returns the number of shown lines that were
+    /// dropped with them.
+    fn evict(&mut self, n: usize) -> usize {
+        let n = n.min(self.len());
+
+        let size = self.entries.drain(..n).map(|e| e.size()).sum();
+
+        self.bytes -= size;
+        LOG_BUFFER_BYTES.sub(bytes(size));
+
+        let removed = self.shown.partition_point(|idx| *idx < n);
+
+        self.text.drain(..removed);
+        self.shown.drain(..removed);
+
+        for idx in &mut self.shown {
+            *idx -= n;
+        }
+
+        removed
+    }
+
+    fn clear(&mut self) {
+        self.evict(self.len());
+    }
+
+    fn oldest(&self) -> Option<DateTime<Utc>> {
+        self.entries.iter().find_map(|e| e.timestamp)
+    }
+
+    fn render(&mut self) {
+        (self.text, self.shown) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.accept(e))
+            .map(|(i, e)| (e.render(self.format), i))
+            .unzip();
+    }
+
+    fn format(&mut self, format: Format) {
+        self.format = format;
+        self.render();
+    }
+
+    fn filter(&mut self, filter: Option<Filter>) {
+        self.filter = filter;
+        self.render();
+    }
+}
+
+impl Drop for Lines<'_> {
+    fn drop(&mut self) {
+        LOG_BUFFER_BYTES.sub(bytes(self.bytes));
+    }
+}
+
+// Lines from before the start of the buffer and whether the start of the logs
+// has been reached.
+type History = (Vec<Record>, bool);
+
+enum Target {
+    Pod(Arc<Pod>),
+    // Every pod in the store, as they come and go.
+    Pods {
+        name: String,
+        store: Arc<Store<Pod>>,
+    },
+    // The kubelet's logs, these can't be followed so they're fetched once.
+    Node(Arc<Node>),
+}
+
 pub struct Log<'a> {
+    client: kube::Client,
+    target: Target,
+
+    // `None` shows the logs for every container in the pod.
+    container: Option<String>,
+    previous: bool,
+    picker: Option<table::Table<Arc<Pod>>>,
+
+    filter_input: Option<input::Text>,
+    filter: Content,
+    last_filter: Option<String>,
+    invalid_filter: bool,
+
     task: Option<JoinHandle<Result<()>>>,
+    history: Option<JoinHandle<Result<History>>>,
 
-    rx: mpsc::UnboundedReceiver<Option<String>>,
-    buffer: Vec<Text<'a>>,
+    rx: mpsc::UnboundedReceiver<Record>,
+    buffer: Lines<'a>,
+    // Set once the start of the logs has been loaded into the buffer.
+    complete: bool,
 
     position: BigPosition,
+    search: Search,
+    export: Export,
 }
 
 // TODO:
-// - Make this work with with anything that has pods (e.g. deployments,
-//   stateful).
-// - Allow for searching within the logs. Feels like it should be ala fzf and
-//   jump to the text + highlight it.
-// - Only fetch the most recent X lines, on scroll-back, fetch more.
 // - Convert into something more general, this is fundamentally the same thing
 //   as the yaml widget - but without the syntax highlighting. There should
 //   probably be an "editor" widget that takes something to populate the lines.
@@ -61,73 +415,559 @@ impl Log<'_> {
     pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
         WIDGET_VIEWS.pod.log.inc();
 
-        let (tx, rx) = mpsc::unbounded_channel();
+        Self::with_target(client, Target::Pod(pod))
+    }
+
+    /// Aggregate the logs of every pod in `store`. Each line is prefixed with
+    /// the pod and container that it came from.
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip_all, fields(activity = "workload.logs"))]
+    pub fn aggregate(client: kube::Client, name: String, store: Arc<Store<Pod>>) -> Self {
+        WIDGET_VIEWS.workload.log.inc();
+
+        Self::with_target(client, Target::Pods { name, store })
+    }
 
-        // TODO: this should be a function call.
-        let task = tokio::spawn(log_stream(
+    /// The kubelet logs of `node`, see `node::logs` for what the node needs.
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip_all, fields(activity = "node.logs"))]
+    pub fn node(client: kube::Client, node: Arc<Node>) -> Self {
+        WIDGET_VIEWS.node.log.inc();
+
+        Self::with_target(client, Target::Node(node))
+    }
+
+    fn with_target(client: kube::Client, target: Target) -> Self {
+        let (_, rx) = mpsc::unbounded_channel();
+
+        let mut log = Self {
             client,
-            pod,
-            tx,
-            LogParams {
-                follow: true,
-                pretty: true,
-                previous: true,
-                ..Default::default()
-            },
-            true,
-        ));
+            target,
+
+            container: None,
+            previous: false,
+            picker: None,
+
+            filter_input: None,
+            filter: Content::default(),
+            last_filter: None,
+            invalid_filter: false,
+
+            task: None,
+            history: None,
 
-        Self {
-            task: Some(task),
             rx,
-            buffer: Vec::new(),
+            buffer: Lines::default(),
+            complete: false,
 
             position: BigPosition::default(),
-        }
+            search: Search::default(),
+            export: Export::default(),
+        };
+
+        log.start();
+
+        log
     }
 
     // TODO: This should be a macro. Ideally, it'd be a trait with a default impl
     // but I don't think it is possible to do generically.
     pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        let requires = vec![authz::namespaced(
+            "",
+            "pods/log",
+            "get",
+            &pod.namespace().unwrap_or_default(),
+        )];
+
         Tab::builder()
             .name(name)
             .constructor(Box::new(move || {
                 Log::new(client.clone(), pod.clone()).boxed().into()
             }))
+            .requires(requires)
             .build()
     }
 
+    pub fn node_tab(name: String, client: kube::Client, node: Arc<Node>) -> Tab {
+        let requires = vec![ResourceAttributes {
+            resource: Some("nodes".to_string()),
+            subresource: Some("proxy".to_string()),
+            verb: Some("get".to_string()),
+            name: Some(node.name_any()),
+            ..Default::default()
+        }];
+
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || {
+                Log::node(client.clone(), node.clone()).boxed().into()
+            }))
+            .requires(requires)
+            .build()
+    }
+
+    fn pods(&self) -> Vec<Arc<Pod>> {
+        match &self.target {
+            Target::Pod(pod) => vec![pod.clone()],
+            Target::Pods { store, .. } => store.items(None),
+            Target::Node(_) => Vec::new(),
+        }
+    }
+
+    // Whether the task is expected to keep running.
+    fn follows(&self) -> bool {
+        !self.previous && !matches!(self.target, Target::Node(_))
+    }
+
+    fn containers(&self, pod: &Pod) -> Vec<Container> {
+        pod.containers(None)
+            .into_iter()
+            .filter(|c| {
+                self.container
+                    .as_ref()
+                    .map_or(true, |name| c.name_any() == *name)
+            })
+            .collect()
+    }
+
+    // (Re)start streaming logs with the current container and previous
+    // selection. Everything currently in the buffer is dropped.
+    fn start(&mut self) {
+        self.stop();
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let config = config();
+
+        let params = LogParams {
+            follow: true,
+            pretty: true,
+            previous: self.previous,
+            timestamps: true,
+            tail_lines: config.tail_lines,
+            since_seconds: config.since_seconds,
+            ..Default::default()
+        };
+
+        let prefix = match &self.target {
+            Target::Pod(pod) => {
+                let containers = self.containers(pod);
+                let prefix = containers.len() > 1;
+
+                self.task = Some(tokio::spawn(log_stream(
+                    self.client.clone(),
+                    pod.clone(),
+                    containers,
+                    tx,
+                    params,
+                    false,
+                )));
+
+                prefix
+            }
+            Target::Pods { store, .. } => {
+                self.task = Some(tokio::spawn(aggregate_stream(
+                    self.client.clone(),
+                    store.clone(),
+                    tx,
+                    params,
+                )));
+
+                true
+            }
+            Target::Node(node) => {
+                self.task = Some(tokio::spawn(node_stream(
+                    self.client.clone(),
+                    node.name_any(),
+                    tx,
+                    config
+                        .tail_lines
+                        .unwrap_or(i64::try_from(CAPACITY).unwrap_or(i64::MAX)),
+                )));
+
+                false
+            }
+        };
+
+        self.rx = rx;
+        self.buffer.clear();
+        self.buffer.format(Format {
+            prefix,
+            ..self.buffer.format
+        });
+        self.complete = matches!(self.target, Target::Node(_))
+            || (config.tail_lines.is_none() && config.since_seconds.is_none());
+
+        self.position = BigPosition::default();
+        self.search.invalidate();
+    }
+
+    fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+
+        if let Some(task) = self.history.take() {
+            task.abort();
+        }
+    }
+
+    fn select(&mut self, container: Option<String>) {
+        if container == self.container {
+            return;
+        }
+
+        self.container = container;
+        self.start();
+    }
+
     fn update(&mut self) -> u32 {
         let mut i = 0;
 
-        while let Ok(line) = self.rx.try_recv() {
-            if let Some(line) = line {
-                match line.into_text() {
-                    Ok(l) => self.buffer.push(l),
-                    Err(err) => {
-                        tracing::debug!(err = ?err, "failed to parse log line");
-                        self.buffer.push(Text::from(line));
-                    }
-                }
-            } else {
-                self.buffer = Vec::new();
-            }
+        while let Ok(record) = self.rx.try_recv() {
+            self.buffer.push(record);
 
             i += 1;
         }
 
+        self.evict();
+
         i
     }
+
+    // Drop lines from the start of the buffer. To keep under the per-view
+    // capacity, only lines above the current view are dropped. This allows the
+    // buffer to grow while scrolling back, until the view moves. When over the
+    // memory limit for all sessions, lines are dropped regardless.
+    fn evict(&mut self) {
+        let len = self.buffer.len();
+        let y: usize = self.position.y.shrink();
+        let top = self.buffer.entry_at(
+            self.search
+                .lines()
+                .map_or(y, |lines| lines.get(y).copied().unwrap_or(usize::MAX)),
+        );
+
+        let mut n = if len > CAPACITY + CAPACITY / 10 {
+            (len - CAPACITY).min(top)
+        } else {
+            0
+        };
+
+        if LOG_BUFFER_BYTES.get() > bytes(config().memory_limit) && len > MINIMUM {
+            n = n.max((len - MINIMUM).min(len / 10));
+        }
+
+        if n == 0 {
+            return;
+        }
+
+        let lines = self.buffer.evict(n);
+        let matches = self.search.shift(lines);
+
+        self.complete = false;
+        self.position.y = self.position.y.saturating_sub(
+            if self.search.lines().is_some() {
+                matches
+            } else {
+                lines
+            }
+            .shrink(),
+        );
+    }
+
+    // Trying to go above the first line fetches older logs.
+    fn scroll(&mut self, y: i32) {
+        if y < 0 && self.position.y == 0 {
+            self.scrollback();
+        }
+
+        self.position.y = self.position.y.saturating_add_signed(y);
+    }
+
+    fn scrollback(&mut self) {
+        if self.complete || self.history.is_some() {
+            return;
+        }
+
+        let Some(before) = self.buffer.oldest() else {
+            return;
+        };
+
+        let sources = self
+            .pods()
+            .into_iter()
+            .map(|pod| {
+                let containers = self.containers(&pod);
+
+                (pod, containers)
+            })
+            .collect();
+
+        self.history = Some(tokio::spawn(history(
+            self.client.clone(),
+            sources,
+            matches!(self.target, Target::Pods { .. }),
+            self.previous,
+            before,
+            SCROLLBACK,
+        )));
+    }
+
+    fn prepend(&mut self) -> Result<()> {
+        if !self.history.as_ref().map_or(false, JoinHandle::is_finished) {
+            return Ok(());
+        }
+
+        let task = self.history.take().expect("task is finished");
+
+        let (records, complete) = futures::executor::block_on(async move { task.await? })?;
+
+        let added = self.buffer.prepend(records);
+        self.complete = complete;
+        self.search.invalidate();
+
+        // Keep the view on the same lines, the older ones are now above it.
+        if self.search.lines().is_none() {
+            self.position.y = self.position.y.saturating_add(added.shrink());
+        }
+
+        Ok(())
+    }
+
+    // Exports what is being shown, so when search is filtering lines only the
+    // matches are included.
+    fn export(&mut self, key: &Keypress) -> Option<Broadcast> {
+        let name = match &self.target {
+            Target::Pod(pod) => format!(
+                "{}-{}.log",
+                pod.namespace().unwrap_or_default(),
+                pod.name_any()
+            ),
+            Target::Pods { name, .. } => format!("{name}.log"),
+            Target::Node(node) => format!("{}-{SERVICE}.log", node.name_any()),
+        };
+
+        let (search, text) = (&self.search, &self.buffer.text);
+
+        self.export.dispatch(key, &name, || match search.lines() {
+            Some(lines) => export::to_string(lines.iter().filter_map(|i| text.get(*i))),
+            None => export::to_string(text),
+        })
+    }
+
+    fn title(&self) -> Line<'static> {
+        let mut title = match &self.target {
+            Target::Pod(_) => format!(
+                " {} ",
+                self.container.as_deref().unwrap_or("all containers")
+            ),
+            Target::Pods { name, .. } => format!(" {name} "),
+            Target::Node(_) => format!(" {SERVICE} "),
+        };
+
+        if self.previous {
+            title.push_str("(previous) ");
+        }
+
+        if let Some(filter) = self.last_filter.as_ref().filter(|f| !f.is_empty()) {
+            title.push_str(format!("| {filter} ").as_str());
+
+            if self.invalid_filter {
+                title.push_str("(invalid) ");
+            }
+        }
+
+        Line::from(title)
+    }
+
+    // Filtering happens on the lines that have already been fetched, there's no
+    // need to go back to the API.
+    fn refilter(&mut self) {
+        let query = self.filter.borrow().clone();
+
+        if query == self.last_filter {
+            return;
+        }
+
+        let filter = query
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::parse::<Filter>)
+            .transpose();
+
+        self.last_filter = query;
+
+        let Ok(filter) = filter else {
+            self.invalid_filter = true;
+
+            return;
+        };
+
+        self.invalid_filter = false;
+        self.buffer.filter(filter);
+        self.search.invalidate();
+    }
+
+    fn draw_filter(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(input) = self.filter_input.as_mut() else {
+            return Ok(());
+        };
+
+        let [_, area] =
+            Layout::vertical([Constraint::Fill(0), input.placement().vertical]).areas(area);
+
+        input.draw(frame, area)
+    }
+
+    fn draw_picker(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let (Some(picker), Target::Pod(pod)) = (self.picker.as_mut(), &self.target) else {
+            return Ok(());
+        };
+
+        let height = u16::try_from(pod.containers(None).len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(3);
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        frame.render_widget(Clear, area);
+
+        picker.draw(frame, area)
+    }
 }
 
 impl Widget for Log<'_> {
-    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(picker) = self.picker.as_mut() {
+            match picker.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => {
+                    self.picker = None;
+
+                    // The picker is only available when showing a single pod.
+                    if let Target::Pod(pod) = &self.target {
+                        let container = pod.containers(None).get(idx).map(ContainerExt::name_any);
+
+                        self.select(container);
+                    }
+                }
+                Broadcast::Ignored if event.key().is_some_and(keymap::exit) => {
+                    self.picker = None;
+                }
+                _ => {}
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if let Some(input) = self.filter_input.as_mut() {
+            match input.dispatch(event, buffer, area)? {
+                Broadcast::Exited => self.filter_input = None,
+                Broadcast::Ignored if matches!(event.key(), Some(Keypress::Enter)) => {
+                    self.filter_input = None;
+                }
+                _ => {}
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match self.search.dispatch(event, buffer, area)? {
+            Broadcast::Selected(line) => {
+                self.position.y = line.shrink();
+
+                return Ok(Broadcast::Consumed);
+            }
+            Broadcast::Ignored => {}
+            result => return Ok(result),
+        }
+
+        if let Some(Movement::Y(y)) = wheel(event) {
+            self.scroll(y);
+
+            return Ok(Broadcast::Consumed);
+        }
+
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
 
+        if let Some(result) = self.export(key) {
+            return Ok(result);
+        }
+
+        match key {
+            Keypress::Printable('c') => {
+                let Target::Pod(pod) = &self.target else {
+                    return Ok(Broadcast::Ignored);
+                };
+
+                self.picker = Some(
+                    table::Table::builder()
+                        .title("Containers")
+                        .items(pod.clone())
+                        .build(),
+                );
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('C') => {
+                self.select(None);
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('t') => {
+                self.buffer.format(Format {
+                    timestamps: !self.buffer.format.timestamps,
+                    ..self.buffer.format
+                });
+                self.search.invalidate();
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('p') if !matches!(self.target, Target::Node(_)) => {
+                self.previous = !self.previous;
+                self.start();
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('s') => {
+                self.buffer.format(Format {
+                    structured: !self.buffer.format.structured,
+                    ..self.buffer.format
+                });
+                self.search.invalidate();
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('|') => {
+                self.filter_input = Some(
+                    input::Text::builder()
+                        .title("Filter")
+                        .content(self.filter.clone())
+                        .border_style(Style::default().fg(tailwind::BLUE.c500))
+                        .build(),
+                );
+
+                return Ok(Broadcast::Consumed);
+            }
+            _ => {}
+        }
+
         if let Some(Movement::Y(y)) = move_cursor(key, area) {
-            self.position.y = self.position.y.saturating_add_signed(y);
+            self.scroll(y);
 
             return Ok(Broadcast::Consumed);
         }
@@ -137,31 +977,37 @@ impl Widget for Log<'_> {
 
     #[allow(clippy::cast_possible_truncation)]
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.prepend()?;
+        self.refilter();
+
         let lines = self.update();
 
+        self.search
+            .update(&self.buffer.text, self.position.y.shrink());
+        let len = self.search.len(self.buffer.text.len());
+
         if self
             .position
             .y
             .saturating_add(lines)
             .saturating_add(area.height.into())
-            >= self.buffer.len() as u32
+            >= len as u32
         {
             // See warning for move_cursor for why this somewhat ridiculous dance occurs.
             self.position.y = i32::MAX as u32;
         }
 
-        self.position.y = self.position.y.clamp(
-            0,
-            self.buffer
-                .len()
-                .saturating_sub(usize::from(area.height))
-                .shrink(),
-        );
+        self.position.y = self
+            .position
+            .y
+            .clamp(0, len.saturating_sub(usize::from(area.height)).shrink());
 
         if self.task.as_ref().map_or(false, JoinHandle::is_finished) {
             let task = self.task.take().expect("task is finished");
 
             match futures::executor::block_on(async move { task.await? }) {
+                // Logs for previous containers and nodes are complete and don't follow.
+                Ok(()) if !self.follows() => {}
                 Ok(()) => return Err(eyre!("Log task finished unexpectedly")),
                 Err(err) => {
                     let Some(kube::Error::Api(resp)) = err.downcast_ref::<kube::Error>() else {
@@ -175,14 +1021,26 @@ impl Widget for Log<'_> {
             }
         }
 
+        let mut block = Block::default().borders(Borders::ALL).title(self.title());
+
+        if self.history.is_some() {
+            block = block.title(Line::from(" fetching older logs ").centered());
+        }
+
         let result = Viewport::builder()
-            .block(Block::default().borders(Borders::ALL))
-            .buffer(&self.buffer)
+            .block(block)
+            .buffer(&self.buffer.text)
+            .maybe_lines(self.search.lines())
+            .maybe_highlight(self.search.highlight())
             .view(self.position)
             .build()
-            .draw(frame, area);
+            .draw(frame, area)
+            .and_then(|()| self.search.draw(frame, area))
+            .map(|()| self.export.draw(frame, area))
+            .and_then(|()| self.draw_filter(frame, area))
+            .and_then(|()| self.draw_picker(frame, area));
 
-        if self.task.is_none() {
+        if self.task.is_none() && self.follows() {
             frame.render_widget(
                 Paragraph::new("Log stream ended, come back to restart")
                     .style(Style::default().fg(tailwind::RED.c300))
@@ -197,83 +1055,415 @@ impl Widget for Log<'_> {
 
 impl Drop for Log<'_> {
     fn drop(&mut self) {
-        if let Some(task) = self.task.as_ref() {
-            task.abort();
-        }
+        self.stop();
     }
 }
 
-#[tracing::instrument(skip(client, pod, tx, params))]
-fn log_stream<'a>(
+// Fetch up to `count` lines that were logged before `before`. The API doesn't
+// provide a way to read backwards, so this looks at a window of time before
+// `before` which grows until enough lines have been found or the window
+// reaches the start of the oldest pod. Returns the lines and whether the start
+// of the logs was reached.
+async fn history(
     client: kube::Client,
-    pod: Arc<Pod>,
-    tx: UnboundedSender<Option<String>>,
+    sources: Vec<(Arc<Pod>, Vec<Container>)>,
+    qualified: bool,
+    previous: bool,
+    before: DateTime<Utc>,
+    count: usize,
+) -> Result<History> {
+    let start = sources
+        .iter()
+        .map(|(pod, _)| pod.creation_timestamp().map(|ts| ts.0))
+        .min()
+        .flatten();
+
+    let containers: Vec<_> = sources
+        .into_iter()
+        .flat_map(|(pod, containers)| containers.into_iter().map(move |c| (pod.clone(), c)))
+        .collect();
+
+    let mut window = TimeDelta::minutes(1);
+
+    loop {
+        let since = before - window;
+        let mut found = Vec::new();
+
+        for (pod, container) in &containers {
+            let pod_client = Api::<Pod>::namespaced(client.clone(), &pod.namespace().unwrap());
+
+            let lines = page(&pod_client, container, previous, since, before, count).await?;
+
+            found.extend(lines.into_iter().map(|(ts, line)| {
+                (
+                    ts,
+                    Record {
+                        source: source(pod, container, qualified),
+                        line,
+                    },
+                )
+            }));
+        }
+
+        let reached_start = start.map_or(true, |start| since <= start);
+
+        if found.len() >= count || reached_start {
+            found.sort_by_key(|(ts, _)| *ts);
+
+            let complete = reached_start && found.len() <= count;
+            let skip = found.len().saturating_sub(count);
+
+            return Ok((
+                found
+                    .into_iter()
+                    .skip(skip)
+                    .map(|(_, record)| record)
+                    .collect(),
+                complete,
+            ));
+        }
+
+        window = window * 4;
+    }
+}
+
+// The last `count` lines of `container` between `since` and `before`. Requests
+// are limited to `SCROLLBACK_BYTES` and the API returns the start of the
+// window when there's more than that, so this pages forward from the last line
+// it saw until reaching `before`. Otherwise, there would be a gap between what
+// was fetched and the start of the buffer.
+async fn page(
+    client: &Api<Pod>,
+    container: &Container,
+    previous: bool,
+    mut since: DateTime<Utc>,
+    before: DateTime<Utc>,
+    count: usize,
+) -> Result<VecDeque<(DateTime<Utc>, String)>> {
+    let mut found = VecDeque::new();
+    let mut last: Option<DateTime<Utc>> = None;
+
+    loop {
+        let Some(stream) = container_stream(
+            client,
+            container,
+            LogParams {
+                container: Some(container.name_any()),
+                previous,
+                since_time: Some(since),
+                timestamps: true,
+                limit_bytes: Some(SCROLLBACK_BYTES),
+                ..Default::default()
+            },
+        )
+        .await?
+        else {
+            return Ok(found);
+        };
+
+        let mut lines = stream.lines();
+        let mut read = 0;
+        let mut batch = Vec::new();
+        let mut reached = false;
+
+        while let Some(line) = lines.try_next().await? {
+            read += line.len() + 1;
+
+            let (Some(ts), _) = parse(line.as_str()) else {
+                continue;
+            };
+
+            if ts >= before {
+                reached = true;
+
+                break;
+            }
+
+            // Pages overlap by up to a second, `since_time` has no more
+            // precision than that.
+            if last.map_or(false, |last| ts <= last) {
+                continue;
+            }
+
+            batch.push((ts, line));
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let truncated = !reached && read >= SCROLLBACK_BYTES as usize;
+
+        // The limit can cut the last line off part way through, it is read
+        // again as part of the next page.
+        if truncated {
+            batch.pop();
+        }
+
+        let next = batch.last().map(|(ts, _)| *ts);
+
+        for line in batch {
+            found.push_back(line);
+
+            if found.len() > count {
+                found.pop_front();
+            }
+        }
+
+        let Some(next) = next.filter(|_| truncated) else {
+            return Ok(found);
+        };
+
+        // More than `SCROLLBACK_BYTES` within a single second can't be paged
+        // through, there is nothing to do but take what there is.
+        if next.trunc_subsecs(0) <= since.trunc_subsecs(0) {
+            return Ok(found);
+        }
+
+        last = Some(next);
+        since = next.trunc_subsecs(0);
+    }
+}
+
+fn source(pod: &Pod, container: &Container, qualified: bool) -> String {
+    if qualified {
+        format!("{}/{}", pod.name_any(), container.name_any())
+    } else {
+        container.name_any()
+    }
+}
+
+// A stream for one of the pods in `aggregate_stream`.
+struct Follow {
+    version: Option<String>,
     params: LogParams,
-    retry: bool,
-) -> BoxFuture<'a, Result<()>> {
-    async move {
-        let pod_client = Api::<Pod>::namespaced(client.clone(), &pod.namespace().unwrap());
+    // Timestamp of the last line sent, so that a stream that ends can be
+    // restarted from there.
+    last: Arc<Mutex<Option<DateTime<Utc>>>>,
+    // Dropping a `JoinHandle` leaves the task running, this aborts when the
+    // aggregate itself is aborted.
+    task: AbortOnDropHandle<Result<()>>,
+}
 
-        let containers = try_join_all(pod.containers(None).iter().map(|c| {
-            let mut params = params.clone();
-            params.container = Some(c.name_any());
+impl Follow {
+    fn start(
+        client: kube::Client,
+        pod: Arc<Pod>,
+        tx: UnboundedSender<Record>,
+        params: LogParams,
+        after: Option<DateTime<Utc>>,
+        version: Option<String>,
+    ) -> Self {
+        let last = Arc::new(Mutex::new(after));
 
-            container_stream(&pod_client, c, params)
-        }))
-        .await?;
+        let task = {
+            let params = params.clone();
+            let last = last.clone();
 
-        let mut all_logs = stream::select_all(containers.into_iter().map(AsyncBufReadExt::lines));
+            AbortOnDropHandle::new(tokio::spawn(async move {
+                let (inner, mut rx) = mpsc::unbounded_channel::<Record>();
+                let containers = pod.containers(None);
+
+                let forward = async {
+                    while let Some(record) = rx.recv().await {
+                        if let (Some(ts), _) = parse(&record.line) {
+                            // `since_time` only has second precision, so a
+                            // restarted stream repeats part of what was shown.
+                            if after.map_or(false, |after| ts <= after) {
+                                continue;
+                            }
+
+                            *last.lock().unwrap() = Some(ts);
+                        }
+
+                        tx.send(record)?;
+                    }
+
+                    Ok::<_, Report>(())
+                };
 
-        while let Some(line) = all_logs.try_next().await? {
-            tx.send(Some(line))?;
+                let (result, forwarded) = tokio::join!(
+                    log_stream(client, pod.clone(), containers, inner, params, true),
+                    forward,
+                );
+
+                forwarded?;
+
+                if let Err(err) = result {
+                    tracing::debug!(pod = pod.name_any(), err = ?err, "stream failed");
+
+                    tx.send(Record {
+                        source: pod.name_any(),
+                        line: format!("Unable to stream logs: {err}"),
+                    })?;
+                }
+
+                Ok(())
+            }))
+        };
+
+        Self {
+            version,
+            params,
+            last,
+            task,
         }
+    }
+}
+
+// Follow the logs of every pod in the store. Pods are picked up as they appear
+// and their streams are stopped when they go away. Streams that end, for
+// example because a container hasn't started yet, are retried once the pod has
+// changed.
+#[tracing::instrument(skip_all)]
+async fn aggregate_stream(
+    client: kube::Client,
+    store: Arc<Store<Pod>>,
+    tx: UnboundedSender<Record>,
+    params: LogParams,
+) -> Result<()> {
+    store.wait_until_ready().await?;
+
+    let mut streams: HashMap<String, Follow> = HashMap::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    let mut initial = true;
+
+    loop {
+        interval.tick().await;
+
+        let pods = store.items(None);
 
-        tracing::debug!(pod = pod.name_any(), "stream ended");
+        // Removed streams are aborted as they're dropped.
+        streams.retain(|uid, _| pods.iter().any(|pod| pod.uid().as_ref() == Some(uid)));
 
-        // The api server is a little finicky about streaming previous logs. It is
-        // possible that the request succeeds, but also that the stream finishes
-        // immediately. If this happens, retry without the previous flag. Because we're
-        // not clearing out the buffer on the widget side of things, the logs will be
-        // duplicated. Before the restart, we send a simple message to tell the user
-        // what happened.
-        if retry {
-            // Reset the contents of the buffer.
-            tx.send(None)?;
-            tx.send(Some(
-                "Stream terminated, retrying without previous logs".to_string(),
-            ))?;
-            tx.send(Some("-".repeat(20).to_string()))?;
+        for pod in pods {
+            let Some(uid) = pod.uid() else {
+                continue;
+            };
 
-            let mut new_params = params.clone();
-            new_params.previous = false;
+            let version = pod.resource_version();
+
+            let (params, after) = match streams.get(&uid) {
+                Some(follow) if !follow.task.is_finished() || follow.version == version => continue,
+                // Pick up from the last line shown for this pod, anything
+                // written since the stream ended would be lost otherwise.
+                Some(follow) => match *follow.last.lock().unwrap() {
+                    Some(last) => {
+                        let mut params = follow.params.clone();
+                        params.tail_lines = None;
+                        params.since_seconds = None;
+                        params.since_time = Some(last);
+
+                        (params, Some(last))
+                    }
+                    None => (follow.params.clone(), None),
+                },
+                // Pods that show up after the initial set are new, show everything.
+                None if !initial => {
+                    let mut params = params.clone();
+                    params.tail_lines = None;
+                    params.since_seconds = None;
 
-            return log_stream(client, pod, tx, new_params, false).await;
+                    (params, None)
+                }
+                None => (params.clone(), None),
+            };
+
+            streams.insert(
+                uid,
+                Follow::start(client.clone(), pod, tx.clone(), params, after, version),
+            );
         }
 
-        Ok(())
+        initial = false;
     }
-    .boxed()
 }
 
+#[tracing::instrument(skip(client, pod, containers, tx, params))]
+async fn log_stream(
+    client: kube::Client,
+    pod: Arc<Pod>,
+    containers: Vec<Container>,
+    tx: UnboundedSender<Record>,
+    params: LogParams,
+    qualified: bool,
+) -> Result<()> {
+    let pod_client = Api::<Pod>::namespaced(client, &pod.namespace().unwrap());
+
+    let streams = try_join_all(containers.iter().map(|c| {
+        let mut params = params.clone();
+        params.container = Some(c.name_any());
+
+        container_stream(&pod_client, c, params)
+    }))
+    .await?;
+
+    let mut sources = Vec::new();
+
+    for (container, stream) in containers.iter().zip(streams) {
+        let source = source(&pod, container, qualified);
+
+        let Some(stream) = stream else {
+            tx.send(Record {
+                source,
+                line: "No previous logs for this container".to_string(),
+            })?;
+
+            continue;
+        };
+
+        sources.push(
+            stream
+                .lines()
+                .map_ok(move |line| Record {
+                    source: source.clone(),
+                    line,
+                })
+                .boxed(),
+        );
+    }
+
+    let mut all_logs = stream::select_all(sources);
+
+    while let Some(record) = all_logs.try_next().await? {
+        tx.send(record)?;
+    }
+
+    tracing::debug!(pod = pod.name_any(), "stream ended");
+
+    Ok(())
+}
+
+async fn node_stream(
+    client: kube::Client,
+    node: String,
+    tx: UnboundedSender<Record>,
+    tail: i64,
+) -> Result<()> {
+    for line in node::logs(client, &node, SERVICE, tail).await?.lines() {
+        tx.send(Record {
+            source: SERVICE.to_string(),
+            line: line.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+// Returns `None` when previous logs were requested and the container has not
+// been restarted.
 fn container_stream<'a>(
     client: &'a Api<Pod>,
     container: &'a Container,
     params: LogParams,
-) -> BoxFuture<'a, Result<impl AsyncBufRead>> {
+) -> BoxFuture<'a, Result<Option<impl AsyncBufRead>>> {
     async move {
         match client.log_stream(&container.pod_name(), &params).await {
-            Ok(stream) => Ok(stream),
+            Ok(stream) => Ok(Some(stream)),
             Err(err) => {
                 let kube::Error::Api(resp) = &err else {
                     return Err(Report::new(err));
                 };
 
-                if resp.message.contains("previous terminated") {
-                    let mut new_params = params.clone();
-
-                    new_params.previous = false;
-
-                    return container_stream(client, container, new_params).await;
+                if params.previous && resp.message.contains("previous terminated") {
+                    return Ok(None);
                 }
 
                 Err(eyre!(err))
@@ -0,0 +1,422 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use eyre::{eyre, Result};
+use ratatui::{
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::{Map, Value};
+
+static LEVEL_KEYS: &[&str] = &["level", "lvl", "severity", "log.level"];
+static TIMESTAMP_KEYS: &[&str] = &["time", "ts", "timestamp", "@timestamp"];
+static MESSAGE_KEYS: &[&str] = &["msg", "message"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl FromStr for Level {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.to_lowercase().as_str() {
+            "trace" | "10" => Self::Trace,
+            "debug" | "20" => Self::Debug,
+            "info" | "information" | "notice" | "30" => Self::Info,
+            "warn" | "warning" | "40" => Self::Warn,
+            "error" | "err" | "50" => Self::Error,
+            "fatal" | "critical" | "crit" | "panic" | "dpanic" | "60" => Self::Fatal,
+            x => return Err(eyre!("unknown level: {x}")),
+        })
+    }
+}
+
+impl Level {
+    fn style(self) -> Style {
+        match self {
+            Self::Trace => Style::default().fg(tailwind::GRAY.c500),
+            Self::Debug => Style::default().fg(tailwind::BLUE.c400),
+            Self::Info => Style::default().fg(tailwind::GREEN.c400),
+            Self::Warn => Style::default().fg(tailwind::YELLOW.c400),
+            Self::Error => Style::default().fg(tailwind::RED.c400),
+            Self::Fatal => Style::default()
+                .fg(tailwind::RED.c600)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Trace => "TRACE",
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+            Self::Fatal => "FATAL",
+        }
+    }
+}
+
+/// The key/value pairs from a structured (JSON or logfmt) log line, in the
+/// order they appeared.
+pub struct Fields(Vec<(String, String)>);
+
+impl Fields {
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+
+        if line.starts_with('{') {
+            return from_json(line);
+        }
+
+        from_logfmt(line)
+    }
+
+    /// Roughly how much memory the fields take up.
+    pub fn size(&self) -> usize {
+        self.0.iter().map(|(k, v)| k.len() + v.len()).sum()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn find<'a>(&'a self, keys: &[&'static str]) -> Option<(&'static str, &'a str)> {
+        keys.iter().find_map(|key| self.get(key).map(|v| (*key, v)))
+    }
+
+    pub fn level(&self) -> Option<Level> {
+        self.find(LEVEL_KEYS).and_then(|(_, v)| v.parse().ok())
+    }
+
+    /// Render as `LEVEL timestamp message key=value ...`, with the level
+    /// colored by severity.
+    pub fn render(&self) -> Line<'static> {
+        let level = self.find(LEVEL_KEYS);
+        let timestamp = self.find(TIMESTAMP_KEYS);
+        let message = self.find(MESSAGE_KEYS);
+
+        let mut spans = Vec::new();
+
+        if let Some((_, raw)) = level {
+            let span = match raw.parse::<Level>() {
+                Ok(level) => Span::styled(format!("{:<5} ", level.as_str()), level.style()),
+                Err(_) => Span::raw(format!("{raw} ")),
+            };
+
+            spans.push(span);
+        }
+
+        if let Some((_, ts)) = timestamp {
+            spans.push(Span::styled(
+                format!("{ts} "),
+                Style::default().fg(tailwind::GRAY.c500),
+            ));
+        }
+
+        if let Some((_, msg)) = message {
+            spans.push(Span::raw(format!("{msg} ")));
+        }
+
+        let used: Vec<_> = [level, timestamp, message]
+            .into_iter()
+            .flatten()
+            .map(|(k, _)| k)
+            .collect();
+
+        for (k, v) in self.0.iter().filter(|(k, _)| !used.contains(&k.as_str())) {
+            spans.push(Span::styled(
+                format!("{k}="),
+                Style::default().fg(tailwind::GRAY.c500),
+            ));
+            spans.push(Span::raw(format!("{v} ")));
+        }
+
+        Line::from(spans)
+    }
+}
+
+fn flatten(prefix: Option<&str>, obj: Map<String, Value>, out: &mut Vec<(String, String)>) {
+    for (k, v) in obj {
+        let key = prefix.map_or_else(|| k.clone(), |p| format!("{p}.{k}"));
+
+        match v {
+            Value::Object(obj) => flatten(Some(key.as_str()), obj, out),
+            Value::String(s) => out.push((key, s)),
+            v => out.push((key, v.to_string())),
+        }
+    }
+}
+
+fn from_json(line: &str) -> Option<Fields> {
+    let obj = serde_json::from_str::<Map<String, Value>>(line).ok()?;
+
+    let mut fields = Vec::new();
+    flatten(None, obj, &mut fields);
+
+    Some(Fields(fields))
+}
+
+// Every token must be a `key=value` pair for the line to be considered logfmt.
+// Otherwise, plain lines that happen to contain an `=` would be mangled.
+fn from_logfmt(line: &str) -> Option<Fields> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+            if c == '"' {
+                return None;
+            }
+
+            key.push(c);
+        }
+
+        if key.is_empty() || chars.next() != Some('=') {
+            return None;
+        }
+
+        let mut value = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => value.push(chars.next()?),
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+
+        fields.push((key, value));
+    }
+
+    (fields.len() >= 2).then_some(Fields(fields))
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+impl Op {
+    fn test(self, ord: Ordering) -> bool {
+        match self {
+            Self::Eq => ord.is_eq(),
+            Self::Ne => ord.is_ne(),
+            Self::Gt => ord.is_gt(),
+            Self::Ge => ord.is_ge(),
+            Self::Lt => ord.is_lt(),
+            Self::Le => ord.is_le(),
+            Self::Contains => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Clause {
+    Field { key: String, op: Op, value: String },
+    Text(String),
+}
+
+impl Clause {
+    fn matches(&self, fields: Option<&Fields>, raw: &str) -> bool {
+        let (key, op, value) = match self {
+            Self::Text(text) => return raw.contains(text.as_str()),
+            Self::Field { key, op, value } => (key, *op, value),
+        };
+
+        let Some(fields) = fields else {
+            return false;
+        };
+
+        // `level` matches whatever key the line uses for its level and compares
+        // by severity.
+        if key == "level" {
+            if let (Some(lhs), Ok(rhs)) = (fields.level(), value.parse::<Level>()) {
+                return op.test(lhs.cmp(&rhs));
+            }
+        }
+
+        let Some(actual) = fields.get(key) else {
+            return matches!(op, Op::Ne);
+        };
+
+        if let Op::Contains = op {
+            return actual.contains(value.as_str());
+        }
+
+        let ord = match (actual.parse::<f64>(), value.parse::<f64>()) {
+            (Ok(lhs), Ok(rhs)) => lhs.partial_cmp(&rhs).unwrap_or(Ordering::Equal),
+            _ => actual.cmp(value.as_str()),
+        };
+
+        op.test(ord)
+    }
+}
+
+/// A set of clauses, separated by whitespace, that must all match a line. A
+/// clause is either `<key><op><value>`, where `op` is one of `=`, `!=`, `>`,
+/// `>=`, `<`, `<=` or `~` (contains), or plain text that must appear in the
+/// line. For example: `level>=warn user_id=123`.
+#[derive(Debug)]
+pub struct Filter(Vec<Clause>);
+
+impl FromStr for Filter {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        static OPS: &[(&str, Op)] = &[
+            ("!=", Op::Ne),
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("=", Op::Eq),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("~", Op::Contains),
+        ];
+
+        s.split_whitespace()
+            .map(|token| {
+                let Some((idx, token_op, op)) = OPS
+                    .iter()
+                    .filter_map(|(sym, op)| token.find(sym).map(|idx| (idx, *sym, *op)))
+                    .min_by_key(|(idx, sym, _)| (*idx, usize::MAX - sym.len()))
+                else {
+                    return Ok(Clause::Text(token.to_string()));
+                };
+
+                if idx == 0 {
+                    return Err(eyre!("missing key in {token}"));
+                }
+
+                Ok(Clause::Field {
+                    key: token[..idx].to_string(),
+                    op,
+                    value: token[idx + token_op.len()..].to_string(),
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl Filter {
+    /// Whether `line`, with `fields` already parsed from it, matches.
+    pub fn matches(&self, line: &str, fields: Option<&Fields>) -> bool {
+        self.0.iter().all(|c| c.matches(fields, line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(filter: &str, line: &str) -> bool {
+        filter
+            .parse::<Filter>()
+            .unwrap()
+            .matches(line, Fields::parse(line).as_ref())
+    }
+
+    #[test]
+    fn json_fields() {
+        let fields = Fields::parse(r#"{"level":"info","req":{"id":7,"ok":true}}"#).unwrap();
+
+        assert_eq!(fields.get("level"), Some("info"));
+        assert_eq!(fields.get("req.id"), Some("7"));
+        assert_eq!(fields.get("req.ok"), Some("true"));
+    }
+
+    #[test]
+    fn logfmt_fields() {
+        let fields = Fields::parse(r#"lvl=warn msg="a \"quoted\" value" n=3"#).unwrap();
+
+        assert_eq!(fields.level(), Some(Level::Warn));
+        assert_eq!(fields.get("msg"), Some(r#"a "quoted" value"#));
+        assert_eq!(fields.get("n"), Some("3"));
+    }
+
+    #[test]
+    fn plain_lines() {
+        assert!(Fields::parse("GET /index.html 200").is_none());
+        assert!(Fields::parse("retrying with backoff=2s").is_none());
+        assert!(Fields::parse(r#"key="unterminated"#).is_none());
+    }
+
+    #[test]
+    fn operators() {
+        let parse = |s: &str| format!("{:?}", s.parse::<Filter>().unwrap());
+
+        assert!(parse("a!=b").contains("Ne"));
+        assert!(parse("a>=b").contains("Ge"));
+        assert!(parse("a<=b").contains("Le"));
+        assert!(parse("a=b").contains("Eq"));
+        assert!(parse("a>b").contains("Gt"));
+        assert!(parse("a<b").contains("Lt"));
+        assert!(parse("a~b").contains("Contains"));
+        // The first operator in the token wins, values can have others in them.
+        assert!(parse("url=a>b").contains(r#"key: "url", op: Eq, value: "a>b""#));
+        assert!(parse("plain").contains(r#"Text("plain")"#));
+    }
+
+    #[test]
+    fn missing_key() {
+        assert!("=value".parse::<Filter>().is_err());
+        assert!(">=warn".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn levels() {
+        let line = r#"{"severity":"ERROR","msg":"boom"}"#;
+
+        assert!(matches("level>=warn", line));
+        assert!(matches("level=error", line));
+        assert!(!matches("level<warn", line));
+    }
+
+    #[test]
+    fn numbers_and_strings() {
+        let line = "msg=done duration=120 user=alice";
+
+        assert!(matches("duration>20", line));
+        assert!(!matches("duration<20", line));
+        assert!(matches("user~lic duration>=120", line));
+        assert!(matches("missing!=x", line));
+        assert!(!matches("missing=x", line));
+        assert!(matches("done", line));
+        assert!(!matches("user=bob", line));
+    }
+
+    #[test]
+    fn fields_only_match_structured_lines() {
+        assert!(!matches("level=info", "plain info line"));
+        assert!(matches("info", "plain info line"));
+    }
+}
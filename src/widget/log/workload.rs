@@ -0,0 +1,193 @@
+use eyre::Result;
+use futures::FutureExt;
+use k8s_openapi::api::core::v1::Pod;
+use kube::{runtime::watcher, Api};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::Log;
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{store::Store, workload},
+    widget::{
+        error::Error, input, keymap, propagate, tabs::Tab, view::Element, Widget, WIDGET_VIEWS,
+    },
+};
+
+/// Pick a workload, or label selector, to show the aggregated logs for. See
+/// `resources::workload::Workload` for the format.
+pub struct Workload {
+    client: kube::Client,
+    content: input::Text,
+
+    task: Option<JoinHandle<Result<(workload::Workload, String)>>>,
+    log: Option<Log<'static>>,
+    error: Option<Error>,
+}
+
+impl Workload {
+    pub fn new(client: kube::Client) -> Self {
+        WIDGET_VIEWS.workload.cmd.inc();
+
+        Self {
+            client,
+            content: input::Text::builder().title("Workload").build(),
+
+            task: None,
+            log: None,
+            error: None,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, terminal: bool) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || {
+                Element::builder()
+                    .widget(Self::new(client.clone()).boxed())
+                    .terminal(terminal)
+                    .build()
+            }))
+            .build()
+    }
+
+    fn resolve(&mut self) -> Result<()> {
+        let workload: workload::Workload = self
+            .content
+            .content()
+            .borrow()
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .parse()?;
+
+        let client = self.client.clone();
+
+        self.task = Some(tokio::spawn(async move {
+            let selector = workload.selector(client).await?;
+
+            Ok((workload, selector))
+        }));
+
+        Ok(())
+    }
+
+    fn update(&mut self) {
+        if !self.task.as_ref().map_or(false, JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(Some(result)) = self.task.take().map(FutureExt::now_or_never) else {
+            return;
+        };
+
+        match result.map_err(eyre::Report::from).and_then(|r| r) {
+            Ok((workload, selector)) => {
+                let (store, _) = Store::watch(
+                    Api::<Pod>::namespaced(self.client.clone(), workload.namespace()),
+                    watcher::Config::default().labels(selector.as_str()),
+                );
+
+                self.log = Some(Log::aggregate(
+                    self.client.clone(),
+                    workload.to_string(),
+                    store,
+                ));
+            }
+            Err(err) => self.error = Some(Error::from(err)),
+        }
+    }
+}
+
+impl Widget for Workload {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(error) = self.error.as_mut() {
+            propagate!(error.dispatch(event, buffer, area), self.error = None);
+        }
+
+        if let Some(log) = self.log.as_mut() {
+            propagate!(log.dispatch(event, buffer, area));
+
+            if event.key().is_some_and(keymap::exit) {
+                self.log = None;
+
+                return Ok(Broadcast::Consumed);
+            }
+
+            return Ok(Broadcast::Ignored);
+        }
+
+        propagate!(self.content.dispatch(event, buffer, area));
+
+        if let Some(Keypress::Enter) = event.key() {
+            if let Err(err) = self.resolve() {
+                self.error = Some(Error::from(err));
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        Ok(Broadcast::Ignored)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.update();
+
+        if let Some(log) = self.log.as_mut() {
+            log.draw(frame, area)?;
+        } else {
+            let border = Block::default().borders(Borders::ALL);
+
+            let inner = border.inner(area);
+            frame.render_widget(border, area);
+
+            let [_, input, help, _] = Layout::vertical([
+                Constraint::Fill(0),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Fill(0),
+            ])
+            .areas(inner);
+
+            let [_, input, _] = Layout::horizontal([
+                Constraint::Max(10),
+                Constraint::Fill(0),
+                Constraint::Max(10),
+            ])
+            .areas(input);
+
+            self.content.draw(frame, input)?;
+
+            let hint = if self.task.is_some() {
+                "Looking up pods..."
+            } else {
+                "deployments/<namespace>/<name>, statefulsets/..., services/... or \
+                 labels/<namespace>/<selector>"
+            };
+
+            frame.render_widget(
+                Line::from(hint)
+                    .style(Style::default().fg(tailwind::GRAY.c500))
+                    .centered(),
+                help,
+            );
+        }
+
+        if let Some(error) = self.error.as_mut() {
+            error.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        u16::from(self.log.is_some())
+    }
+}
@@ -1,6 +1,10 @@
 use ratatui::layout::{Position, Rect};
 
-use crate::events::Keypress;
+use super::keymap::{self, Action};
+use crate::events::{Event, Keypress, MouseKind};
+
+// Lines moved for each notch of the scroll wheel.
+static WHEEL_LINES: i32 = 3;
 
 pub enum Movement {
     X(i32),
@@ -116,37 +120,32 @@ impl Shrink<(u16, u16)> for BigPosition {
 
 // Retrieve a (x, y) tuple of how far to move the cursor. Takes the key pressed
 // and the area to move within. Returns i16, so use saturating_add_signed() to
-// avoid overflow.
+// avoid overflow. Keys come from the session's keymap.
 //
 // WARNING: This works with i16, if you're setting something to u16:MAX, going
 // to the first element won't work - it'll take 2 times to get there.
 #[allow(clippy::cast_possible_wrap)]
 pub fn move_cursor(key: &Keypress, area: Rect) -> Option<Movement> {
-    match key {
-        Keypress::CursorLeft | Keypress::Printable('h') => Some(Movement::X(-1)),
-        Keypress::CursorRight | Keypress::Printable('l') => Some(Movement::X(1)),
-        Keypress::CursorUp | Keypress::Printable('k') => Some(Movement::Y(-1)),
-        Keypress::CursorDown | Keypress::Printable('j') => Some(Movement::Y(1)),
-        Keypress::Printable('H') => Some(Movement::Y(-i32::MAX)),
-        Keypress::Printable('L') => Some(Movement::Y(i32::MAX)),
-        Keypress::Printable('b') | Keypress::Control('b') => {
-            Some(Movement::Y(-i32::from(area.height)))
-        }
-        Keypress::Printable(' ' | 'f') | Keypress::Control('f') => {
-            Some(Movement::Y(i32::from(area.height)))
-        }
-        Keypress::Printable('^') | Keypress::Control('a') => Some(Movement::X(-i32::MAX)),
-        Keypress::Printable('$') | Keypress::Control('e') => Some(Movement::X(i32::MAX)),
-        _ => None,
+    match keymap::action(key)? {
+        Action::Left => Some(Movement::X(-1)),
+        Action::Right => Some(Movement::X(1)),
+        Action::Up => Some(Movement::Y(-1)),
+        Action::Down => Some(Movement::Y(1)),
+        Action::Top => Some(Movement::Y(-i32::MAX)),
+        Action::Bottom => Some(Movement::Y(i32::MAX)),
+        Action::PageUp => Some(Movement::Y(-i32::from(area.height))),
+        Action::PageDown => Some(Movement::Y(i32::from(area.height))),
+        Action::LineStart => Some(Movement::X(-i32::MAX)),
+        Action::LineEnd => Some(Movement::X(i32::MAX)),
+        Action::Exit => None,
     }
 }
 
-/// Add to match key {} to handle exiting the widget.
-#[macro_export]
-macro_rules! exit_keys {
-    () => {
-        Keypress::Escape | Keypress::Control('c' | 'd')
-    };
+// The scroll wheel as a movement, for widgets that scroll their content.
+pub fn wheel(event: &Event) -> Option<Movement> {
+    match event.mouse()?.kind {
+        MouseKind::ScrollUp => Some(Movement::Y(-WHEEL_LINES)),
+        MouseKind::ScrollDown => Some(Movement::Y(WHEEL_LINES)),
+        _ => None,
+    }
 }
-
-pub use exit_keys;
@@ -1,15 +1,21 @@
+pub mod capacity;
+pub mod shell;
+
 use std::sync::Arc;
 
 use eyre::{eyre, Result};
-use k8s_openapi::api::core::v1::Node;
+use k8s_openapi::api::{authorization::v1::ResourceAttributes, core::v1::Node};
 use kube::ResourceExt;
 use ratatui::{buffer::Buffer, layout::Rect, Frame};
 use tokio::sync::oneshot;
 
+use self::{capacity::Capacity, shell::Shell};
 use super::{
+    action::Actions,
+    keymap,
     loading::Loading,
-    nav::exit_keys,
-    propagate, table,
+    log::Log,
+    pod, propagate, table,
     tabs::{Tab, TabbedView},
     view::{Element, View},
     yaml::Yaml,
@@ -17,12 +23,16 @@ use super::{
 };
 use crate::{
     events::{Broadcast, Event, Keypress},
-    resources::store::Store,
+    resources::{authz::Authz, store::Store},
 };
 
 pub struct List {
     view: View,
     is_ready: oneshot::Receiver<()>,
+    ready: bool,
+    // Routes can arrive before the store has anything in it, they're held on
+    // to until it is ready.
+    route: Option<Vec<String>>,
 }
 
 #[bon::bon]
@@ -30,13 +40,18 @@ impl List {
     #[allow(clippy::blocks_in_conditions)]
     #[tracing::instrument(skip_all, fields(activity = "node.list"))]
     #[builder]
-    pub fn new(client: kube::Client) -> Self {
+    pub fn new(client: kube::Client, authz: Authz) -> Self {
         WIDGET_VIEWS.node.list.inc();
 
         let (nodes, is_ready) = Store::<Node>::new(client.clone());
         let table = table::Filtered::builder()
             .table(table::Table::builder().items(nodes.clone()).build())
-            .constructor(Detail::from_store(client, nodes))
+            .constructor(Detail::from_store(
+                client.clone(),
+                authz.clone(),
+                nodes.clone(),
+            ))
+            .actions(Actions::from_nodes(client, authz, nodes))
             .build();
 
         let widgets = vec![
@@ -50,28 +65,53 @@ impl List {
         Self {
             view: View::builder().widgets(widgets).build(),
             is_ready,
+            ready: false,
+            route: None,
         }
     }
 
-    pub fn tab(name: String, client: kube::Client, terminal: bool) -> Tab {
+    pub fn tab(name: String, client: kube::Client, authz: Authz, terminal: bool) -> Tab {
         Tab::builder()
             .name(name)
             .constructor(Box::new(move || {
                 Element::builder()
-                    .widget(Self::builder().client(client.clone()).build().boxed())
+                    .widget(
+                        Self::builder()
+                            .client(client.clone())
+                            .authz(authz.clone())
+                            .build()
+                            .boxed(),
+                    )
                     .terminal(terminal)
                     .build()
             }))
+            .requires(vec![ResourceAttributes {
+                resource: Some("nodes".to_string()),
+                verb: Some("list".to_string()),
+                ..Default::default()
+            }])
             .build()
     }
 }
 
 impl Widget for List {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let (Event::Goto(route), false) = (event, self.ready) {
+            self.route = Some(route.clone());
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if self.ready {
+            if let Some(route) = self.route.take() {
+                self.view.dispatch(&Event::Goto(route), buffer, area)?;
+            }
+        }
+
         propagate!(self.view.dispatch(event, buffer, area));
 
         match event.key().unwrap_or(&Keypress::Null) {
-            exit_keys!() => Ok(Broadcast::Exited),
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
             _ => Ok(Broadcast::Ignored),
         }
     }
@@ -79,6 +119,7 @@ impl Widget for List {
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         if let Ok(()) = self.is_ready.try_recv() {
             self.view.pop();
+            self.ready = true;
         }
 
         self.view.draw(frame, area)
@@ -90,25 +131,80 @@ impl Widget for List {
 }
 
 pub struct Detail {
+    client: kube::Client,
+    authz: Authz,
+    node: Arc<Node>,
+
     view: TabbedView,
+    actions: Option<Actions>,
 }
 
 #[bon::bon]
 impl Detail {
     #[builder]
-    #[allow(unused_variables, clippy::needless_pass_by_value)]
-    pub fn new(client: kube::Client, node: Arc<Node>) -> Self {
+    pub fn new(client: kube::Client, authz: Authz, node: Arc<Node>) -> Self {
         WIDGET_VIEWS.node.detail.inc();
 
+        let name = node.name_any();
+        let pods = {
+            let client = client.clone();
+            let authz = authz.clone();
+
+            Tab::builder()
+                .name("Pods".to_string())
+                .constructor(Box::new(move || {
+                    pod::List::on_node(client.clone(), authz.clone(), &name)
+                        .boxed()
+                        .into()
+                }))
+                .requires(vec![ResourceAttributes {
+                    resource: Some("pods".to_string()),
+                    verb: Some("list".to_string()),
+                    ..Default::default()
+                }])
+                .build()
+        };
+
+        let mut tabs = vec![
+            Yaml::tab(
+                "YAML".to_string(),
+                client.clone(),
+                authz.clone(),
+                node.clone(),
+            ),
+            pods,
+            Capacity::tab("Capacity".to_string(), client.clone(), node.clone()),
+            Log::node_tab("Logs".to_string(), client.clone(), node.clone()),
+        ];
+
+        if shell::enabled() {
+            tabs.push(Shell::tab(
+                "Shell".to_string(),
+                client.clone(),
+                node.clone(),
+            ));
+        }
+
         let view = TabbedView::builder()
-            .tabs(vec![Yaml::tab("YAML".to_string(), node.clone())])
+            .tabs(tabs)
             .title(vec!["nodes".to_string(), node.name_any()])
+            .authz(authz.clone())
             .build();
 
-        Self { view }
+        Self {
+            client,
+            authz,
+            node,
+            view,
+            actions: None,
+        }
     }
 
-    pub fn from_store(client: kube::Client, store: Arc<Store<Node>>) -> table::DetailFn {
+    pub fn from_store(
+        client: kube::Client,
+        authz: Authz,
+        store: Arc<Store<Node>>,
+    ) -> table::DetailFn {
         Box::new(move |idx, filter| {
             let node = store
                 .get(idx, filter)
@@ -116,6 +212,7 @@ impl Detail {
 
             Ok(Detail::builder()
                 .client(client.clone())
+                .authz(authz.clone())
                 .node(node)
                 .build()
                 .boxed())
@@ -125,16 +222,35 @@ impl Detail {
 
 impl Widget for Detail {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(actions) = self.actions.as_mut() {
+            propagate!(actions.dispatch(event, buffer, area), self.actions = None);
+        }
+
         propagate!(self.view.dispatch(event, buffer, area));
 
         match event.key().unwrap_or(&Keypress::Null) {
-            exit_keys!() => Ok(Broadcast::Exited),
+            Keypress::Printable('a') => {
+                self.actions = Some(Actions::node(
+                    self.client.clone(),
+                    self.authz.clone(),
+                    &self.node,
+                ));
+
+                Ok(Broadcast::Consumed)
+            }
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
             _ => Ok(Broadcast::Ignored),
         }
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        self.view.draw(frame, area)
+        self.view.draw(frame, area)?;
+
+        if let Some(actions) = self.actions.as_mut() {
+            actions.draw(frame, area)?;
+        }
+
+        Ok(())
     }
 
     fn zindex(&self) -> u16 {
@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use itertools::Itertools;
+use k8s_openapi::api::{
+    authorization::v1::ResourceAttributes,
+    core::v1::{Node, NodeCondition, Pod},
+};
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+use tokio::sync::oneshot::{self, error::TryRecvError};
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        node::{self, Usage},
+        quantity,
+        store::Store,
+    },
+    widget::{keymap, loading::Loading, table::RowStyle, tabs::Tab, Widget},
+};
+
+/// What the pods on a node have asked for compared to what the node has, along
+/// with the node's conditions and taints.
+pub struct Capacity {
+    node: Arc<Node>,
+    pods: Arc<Store<Pod>>,
+    is_ready: Option<oneshot::Receiver<()>>,
+}
+
+#[bon::bon]
+impl Capacity {
+    #[builder]
+    pub fn new(client: kube::Client, node: Arc<Node>) -> Self {
+        let (pods, is_ready) = node::pods(client, &node.name_any());
+
+        Self {
+            node,
+            pods,
+            is_ready: Some(is_ready),
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, node: Arc<Node>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || {
+                Self::builder()
+                    .client(client.clone())
+                    .node(node.clone())
+                    .build()
+                    .boxed()
+                    .into()
+            }))
+            .requires(vec![ResourceAttributes {
+                resource: Some("pods".to_string()),
+                verb: Some("list".to_string()),
+                ..Default::default()
+            }])
+            .build()
+    }
+
+    fn usage(&self) -> Table<'static> {
+        let pods = self.pods.items(None);
+
+        let percent = |value: f64, total: f64| {
+            if total == 0.0 {
+                String::new()
+            } else {
+                format!(" ({:.0}%)", value / total * 100.0)
+            }
+        };
+
+        let row = |name: &'static str, usage: &Usage, fmt: fn(f64) -> String| {
+            Row::new(vec![
+                name.to_string(),
+                fmt(usage.allocatable),
+                format!(
+                    "{}{}",
+                    fmt(usage.requests),
+                    percent(usage.requests, usage.allocatable)
+                ),
+                format!(
+                    "{}{}",
+                    fmt(usage.limits),
+                    percent(usage.limits, usage.allocatable)
+                ),
+            ])
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let count = pods.iter().filter(|pod| node::is_active(pod)).count() as f64;
+        let allocatable = Usage::new(&self.node, &pods, "pods").allocatable;
+
+        Table::new(
+            vec![
+                row("CPU", &Usage::new(&self.node, &pods, "cpu"), quantity::cpu),
+                row(
+                    "Memory",
+                    &Usage::new(&self.node, &pods, "memory"),
+                    quantity::memory,
+                ),
+                Row::new(vec![
+                    "Pods".to_string(),
+                    format!("{allocatable}"),
+                    format!("{count}{}", percent(count, allocatable)),
+                    String::new(),
+                ]),
+            ],
+            [
+                Constraint::Max(10),
+                Constraint::Max(15),
+                Constraint::Max(20),
+                Constraint::Max(20),
+            ],
+        )
+        .header(header(["", "Allocatable", "Requests", "Limits"]))
+        .block(block("Resources"))
+    }
+
+    fn conditions(&self) -> Table<'static> {
+        let style = RowStyle::default();
+
+        let rows = self
+            .node
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|condition| {
+                Row::new(vec![
+                    condition.type_.clone(),
+                    condition.status.clone(),
+                    condition.reason.clone().unwrap_or_default(),
+                    condition.message.clone().unwrap_or_default(),
+                ])
+                .style(if is_healthy(condition) {
+                    style.healthy
+                } else {
+                    style.unhealthy
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Table::new(
+            rows,
+            [
+                Constraint::Max(20),
+                Constraint::Max(8),
+                Constraint::Max(30),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header(["Type", "Status", "Reason", "Message"]))
+        .block(block("Conditions"))
+    }
+
+    fn taints(&self) -> Paragraph<'static> {
+        let taints = self
+            .node
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.taints.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|taint| {
+                let key = taint
+                    .value
+                    .as_ref()
+                    .map_or(taint.key.clone(), |value| format!("{}={value}", taint.key));
+
+                Line::from(format!("{key}:{}", taint.effect))
+            })
+            .collect_vec();
+
+        let taints = if taints.is_empty() {
+            vec![Line::styled(
+                "None",
+                Style::default().fg(tailwind::GRAY.c500),
+            )]
+        } else {
+            taints
+        };
+
+        Paragraph::new(taints).block(block("Taints"))
+    }
+}
+
+// Everything other than `Ready` is a problem when it is true.
+fn is_healthy(condition: &NodeCondition) -> bool {
+    (condition.type_ == "Ready") == (condition.status == "True")
+}
+
+fn header<const N: usize>(cells: [&'static str; N]) -> Row<'static> {
+    Row::new(cells.into_iter().map(Cell::from)).style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+fn block(title: &'static str) -> Block<'static> {
+    Block::default().borders(Borders::TOP).title(title)
+}
+
+impl Widget for Capacity {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        match event.key().unwrap_or(&Keypress::Null) {
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some(rx) = self.is_ready.as_mut() {
+            if let Err(TryRecvError::Empty) = rx.try_recv() {
+                return Loading.draw(frame, area);
+            }
+
+            self.is_ready = None;
+        }
+
+        let conditions = self
+            .node
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .map_or(0, Vec::len) as u16;
+        let taints = self
+            .node
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.taints.as_ref())
+            .map_or(1, |t| t.len().max(1)) as u16;
+
+        let [usage, conditions, taints, _] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Length(conditions + 2),
+            Constraint::Length(taints + 1),
+            Constraint::Fill(1),
+        ])
+        .spacing(1)
+        .areas(area);
+
+        frame.render_widget(self.usage(), usage);
+        frame.render_widget(self.conditions(), conditions);
+        frame.render_widget(self.taints(), taints);
+
+        Ok(())
+    }
+}
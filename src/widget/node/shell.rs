@@ -0,0 +1,140 @@
+use std::sync::{Arc, OnceLock};
+
+use chrono::Utc;
+use eyre::{Report, Result};
+use k8s_openapi::api::core::v1::Node;
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{palette::tailwind, Style},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{container::Container, node::shell},
+    widget::{error::Error, keymap, pod::shell::ExecBuilder, propagate, tabs::Tab, Widget},
+};
+
+/// Whether the server allows shells on nodes. This is effectively root on the
+/// node, so it is off unless explicitly enabled.
+pub static ENABLED: OnceLock<bool> = OnceLock::new();
+
+pub fn enabled() -> bool {
+    ENABLED.get().copied().unwrap_or_default()
+}
+
+/// A shell on a node, from a privileged pod scheduled on it. Unlike the pod
+/// shell, nothing is started until `<enter>` is pressed, as it is easy to end
+/// up on the tab by accident. The pod is removed once the shell exits.
+pub struct Shell {
+    client: kube::Client,
+    node: Arc<Node>,
+    namespace: String,
+
+    error: Option<Error>,
+}
+
+#[bon::bon]
+impl Shell {
+    #[builder]
+    pub fn new(client: kube::Client, node: Arc<Node>) -> Self {
+        let namespace = client.default_namespace().to_string();
+
+        Self {
+            client,
+            node,
+            namespace,
+            error: None,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, node: Arc<Node>) -> Tab {
+        let requires = shell::requirements(client.default_namespace());
+
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || {
+                Self::builder()
+                    .client(client.clone())
+                    .node(node.clone())
+                    .build()
+                    .boxed()
+                    .into()
+            }))
+            .requires(requires)
+            .build()
+    }
+
+    fn exec(&self, size: Rect) -> Result<Broadcast> {
+        let pod = shell::pod(&self.node.name_any(), &self.namespace)?;
+        let spec = pod
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.containers.first())
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(Broadcast::Raw(Box::new(
+            ExecBuilder::default()
+                .start(Utc::now())
+                .client(self.client.clone())
+                .container(Container::new(pod.clone(), spec))
+                .pod(Arc::new(pod))
+                .host(true)
+                .size(size)
+                .build()?,
+        )))
+    }
+}
+
+impl Widget for Shell {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(error) = self.error.as_mut() {
+            propagate!(error.dispatch(event, buffer, area), self.error = None);
+        }
+
+        if let Event::Finished(Err(err)) = event {
+            self.error = Some(Error::from(Report::new(err.clone())));
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match event.key().unwrap_or(&Keypress::Null) {
+            Keypress::Enter => self.exec(buffer.area),
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let pg = Paragraph::new(format!(
+            "<enter> for a shell on {}, it'll run as a privileged pod in `{}`",
+            self.node.name_any(),
+            self.namespace,
+        ))
+        .style(Style::default().fg(tailwind::GRAY.c500));
+
+        let [vert] = Layout::vertical([Constraint::Length(1)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [horiz] = Layout::horizontal([Constraint::Length(pg.line_width() as u16)])
+            .flex(Flex::Center)
+            .areas(vert);
+
+        frame.render_widget(pg, horiz);
+
+        if let Some(error) = self.error.as_mut() {
+            error.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
@@ -0,0 +1,282 @@
+use eyre::{Report, Result};
+use futures::FutureExt;
+use itertools::Itertools;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::{api::ListParams, Api, ResourceExt};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    input::{Content, ContentExt, Text},
+    keymap, node, Widget,
+};
+use crate::events::{Broadcast, Event, Keypress};
+
+// The tabs of the apex, pod detail and node detail. These are matched against
+// tab names without case.
+static ROOT: [&str; 3] = ["pods", "nodes", "logs"];
+static POD: [&str; 4] = ["overview", "logs", "shell", "usage"];
+static NODE: [&str; 5] = ["yaml", "pods", "capacity", "logs", "shell"];
+
+static MAX_MATCHES: usize = 8;
+
+#[derive(Default)]
+struct Names {
+    pods: Vec<(String, String)>,
+    nodes: Vec<String>,
+}
+
+async fn names(client: kube::Client) -> Result<Names> {
+    let pods = Api::<Pod>::all(client.clone())
+        .list_metadata(&ListParams::default())
+        .await?
+        .into_iter()
+        .map(|pod| (pod.namespace().unwrap_or_default(), pod.name_any()))
+        .sorted()
+        .collect();
+
+    // Listing nodes needs more than the minimum permissions, completion for
+    // them is skipped when that isn't allowed.
+    let nodes = Api::<Node>::all(client)
+        .list_metadata(&ListParams::default())
+        .await
+        .map(|nodes| {
+            nodes
+                .into_iter()
+                .map(|node| node.name_any())
+                .sorted()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Names { pods, nodes })
+}
+
+/// Split a path such as `pods/default/nginx/logs` into the segments of a
+/// route, empty segments are ignored.
+pub fn route(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Go straight to something with a path, such as `pods/default/nginx/logs` or
+/// `nodes/worker-1`. `<tab>` completes the segment being typed from what is in
+/// the cluster, `<enter>` follows the path.
+pub struct Palette {
+    input: Text,
+    content: Content,
+
+    names: Names,
+    task: Option<JoinHandle<Result<Names>>>,
+
+    route: Option<Vec<String>>,
+}
+
+impl Palette {
+    pub fn new(client: kube::Client) -> Self {
+        let content = Content::default();
+
+        Self {
+            input: Text::builder()
+                .title("Go to")
+                .content(content.clone())
+                .border_style(Style::default().fg(tailwind::BLUE.c500))
+                .build(),
+            content,
+            names: Names::default(),
+            task: Some(tokio::spawn(names(client))),
+            route: None,
+        }
+    }
+
+    /// The route that was picked, only set once `<enter>` has been pressed.
+    pub fn route(&mut self) -> Option<Vec<String>> {
+        self.route.take()
+    }
+
+    fn update(&mut self) {
+        if !self.task.as_ref().map_or(false, JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(Some(result)) = self.task.take().map(FutureExt::now_or_never) else {
+            return;
+        };
+
+        match result.map_err(Report::from).and_then(|r| r) {
+            Ok(names) => self.names = names,
+            Err(err) => tracing::debug!("unable to list names for completion: {err:?}"),
+        }
+    }
+
+    fn text(&self) -> String {
+        self.content.borrow().clone().unwrap_or_default()
+    }
+
+    fn candidates(&self, segments: &[&str]) -> Vec<String> {
+        let tabs = |tabs: &[&str]| tabs.iter().map(ToString::to_string).collect();
+
+        match segments {
+            [] => tabs(&ROOT),
+            ["pods"] => self
+                .names
+                .pods
+                .iter()
+                .map(|(ns, _)| ns.clone())
+                .dedup()
+                .collect(),
+            ["pods", namespace] => self
+                .names
+                .pods
+                .iter()
+                .filter(|(ns, _)| ns == namespace)
+                .map(|(_, name)| name.clone())
+                .collect(),
+            ["pods", _, _] => tabs(&POD),
+            ["nodes"] => self.names.nodes.clone(),
+            ["nodes", _] => tabs(&NODE)
+                .into_iter()
+                .filter(|tab: &String| tab != "shell" || node::shell::enabled())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Everything that could finish the segment currently being typed.
+    fn matches(&self) -> Vec<String> {
+        let text = self.text();
+        let mut segments = text.split('/').collect_vec();
+        let last = segments.pop().unwrap_or_default();
+
+        self.candidates(&segments)
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(last))
+            .collect()
+    }
+
+    // Extend the last segment as far as all the matches agree. With only one
+    // match, the segment is finished and the next one started.
+    fn complete(&mut self) {
+        let matches = self.matches();
+
+        let Some(first) = matches.first() else {
+            return;
+        };
+
+        let prefix = matches.iter().skip(1).fold(first.as_str(), |prefix, m| {
+            let len = prefix
+                .chars()
+                .zip(m.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a.len_utf8())
+                .sum();
+
+            &prefix[..len]
+        });
+
+        let text = self.text();
+        let mut segments = text.split('/').collect_vec();
+        segments.pop();
+        segments.push(prefix);
+
+        let mut text = segments.join("/");
+        if matches.len() == 1 {
+            text.push('/');
+        }
+
+        self.content = Content::from_string(text);
+        self.input = Text::builder()
+            .title("Go to")
+            .content(self.content.clone())
+            .border_style(Style::default().fg(tailwind::BLUE.c500))
+            .build();
+    }
+}
+
+impl Widget for Palette {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match key {
+            Keypress::HorizontalTab => {
+                self.update();
+                self.complete();
+            }
+            Keypress::Enter => {
+                self.route = Some(route(&self.text())).filter(|route| !route.is_empty());
+
+                return Ok(Broadcast::Exited);
+            }
+            key if keymap::exit(key) => return Ok(Broadcast::Exited),
+            _ => {
+                self.input.dispatch(event, buffer, area)?;
+            }
+        }
+
+        // Everything else would otherwise go to whatever is underneath.
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.update();
+
+        let matches = self.matches();
+        let shown = matches.len().min(MAX_MATCHES);
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .areas(area);
+        let [_, input, list, _] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(u16::try_from(shown).unwrap_or_default().saturating_add(1)),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        frame.render_widget(Clear, input);
+        self.input.draw(frame, input)?;
+
+        if shown > 0 {
+            let mut lines = matches
+                .into_iter()
+                .take(MAX_MATCHES)
+                .map(Line::from)
+                .collect_vec();
+
+            if let Some(first) = lines.first_mut() {
+                first.style = Style::default().fg(tailwind::BLUE.c300);
+            }
+
+            frame.render_widget(Clear, list);
+            frame.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+                        .border_style(Style::default().fg(tailwind::BLUE.c500)),
+                ),
+                list,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        5
+    }
+}
@@ -1,4 +1,5 @@
 pub mod shell;
+pub mod usage;
 
 use std::sync::Arc;
 
@@ -9,6 +10,7 @@ use ratatui::{layout::Rect, prelude::*};
 use tokio::sync::oneshot;
 
 use super::{
+    action::Actions,
     loading::Loading,
     log::Log,
     propagate, table,
@@ -18,26 +20,55 @@ use super::{
 };
 use crate::{
     events::{Broadcast, Event, Keypress},
-    exit_keys,
-    resources::store::Store,
-    widget::{pod::shell::Shell, yaml::Yaml},
+    resources::{authz::Authz, node, store::Store},
+    widget::{
+        keymap,
+        pod::{shell::Shell, usage::Usage},
+        yaml::Yaml,
+    },
 };
 
 pub struct List {
     view: View,
     is_ready: oneshot::Receiver<()>,
+    ready: bool,
+    // Routes can arrive before the store has anything in it, they're held on
+    // to until it is ready.
+    route: Option<Vec<String>>,
 }
 
 impl List {
     #[allow(clippy::blocks_in_conditions)]
     #[tracing::instrument(skip_all, fields(activity = "pod.list"))]
-    pub fn new(client: kube::Client) -> Self {
+    pub fn new(client: kube::Client, authz: Authz) -> Self {
+        let (pods, is_ready) = Store::new(client.clone());
+
+        Self::from_store(client, authz, pods, is_ready)
+    }
+
+    /// Only the pods scheduled on `node`.
+    pub fn on_node(client: kube::Client, authz: Authz, node: &str) -> Self {
+        let (pods, is_ready) = node::pods(client.clone(), node);
+
+        Self::from_store(client, authz, pods, is_ready)
+    }
+
+    fn from_store(
+        client: kube::Client,
+        authz: Authz,
+        pods: Arc<Store<Pod>>,
+        is_ready: oneshot::Receiver<()>,
+    ) -> Self {
         WIDGET_VIEWS.pod.list.inc();
 
-        let (pods, is_ready) = Store::new(client.clone());
         let table = table::Filtered::builder()
             .table(table::Table::builder().items(pods.clone()).build())
-            .constructor(Detail::from_store(client, pods))
+            .constructor(Detail::from_store(
+                client.clone(),
+                authz.clone(),
+                pods.clone(),
+            ))
+            .actions(Actions::from_pods(client, authz, pods))
             .build();
 
         let widgets = vec![
@@ -51,15 +82,17 @@ impl List {
         Self {
             view: View::builder().widgets(widgets).build(),
             is_ready,
+            ready: false,
+            route: None,
         }
     }
 
-    pub fn tab(name: String, client: kube::Client, terminal: bool) -> Tab {
+    pub fn tab(name: String, client: kube::Client, authz: Authz, terminal: bool) -> Tab {
         Tab::builder()
             .name(name)
             .constructor(Box::new(move || {
                 Element::builder()
-                    .widget(Self::new(client.clone()).boxed())
+                    .widget(Self::new(client.clone(), authz.clone()).boxed())
                     .terminal(terminal)
                     .build()
             }))
@@ -69,10 +102,22 @@ impl List {
 
 impl Widget for List {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let (Event::Goto(route), false) = (event, self.ready) {
+            self.route = Some(route.clone());
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if self.ready {
+            if let Some(route) = self.route.take() {
+                self.view.dispatch(&Event::Goto(route), buffer, area)?;
+            }
+        }
+
         propagate!(self.view.dispatch(event, buffer, area));
 
         match event.key().unwrap_or(&Keypress::Null) {
-            exit_keys!() => Ok(Broadcast::Exited),
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
             _ => Ok(Broadcast::Ignored),
         }
     }
@@ -81,6 +126,7 @@ impl Widget for List {
         // TODO: add an error screen here if Err(TryRecvError::Closed)
         if let Ok(()) = self.is_ready.try_recv() {
             self.view.pop();
+            self.ready = true;
         }
 
         self.view.draw(frame, area)
@@ -99,55 +145,102 @@ impl Widget for List {
 }
 
 struct Detail {
+    client: kube::Client,
+    authz: Authz,
+    pod: Arc<Pod>,
+
     view: TabbedView,
+    actions: Option<Actions>,
 }
 
 #[bon::bon]
 impl Detail {
     #[builder]
-    #[allow(clippy::needless_pass_by_value)]
-    fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+    fn new(client: &kube::Client, authz: Authz, pod: Arc<Pod>) -> Self {
         WIDGET_VIEWS.pod.detail.inc();
 
+        let tabs = vec![
+            Yaml::tab(
+                "Overview".to_string(),
+                client.clone(),
+                authz.clone(),
+                pod.clone(),
+            ),
+            Log::tab("Logs".to_string(), client.clone(), pod.clone()),
+            Shell::tab("Shell".to_string(), client.clone(), pod.clone()),
+            Usage::tab("Usage".to_string(), pod.clone()),
+        ];
+
         let view = TabbedView::builder()
-            .tabs(vec![
-                Yaml::tab("Overview".to_string(), pod.clone()),
-                Log::tab("Logs".to_string(), client.clone(), pod.clone()),
-                Shell::tab("Shell".to_string(), client.clone(), pod.clone()),
-            ])
+            .tabs(tabs)
             .title(vec![
                 "pods".to_string(),
                 pod.namespace().unwrap_or_default(),
                 pod.name_any(),
             ])
+            .authz(authz.clone())
             .build();
 
-        Self { view }
+        Self {
+            client: client.clone(),
+            authz,
+            pod,
+            view,
+            actions: None,
+        }
     }
 
-    pub fn from_store(client: kube::Client, pods: Arc<Store<Pod>>) -> table::DetailFn {
+    pub fn from_store(
+        client: kube::Client,
+        authz: Authz,
+        pods: Arc<Store<Pod>>,
+    ) -> table::DetailFn {
         Box::new(move |idx, filter| {
             let pod = pods
                 .get(idx, filter)
                 .ok_or_else(|| eyre!("pod not found"))?;
 
-            Ok(Detail::builder().client(&client).pod(pod).build().boxed())
+            Ok(Detail::builder()
+                .client(&client)
+                .authz(authz.clone())
+                .pod(pod)
+                .build()
+                .boxed())
         })
     }
 }
 
 impl Widget for Detail {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(actions) = self.actions.as_mut() {
+            propagate!(actions.dispatch(event, buffer, area), self.actions = None);
+        }
+
         propagate!(self.view.dispatch(event, buffer, area));
 
         match event.key().unwrap_or(&Keypress::Null) {
-            exit_keys!() => Ok(Broadcast::Exited),
+            Keypress::Printable('a') => {
+                self.actions = Some(Actions::pod(
+                    self.client.clone(),
+                    self.authz.clone(),
+                    self.pod.clone(),
+                ));
+
+                Ok(Broadcast::Consumed)
+            }
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
             _ => Ok(Broadcast::Ignored),
         }
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        self.view.draw(frame, area)
+        self.view.draw(frame, area)?;
+
+        if let Some(actions) = self.actions.as_mut() {
+            actions.draw(frame, area)?;
+        }
+
+        Ok(())
     }
 
     fn zindex(&self) -> u16 {
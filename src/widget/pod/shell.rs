@@ -1,20 +1,22 @@
-use std::{pin::Pin, sync::Arc, vec};
+use std::{cell::RefCell, collections::HashMap, pin::Pin, sync::Arc, vec};
 
 use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use eyre::{eyre, Result};
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
+use itertools::Itertools;
 use k8s_openapi::api::core::v1::Pod;
 use kube::{
-    api::{Api, AttachParams},
+    api::{Api, AttachParams, AttachedProcess, TerminalSize},
     ResourceExt,
 };
 use lazy_static::lazy_static;
 use prometheus::{histogram_opts, register_histogram, Histogram};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
-    widgets::{Block, Borders},
+    layout::{Constraint, Layout, Rect, Size},
+    style::{palette::tailwind, Style},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 use tokio::{
@@ -25,15 +27,20 @@ use tokio_util::io::ReaderStream;
 
 use crate::{
     events::{Broadcast, Event, Keypress},
-    exit_keys,
+    recording,
     resources::{
+        authz,
         container::{Container, ContainerExt},
-        pod::PodExt,
+        node,
+        pod::{
+            debug::{self, DEFAULT_IMAGE},
+            PodExt,
+        },
         status::StatusExt,
     },
     widget::{
         input::{self, ContentExt},
-        propagate, table,
+        keymap, propagate, table,
         tabs::Tab,
         Raw, Widget, WIDGET_VIEWS,
     },
@@ -73,6 +80,13 @@ impl Shell {
     }
 
     pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        let requires = vec![authz::namespaced(
+            "",
+            "pods/exec",
+            "create",
+            &pod.namespace().unwrap_or_default(),
+        )];
+
         Tab::builder()
             .name(name)
             .constructor(Box::new(move || {
@@ -83,6 +97,7 @@ impl Shell {
                     .boxed()
                     .into()
             }))
+            .requires(requires)
             .build()
     }
 }
@@ -97,13 +112,38 @@ impl Widget for Shell {
     }
 }
 
-static COMMAND: &str = "/bin/bash";
+// Tried in order when there's no command, the first one that runs wins.
+static SHELLS: [&str; 3] = ["bash", "sh", "ash"];
+
+thread_local! {
+    // The last command that worked, by image. Each dashboard runs on its own
+    // thread, so this lasts as long as the session does.
+    static REMEMBERED: RefCell<HashMap<String, String>> = RefCell::default();
+}
+
+fn remembered(container: &Container) -> Option<String> {
+    REMEMBERED.with(|r| r.borrow().get(container.image()).cloned())
+}
+
+fn remember(container: &Container, cmd: &str) {
+    REMEMBERED.with(|r| {
+        r.borrow_mut()
+            .insert(container.image().to_string(), cmd.to_string())
+    });
+}
 
+// Either a command to exec into the container with or, for containers that
+// don't have a shell, an image to start an ephemeral debug container from. The
+// first time this is shown, it goes straight into the container. If that
+// fails, the command can be changed.
 struct Command {
     client: kube::Client,
     pod: Arc<Pod>,
     container: Container,
     content: input::Text,
+    image: input::Text,
+    debug: bool,
+    auto: bool,
 }
 
 impl Command {
@@ -115,11 +155,50 @@ impl Command {
         Self {
             client,
             pod,
-            container,
             content: input::Text::builder()
-                .title(name)
-                .content(input::Content::from_string(COMMAND))
+                .title(name.clone())
+                .content(input::Content::from_string(
+                    remembered(&container).unwrap_or_default(),
+                ))
+                .build(),
+            image: input::Text::builder()
+                .title(format!("Debug image → {name}"))
+                .content(input::Content::from_string(DEFAULT_IMAGE))
+                .border_style(Style::default().fg(tailwind::AMBER.c500))
                 .build(),
+            container,
+            debug: false,
+            auto: true,
+        }
+    }
+
+    fn exec(&self, size: Rect) -> Result<Broadcast> {
+        let value = |input: &input::Text| {
+            input
+                .content()
+                .borrow()
+                .as_ref()
+                .map_or(String::new(), String::clone)
+        };
+
+        Ok(Broadcast::Raw(Box::new(
+            ExecBuilder::default()
+                .start(Utc::now())
+                .client(self.client.clone())
+                .pod(self.pod.clone())
+                .container(self.container.clone())
+                .cmd(value(&self.content))
+                .image(self.debug.then(|| value(&self.image)))
+                .size(size)
+                .build()?,
+        )))
+    }
+
+    fn input(&mut self) -> &mut input::Text {
+        if self.debug {
+            &mut self.image
+        } else {
+            &mut self.content
         }
     }
 
@@ -139,36 +218,47 @@ impl Command {
 
 impl Widget for Command {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
-        propagate!(self.content.dispatch(event, buffer, area));
+        if self.auto {
+            self.auto = false;
+
+            return self.exec(buffer.area);
+        }
+
+        if let Some(Keypress::HorizontalTab) = event.key() {
+            self.debug = !self.debug;
+
+            return Ok(Broadcast::Consumed);
+        }
 
-        let cmd = self
-            .content
-            .content()
-            .borrow()
-            .as_ref()
-            .map_or(String::new(), String::clone);
+        propagate!(self.input().dispatch(event, buffer, area));
 
         match event.key().unwrap_or(&Keypress::Null) {
-            Keypress::Enter => {
-                return Ok(Broadcast::Raw(Box::new(
-                    ExecBuilder::default()
-                        .start(Utc::now())
-                        .client(self.client.clone())
-                        .pod(self.pod.clone())
-                        .container(self.container.clone())
-                        .cmd(cmd)
-                        .build()?,
-                )))
-            }
-            exit_keys!() => return Ok(Broadcast::Exited),
+            Keypress::Enter => return self.exec(buffer.area),
+            key if keymap::exit(key) => return Ok(Broadcast::Exited),
             _ => {}
         };
 
         match event {
-            Event::Finished(result) => Ok(result
-                .as_ref()
-                .map(|()| Broadcast::Exited)
-                .map_err(std::clone::Clone::clone)?),
+            Event::Finished(result) => {
+                // A detected shell shows up afterwards, so that it can be tweaked.
+                let empty = self
+                    .content
+                    .content()
+                    .borrow()
+                    .as_deref()
+                    .map_or(true, |c| c.trim().is_empty());
+
+                if let (true, Some(cmd)) = (empty, remembered(&self.container)) {
+                    self.content = input::Text::builder()
+                        .title(self.container.name_any())
+                        .content(input::Content::from_string(cmd))
+                        .build();
+                }
+
+                result.clone()?;
+
+                Ok(Broadcast::Consumed)
+            }
             _ => Ok(Broadcast::Ignored),
         }
     }
@@ -184,9 +274,10 @@ impl Widget for Command {
             inner
         };
 
-        let [_, area, _] = Layout::vertical([
+        let [_, area, hint, _] = Layout::vertical([
             Constraint::Fill(0),
             Constraint::Length(3),
+            Constraint::Length(1),
             Constraint::Fill(0),
         ])
         .areas(area);
@@ -198,7 +289,20 @@ impl Widget for Command {
         ])
         .areas(area);
 
-        self.content.draw(frame, area)
+        let msg = if self.debug {
+            "<tab> to exec instead"
+        } else {
+            "Leave empty to detect a shell, <tab> to debug with an ephemeral container"
+        };
+
+        frame.render_widget(
+            Paragraph::new(msg)
+                .style(Style::default().fg(tailwind::GRAY.c500))
+                .centered(),
+            hint,
+        );
+
+        self.input().draw(frame, area)
     }
 
     fn zindex(&self) -> u16 {
@@ -207,58 +311,213 @@ impl Widget for Command {
 }
 
 #[derive(Builder)]
-struct Exec {
+pub struct Exec {
     start: DateTime<Utc>,
     client: kube::Client,
     pod: Arc<Pod>,
     container: Container,
+    #[builder(default)]
     cmd: String,
+    // When set, an ephemeral container is started from this image, targeting
+    // `container`, and attached to instead of running `cmd`.
+    #[builder(default)]
+    image: Option<String>,
+    // When set, `pod` doesn't exist yet. It is created, `nsenter` is used to get
+    // into the namespaces of the node it runs on and it is deleted afterwards.
+    #[builder(default)]
+    host: bool,
+    #[builder(default)]
+    size: Rect,
+    // The pod started for `host`, which is removed when this is dropped.
+    #[builder(setter(skip))]
+    running: Option<node::shell::Running>,
 }
 
-#[async_trait::async_trait]
-impl Raw for Exec {
-    #[allow(clippy::blocks_in_conditions)]
-    #[tracing::instrument(skip_all, fields(activity = "pod.exec"))]
-    async fn start(
+impl Exec {
+    // Find a shell that exists in the container by running it, as `which`
+    // isn't something that can be relied on either.
+    async fn detect(&self, api: &Api<Pod>) -> Result<String> {
+        let candidates = remembered(&self.container)
+            .into_iter()
+            .chain(SHELLS.iter().map(ToString::to_string))
+            .unique()
+            .collect::<Vec<_>>();
+
+        for shell in candidates {
+            let Ok(mut proc) = api
+                .exec(
+                    &self.pod.name_any(),
+                    vec![shell.as_str(), "-c", "exit 0"],
+                    &AttachParams::default().container(self.container.name_any()),
+                )
+                .await
+            else {
+                continue;
+            };
+
+            let status = match proc.take_status() {
+                Some(status) => status.await,
+                None => None,
+            };
+
+            proc.join().await?;
+
+            if status.map_or(false, |s| s.is_success()) {
+                return Ok(shell);
+            }
+        }
+
+        Err(eyre!(
+            "No shell found, tried {}. Pick a command or use <tab> to debug with an ephemeral \
+             container instead.",
+            SHELLS.join(", ")
+        ))
+    }
+
+    async fn process(
+        &mut self,
+        stdout: &mut Pin<Box<dyn AsyncWrite + Send + Unpin>>,
+    ) -> Result<AttachedProcess> {
+        let api = Api::<Pod>::namespaced(self.client.clone(), &self.pod.namespace().unwrap());
+        let params = AttachParams {
+            stdin: true,
+            stdout: true,
+            stderr: false,
+            tty: true,
+            ..Default::default()
+        };
+
+        if self.host {
+            WIDGET_VIEWS.node.exec.inc();
+
+            stdout
+                .write_all(
+                    format!(
+                        "Starting a shell on {}...\r\n",
+                        self.pod
+                            .spec
+                            .as_ref()
+                            .and_then(|s| s.node_name.clone())
+                            .unwrap_or_default()
+                    )
+                    .as_bytes(),
+                )
+                .await?;
+            stdout.flush().await?;
+
+            let running = node::shell::start(self.client.clone(), &self.pod).await?;
+
+            if let Some(pod) = running.pod() {
+                self.pod = Arc::new(pod.clone());
+            }
+
+            self.running = Some(running);
+
+            return Ok(api
+                .exec(
+                    &self.pod.name_any(),
+                    node::shell::COMMAND.to_vec(),
+                    &params.container(self.container.name_any()),
+                )
+                .await?);
+        }
+
+        let Some(image) = self.image.as_ref() else {
+            if self.cmd.trim().is_empty() {
+                self.cmd = self.detect(&api).await?;
+
+                remember(&self.container, &self.cmd);
+            }
+
+            return Ok(api
+                .exec(
+                    &self.pod.name_any(),
+                    vec![self.cmd.as_str()],
+                    &params.container(self.container.name_any()),
+                )
+                .await?);
+        };
+
+        WIDGET_VIEWS.container.debug.inc();
+
+        stdout
+            .write_all(format!("Starting {image} as a debug container...\r\n").as_bytes())
+            .await?;
+        stdout.flush().await?;
+
+        let name = debug::ephemeral(
+            self.client.clone(),
+            &self.pod,
+            &self.container.name_any(),
+            image,
+        )
+        .await?;
+
+        Ok(api
+            .attach(&self.pod.name_any(), &params.container(name))
+            .await?)
+    }
+}
+
+fn terminal_size(size: Size) -> TerminalSize {
+    TerminalSize {
+        width: size.width,
+        height: size.height,
+    }
+}
+
+impl Exec {
+    async fn run(
         &mut self,
         stdin: &mut UnboundedReceiver<Event>,
         mut stdout: Pin<Box<dyn AsyncWrite + Send + Unpin>>,
     ) -> Result<()> {
-        WIDGET_VIEWS.container.exec.inc();
+        let mut proc = self.process(&mut stdout).await?;
 
-        let mut proc = Api::<Pod>::namespaced(self.client.clone(), &self.pod.namespace().unwrap())
-            .exec(
-                &self.pod.name_any(),
-                vec![self.cmd.as_str()],
-                &AttachParams {
-                    container: Some(self.container.name_any().to_string()),
-                    stdin: true,
-                    stdout: true,
-                    stderr: false,
-                    tty: true,
-                    ..Default::default()
-                },
-            )
-            .await?;
+        // Until this is dropped, output is recorded as part of this shell
+        // instead of the session.
+        let recording = recording::current()
+            .map(|recorder| recorder.attach(&self.pod, &self.container.name_any()))
+            .transpose()?;
 
         let status = proc.take_status().ok_or(eyre!("status not available"))?;
 
         let mut output = ReaderStream::new(proc.stdout().ok_or(eyre!("stdout not available"))?);
         let mut input = proc.stdin().ok_or(eyre!("stdin not available"))?;
 
-        // TODO: handle resize events.
+        let mut resize = proc.terminal_size();
+
+        if let (Some(tx), false) = (resize.as_mut(), self.size.is_empty()) {
+            tx.send(terminal_size(self.size.as_size())).await?;
+        }
+
+        // Set when the session has gone away.
+        let mut closed = false;
+
         loop {
             tokio::select! {
                 msg = stdin.recv() => {
                     let Some(msg) = msg else {
+                        closed = true;
+
                         break;
                     };
 
+                    if let (Event::Resize(size), Some(tx)) = (&msg, resize.as_mut()) {
+                        tx.send(terminal_size(size.columns_rows)).await?;
+                    }
+
                     let Event::Input(incoming) = &msg else {
                         continue;
                     };
 
-                    input.write_all(incoming.into()).await?;
+                    let incoming: &[u8] = incoming.into();
+
+                    if let Some(recording) = recording.as_ref() {
+                        recording.input(incoming);
+                    }
+
+                    input.write_all(incoming).await?;
                     input.flush().await?;
 
                     if matches!(msg.key(), Some(Keypress::Control('b'))) {
@@ -276,6 +535,15 @@ impl Raw for Exec {
             }
         }
 
+        drop(input);
+
+        // Nobody is left to exit the shell, so its status would never arrive.
+        if closed {
+            proc.abort();
+
+            return Ok(());
+        }
+
         status
             .await
             .map(|status| {
@@ -289,10 +557,39 @@ impl Raw for Exec {
 
         proc.join().await?;
 
+        if self.image.is_none() && !self.host {
+            remember(&self.container, &self.cmd);
+        }
+
         Ok(())
     }
 }
 
+#[async_trait::async_trait]
+impl Raw for Exec {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip_all, fields(activity = "pod.exec"))]
+    async fn start(
+        &mut self,
+        stdin: &mut UnboundedReceiver<Event>,
+        stdout: Pin<Box<dyn AsyncWrite + Send + Unpin>>,
+    ) -> Result<()> {
+        WIDGET_VIEWS.container.exec.inc();
+
+        let result = self.run(stdin, stdout).await;
+
+        // Dropping this would remove the pod too, but stopping here means it is
+        // gone by the time the dashboard is back.
+        if let Some(running) = self.running.take() {
+            if let Err(err) = running.stop().await {
+                tracing::error!(pod = self.pod.name_any(), err = ?err, "unable to remove node shell");
+            }
+        }
+
+        result
+    }
+}
+
 impl Drop for Exec {
     fn drop(&mut self) {
         EXEC_DURATION.observe(
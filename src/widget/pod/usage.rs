@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Pod;
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{palette::tailwind, Style},
+    widgets::{Block, Borders, Paragraph, Sparkline},
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{metrics, quantity},
+    widget::{keymap, tabs::Tab, Widget},
+};
+
+/// CPU and memory of a pod over the last `metrics::RANGE` samples.
+pub struct Usage {
+    pod: Arc<Pod>,
+}
+
+impl Usage {
+    pub fn new(pod: Arc<Pod>) -> Self {
+        Self { pod }
+    }
+
+    pub fn tab(name: String, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(pod.clone()).boxed().into()))
+            .build()
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn samples(history: &[metrics::Usage], value: impl Fn(&metrics::Usage) -> f64) -> Vec<u64> {
+    history
+        .iter()
+        .map(|usage| value(usage).round() as u64)
+        .collect()
+}
+
+fn sparkline(title: String, data: &[u64], color: Style) -> Sparkline<'_> {
+    Sparkline::default()
+        .block(Block::default().borders(Borders::TOP).title(title))
+        .data(data)
+        .style(color)
+}
+
+// The tab is always there so that it can be navigated to, this is what shows up
+// when there's nothing for it to show.
+#[allow(clippy::cast_possible_truncation)]
+fn unavailable(frame: &mut Frame, area: Rect) {
+    let pg = Paragraph::new("Metrics unavailable").style(Style::default().fg(tailwind::GRAY.c500));
+
+    let [area] = Layout::vertical([Constraint::Length(1)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([pg.line_width() as u16])
+        .flex(Flex::Center)
+        .areas(area);
+
+    frame.render_widget(pg, area);
+}
+
+impl Widget for Usage {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        match event.key().unwrap_or(&Keypress::Null) {
+            key if keymap::exit(key) => Ok(Broadcast::Exited),
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let history = metrics::pod_history(
+            &self.pod.namespace().unwrap_or_default(),
+            &self.pod.name_any(),
+        );
+
+        if history.is_empty() && !metrics::pods_available() {
+            unavailable(frame, area);
+
+            return Ok(());
+        }
+
+        let latest = history.last().copied().unwrap_or_default();
+
+        let [cpu, memory] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+
+        frame.render_widget(
+            sparkline(
+                format!("CPU {}", quantity::cpu(latest.cpu)),
+                // Millicores, anything less rounds to nothing.
+                &samples(&history, |usage| usage.cpu * 1000.0),
+                Style::default().fg(tailwind::INDIGO.c300),
+            ),
+            cpu,
+        );
+        frame.render_widget(
+            sparkline(
+                format!("Memory {}", quantity::memory(latest.memory)),
+                &samples(&history, |usage| usage.memory),
+                Style::default().fg(tailwind::GREEN.c300),
+            ),
+            memory,
+        );
+
+        Ok(())
+    }
+}
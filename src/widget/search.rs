@@ -0,0 +1,389 @@
+use std::ops::Range;
+
+use eyre::Result;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span, Text},
+    Frame,
+};
+use regex::{Regex, RegexBuilder};
+
+use super::{
+    input::{self, Content},
+    keymap, Widget,
+};
+use crate::events::{Broadcast, Event, Keypress};
+
+lazy_static! {
+    static ref CONTENT_SEARCH: IntCounter = register_int_counter!(
+        "content_search_total",
+        "Number of times content has been searched"
+    )
+    .unwrap();
+}
+
+// Queries are treated as regular expressions. If the query isn't a valid
+// expression (for example, while typing `foo(`), it falls back to a literal
+// match. Matching is case insensitive unless there's an uppercase character in
+// the query.
+fn compile(query: &str) -> Regex {
+    let insensitive = !query.chars().any(char::is_uppercase);
+
+    RegexBuilder::new(query)
+        .case_insensitive(insensitive)
+        .build()
+        .unwrap_or_else(|_| {
+            RegexBuilder::new(regex::escape(query).as_str())
+                .case_insensitive(insensitive)
+                .build()
+                .expect("escaped query is valid")
+        })
+}
+
+fn is_match(text: &Text, pattern: &Regex) -> bool {
+    text.lines.iter().any(|line| {
+        pattern.is_match(
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+                .as_str(),
+        )
+    })
+}
+
+fn highlight_line<'a>(line: &Line<'a>, pattern: &Regex, style: Style) -> Line<'a> {
+    let content = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect::<String>();
+
+    let ranges: Vec<Range<usize>> = pattern
+        .find_iter(content.as_str())
+        .map(|m| m.range())
+        .filter(|r| !r.is_empty())
+        .collect();
+
+    if ranges.is_empty() {
+        return line.clone();
+    }
+
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    // Matches can span multiple spans (and spans can contain multiple matches), so
+    // each span is split at the match boundaries that fall inside of it.
+    for span in &line.spans {
+        let txt = span.content.as_ref();
+        let end = offset + txt.len();
+        let mut cursor = offset;
+
+        for range in ranges.iter().filter(|r| r.start < end && r.end > offset) {
+            let start = range.start.max(offset);
+            let stop = range.end.min(end);
+
+            if start > cursor {
+                spans.push(Span::styled(
+                    txt[cursor - offset..start - offset].to_string(),
+                    span.style,
+                ));
+            }
+
+            spans.push(Span::styled(
+                txt[start - offset..stop - offset].to_string(),
+                span.style.patch(style),
+            ));
+
+            cursor = stop;
+        }
+
+        if cursor < end {
+            spans.push(Span::styled(txt[cursor - offset..].to_string(), span.style));
+        }
+
+        offset = end;
+    }
+
+    let mut result = line.clone();
+    result.spans = spans;
+
+    result
+}
+
+/// Patch `style` onto every part of `text` that matches `pattern`.
+pub fn highlight<'a>(text: &Text<'a>, pattern: &Regex, style: Style) -> Text<'a> {
+    let mut result = text.clone();
+    result.lines = text
+        .lines
+        .iter()
+        .map(|line| highlight_line(line, pattern, style))
+        .collect();
+
+    result
+}
+
+#[derive(Clone, Copy)]
+pub struct Highlight<'a> {
+    pub pattern: &'a Regex,
+    pub style: Style,
+}
+
+/// Search through lines of content. This is meant to be embedded in widgets
+/// that render a `Viewport`.
+///
+/// - `/` opens an input box, matches are updated as the query is typed and
+///   `<enter>` jumps to the first match after the current position.
+/// - `n` and `N` jump to the next and previous match.
+/// - `&` toggles between showing every line and only the lines that match.
+///
+/// Jumps are communicated to the parent with `Broadcast::Selected(line)`, where
+/// `line` is the new top of the view. When only matching lines are shown, that
+/// is an index into `lines()` instead of the original content.
+pub struct Search {
+    input: Option<input::Text>,
+    query: Content,
+
+    last: Option<String>,
+    pattern: Option<Regex>,
+    matches: Vec<usize>,
+    scanned: usize,
+
+    current: Option<usize>,
+    cursor: usize,
+    filter: bool,
+
+    style: Style,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            input: None,
+            query: Content::default(),
+            last: None,
+            pattern: None,
+            matches: Vec::new(),
+            scanned: 0,
+            current: None,
+            cursor: 0,
+            filter: false,
+            style: Style::default()
+                .fg(tailwind::GRAY.c900)
+                .bg(tailwind::YELLOW.c300),
+        }
+    }
+}
+
+impl Search {
+    /// Refresh the matches for the current query. Content is expected to only
+    /// be appended to, matching only happens on lines that haven't been seen
+    /// before. If the content shrinks, everything is searched again. `cursor`
+    /// is the current top of the view and is used as the starting point when
+    /// jumping to a match for the first time.
+    pub fn update(&mut self, lines: &[Text], cursor: usize) {
+        let query = self.query.borrow().clone();
+
+        if query != self.last || lines.len() < self.scanned {
+            self.pattern = query.as_deref().filter(|q| !q.is_empty()).map(compile);
+            self.last = query;
+            self.matches.clear();
+            self.scanned = 0;
+            self.current = None;
+
+            if self.pattern.is_none() {
+                self.filter = false;
+            }
+        }
+
+        if let Some(pattern) = self.pattern.as_ref() {
+            let start = self.scanned;
+
+            self.matches.extend(
+                lines[start..]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| is_match(line, pattern))
+                    .map(|(i, _)| i + start),
+            );
+        }
+
+        self.scanned = lines.len();
+        self.cursor = cursor;
+    }
+
+    /// Account for `n` lines being dropped from the start of the content.
+    /// Returns the number of matches that were dropped with them.
+    pub fn shift(&mut self, n: usize) -> usize {
+        let removed = self.matches.partition_point(|m| *m < n);
+
+        self.matches.drain(..removed);
+        for m in &mut self.matches {
+            *m -= n;
+        }
+
+        self.scanned = self.scanned.saturating_sub(n);
+        self.current = self.current.and_then(|i| i.checked_sub(removed));
+
+        removed
+    }
+
+    /// Search through all of the content again on the next update.
+    pub fn invalidate(&mut self) {
+        self.matches.clear();
+        self.scanned = 0;
+        self.current = None;
+    }
+
+    /// The lines that should be shown, `None` if all of them are visible.
+    pub fn lines(&self) -> Option<&[usize]> {
+        self.filter.then_some(self.matches.as_slice())
+    }
+
+    /// Total number of visible lines given the total length of the content.
+    pub fn len(&self, total: usize) -> usize {
+        if self.filter {
+            self.matches.len()
+        } else {
+            total
+        }
+    }
+
+    pub fn highlight(&self) -> Option<Highlight<'_>> {
+        self.pattern.as_ref().map(|pattern| Highlight {
+            pattern,
+            style: self.style,
+        })
+    }
+
+    fn jump(&mut self, forward: bool) -> Broadcast {
+        if self.matches.is_empty() {
+            return Broadcast::Consumed;
+        }
+
+        let len = self.matches.len();
+
+        let idx = match self.current {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None => {
+                let next = if self.filter {
+                    self.cursor
+                } else {
+                    self.matches.partition_point(|m| *m < self.cursor)
+                };
+
+                if forward {
+                    next % len
+                } else {
+                    next.checked_sub(1).unwrap_or(len - 1)
+                }
+            }
+        };
+
+        self.current = Some(idx);
+
+        Broadcast::Selected(self.target())
+    }
+
+    fn target(&self) -> usize {
+        match (self.current, self.filter) {
+            (Some(i), true) => i,
+            (Some(i), false) => self.matches[i],
+            (None, true) => self.matches.partition_point(|m| *m < self.cursor),
+            (None, false) => self.matches.get(self.cursor).copied().unwrap_or_default(),
+        }
+    }
+}
+
+impl Widget for Search {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if let Some(input) = self.input.as_mut() {
+            return Ok(match input.dispatch(event, buffer, area)? {
+                Broadcast::Exited => {
+                    self.input = None;
+
+                    Broadcast::Consumed
+                }
+                Broadcast::Ignored if matches!(key, Keypress::Enter) => {
+                    self.input = None;
+
+                    self.jump(true)
+                }
+                // While the input is open, it gets every keypress.
+                _ => Broadcast::Consumed,
+            });
+        }
+
+        let active = self.pattern.is_some();
+
+        Ok(match key {
+            Keypress::Printable('/') => {
+                CONTENT_SEARCH.inc();
+
+                self.input = Some(
+                    input::Text::builder()
+                        .title("Search")
+                        .content(self.query.clone())
+                        .border_style(Style::default().fg(tailwind::BLUE.c500))
+                        .build(),
+                );
+
+                Broadcast::Consumed
+            }
+            Keypress::Printable('n') if active => self.jump(true),
+            Keypress::Printable('N') if active => self.jump(false),
+            Keypress::Printable('&') if active => {
+                self.filter = !self.filter;
+
+                Broadcast::Selected(self.target())
+            }
+            key if active && keymap::exit(key) => {
+                self.query.try_borrow_mut()?.take();
+
+                Broadcast::Consumed
+            }
+            _ => Broadcast::Ignored,
+        })
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some(input) = self.input.as_mut() {
+            let [_, area] =
+                Layout::vertical([Constraint::Fill(0), input.placement().vertical]).areas(area);
+
+            return input.draw(frame, area);
+        }
+
+        let Some(query) = self.last.as_ref().filter(|_| self.pattern.is_some()) else {
+            return Ok(());
+        };
+
+        let status = format!(
+            " /{query} [{}/{}]{} ",
+            self.current.map_or(0, |i| i + 1),
+            self.matches.len(),
+            if self.filter { " (matching)" } else { "" },
+        );
+
+        // Draw over the bottom border of the parent.
+        let area = Rect {
+            x: area.x.saturating_add(1),
+            y: area.bottom().saturating_sub(1),
+            width: area.width.saturating_sub(2),
+            height: area.height.min(1),
+        };
+
+        frame.render_widget(Line::from(status).right_aligned(), area);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
-use eyre::Result;
+use eyre::{eyre, Result};
 use lazy_static::lazy_static;
 use prometheus::{register_int_counter, IntCounter};
 use ratatui::{
@@ -8,6 +11,7 @@ use ratatui::{
     layout::{Constraint, Rect},
     style,
     style::{palette::tailwind, Modifier, Stylize},
+    text::{Line, Span},
     widgets::{self, Block, Borders, TableState},
     Frame,
 };
@@ -15,15 +19,18 @@ use tachyonfx::{fx, EffectTimer, Interpolation};
 use tracing::Level;
 
 use super::{
+    columns::{self, order, Column, Columns, Key},
     error::Error,
     input::Text,
     nav::{move_cursor, Movement, Shrink},
+    theme,
     view::{Element, View},
     BoxWidget, Widget,
 };
 use crate::{
-    events::{Broadcast, Event, Keypress},
+    events::{Broadcast, Event, Keypress, Mouse, MouseButton, MouseKind},
     fx::Animated,
+    resources::{query, Query},
 };
 
 lazy_static! {
@@ -35,13 +42,80 @@ lazy_static! {
 }
 
 pub trait Row {
-    fn constraints() -> Vec<Constraint>;
+    /// Every column rows can have. Rows with columns are drawn one `cell` at a
+    /// time, the columns can be hidden with `<v>` and sorted by with `<o>`.
+    fn columns() -> Vec<Column> {
+        Vec::new()
+    }
+
+    fn constraints() -> Vec<Constraint> {
+        Self::columns()
+            .iter()
+            .filter(|column| !column.hidden)
+            .map(|column| column.constraint)
+            .collect()
+    }
 
     fn header<'a>() -> Option<widgets::Row<'a>> {
+        let columns = Self::columns();
+
+        (!columns.is_empty()).then(|| {
+            widgets::Row::new(
+                columns
+                    .into_iter()
+                    .filter(|column| !column.hidden)
+                    .map(|column| column.name),
+            )
+        })
+    }
+
+    fn row(&self, style: &RowStyle, matches: &Matches) -> widgets::Row {
+        widgets::Row::new(
+            Self::columns()
+                .iter()
+                .filter(|column| !column.hidden)
+                .map(|column| matches.cell(column.name, self.cell(column.name))),
+        )
+        .style(self.style(style))
+    }
+
+    /// The content of `column` for this row.
+    fn cell(&self, _column: &str) -> String {
+        String::new()
+    }
+
+    /// Style of the whole row, when it is drawn from `cell`.
+    fn style(&self, style: &RowStyle) -> style::Style {
+        style.normal
+    }
+
+    /// The value of `column` that rows are sorted by, rows without one go last.
+    fn key(&self, _column: &str) -> Option<Key> {
         None
     }
 
-    fn row(&self, style: &RowStyle) -> widgets::Row;
+    /// The segments that address this row in a route, such as
+    /// `[namespace, name]` for pods. Rows without one can't be routed to.
+    fn path(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// What the filter matched in each cell, for rows to highlight.
+pub struct Matches<'a> {
+    query: Option<&'a Query>,
+    style: style::Style,
+}
+
+impl Matches<'_> {
+    /// `text` as shown in `column`, with the parts matched by the filter
+    /// highlighted.
+    pub fn cell(&self, column: &str, text: String) -> Line<'static> {
+        match self.query {
+            Some(query) => highlight(&text, &query.highlights(column), self.style),
+            None => Line::from(text),
+        }
+    }
 }
 
 pub struct RowStyle {
@@ -52,10 +126,12 @@ pub struct RowStyle {
 
 impl Default for RowStyle {
     fn default() -> Self {
+        let theme = theme::current();
+
         Self {
-            healthy: style::Style::default().fg(tailwind::GREEN.c300),
-            unhealthy: style::Style::default().fg(tailwind::RED.c300),
-            normal: style::Style::default().fg(tailwind::INDIGO.c300),
+            healthy: style::Style::default().fg(theme.healthy),
+            unhealthy: style::Style::default().fg(theme.unhealthy),
+            normal: style::Style::default().fg(theme.normal),
         }
     }
 }
@@ -64,6 +140,7 @@ pub struct Style {
     pub border: style::Style,
     pub header: style::Style,
     pub selected: style::Style,
+    pub matched: style::Style,
     pub row: RowStyle,
 }
 
@@ -74,7 +151,10 @@ impl Default for Style {
             header: style::Style::default().bold(),
             selected: style::Style::default()
                 .add_modifier(Modifier::REVERSED)
-                .bg(tailwind::GRAY.c700),
+                .bg(theme::current().selected),
+            matched: style::Style::default()
+                .fg(theme::current().matched)
+                .add_modifier(Modifier::BOLD),
             row: RowStyle::default(),
         }
     }
@@ -103,6 +183,17 @@ where
     items: S,
     view: TableState,
     filter: Rc<RefCell<Option<String>>>,
+    // The row that is currently selected, after it has been clamped to the
+    // number of rows.
+    selected: Rc<Cell<usize>>,
+    // Which columns are shown and what rows are sorted by.
+    columns: Rc<RefCell<Columns>>,
+    // Position of each drawn row in the natural order of `items`. Anything
+    // outside of the table, such as the detail constructor, uses that order.
+    order: Vec<usize>,
+    // Where the rows were last drawn. Pickers are drawn in a smaller area
+    // than they're dispatched with, so clicks are checked against this.
+    rows: Rect,
 
     _phantom: std::marker::PhantomData<S>,
 }
@@ -135,6 +226,10 @@ where
             items,
             view,
             filter,
+            selected: Rc::default(),
+            columns: Rc::default(),
+            order: Vec::new(),
+            rows: Rect::default(),
             border,
             _phantom: std::marker::PhantomData,
         }
@@ -143,6 +238,98 @@ where
     pub fn filter(&self) -> Rc<RefCell<Option<String>>> {
         self.filter.clone()
     }
+
+    pub fn selected(&self) -> Rc<Cell<usize>> {
+        self.selected.clone()
+    }
+
+    pub fn columns(&self) -> Rc<RefCell<Columns>> {
+        self.columns.clone()
+    }
+
+    fn natural(&self, idx: usize) -> usize {
+        self.order.get(idx).copied().unwrap_or(idx)
+    }
+
+    // Clicking a row selects it, clicking the selected row again is the same as
+    // `<enter>`.
+    fn click(&mut self, mouse: Mouse) -> Broadcast {
+        if mouse.kind != MouseKind::Down(MouseButton::Left) || !self.rows.contains(mouse.position())
+        {
+            return Broadcast::Ignored;
+        }
+
+        let idx = self.view.offset() + usize::from(mouse.position().y - self.rows.y);
+
+        if idx >= self.order.len() {
+            return Broadcast::Ignored;
+        }
+
+        if self.view.selected() == Some(idx) {
+            return Broadcast::Selected(self.natural(idx));
+        }
+
+        self.view.select(Some(idx));
+
+        Broadcast::Consumed
+    }
+
+    // Select the row whose path is at the start of `route`, the rest of the
+    // route is handed back to the parent to continue with.
+    fn route(&mut self, route: &[String]) -> Broadcast {
+        let items = self.items.items(self.filter.borrow().clone());
+
+        let Some((idx, path)) = items
+            .iter()
+            .map(Row::path)
+            .enumerate()
+            .find(|(_, path)| !path.is_empty() && route.starts_with(path))
+        else {
+            return Broadcast::Ignored;
+        };
+
+        self.view.select(Some(
+            self.order.iter().position(|i| *i == idx).unwrap_or(idx),
+        ));
+
+        Broadcast::Routed(idx, route[path.len()..].to_vec())
+    }
+}
+
+// Put `style` on everything in `text` that matches one of `patterns`. Like the
+// filter, patterns without upper case letters match regardless of case.
+fn highlight(text: &str, patterns: &[&str], style: style::Style) -> Line<'static> {
+    let mut matched = vec![false; text.len()];
+
+    for pattern in patterns {
+        let haystack = query::smart_case(text, pattern);
+
+        for (start, found) in haystack.match_indices(pattern) {
+            matched[start..start + found.len()].fill(true);
+        }
+    }
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    for end in 1..=text.len() {
+        if end == text.len() || matched[end] != matched[start] {
+            if !text.is_char_boundary(end) {
+                continue;
+            }
+
+            let span = text[start..end].to_string();
+            spans.push(if matched[start] {
+                Span::styled(span, style)
+            } else {
+                Span::raw(span)
+            });
+
+            start = end;
+        }
+    }
+
+    Line::from(spans)
 }
 
 impl<S> Widget for Table<S>
@@ -151,6 +338,14 @@ where
 {
     #[tracing::instrument(ret(level = tracing::Level::TRACE), skip_all, fields(name = self._name()))]
     fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Goto(route) = event {
+            return Ok(self.route(route));
+        }
+
+        if let Some(mouse) = event.mouse() {
+            return Ok(self.click(*mouse));
+        }
+
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
@@ -168,22 +363,79 @@ where
 
         if matches!(key, Keypress::Enter) {
             return Ok(Broadcast::Selected(
-                self.view.selected().unwrap_or_default(),
+                self.natural(self.view.selected().unwrap_or_default()),
             ));
         }
 
-        Ok(Broadcast::Ignored)
+        if S::Item::columns().is_empty() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        match key {
+            Keypress::Printable('o') => self.columns.borrow_mut().next(&S::Item::columns()),
+            Keypress::Printable('O') => self.columns.borrow_mut().reverse(),
+            _ => return Ok(Broadcast::Ignored),
+        }
+
+        Ok(Broadcast::Consumed)
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let items = self.items.items(self.filter.borrow().clone());
+        let query = self.filter.borrow().as_deref().map(Query::parse);
+        let matches = Matches {
+            query: query.as_ref(),
+            style: self.style.matched,
+        };
+        let columns = self.columns.borrow();
+        let sort = columns.sort();
+        self.order = order(&items, sort);
 
-        let rows = items
-            .iter()
-            .map(|item| item.row(&self.style.row))
+        let shown = S::Item::columns()
+            .into_iter()
+            .filter(|column| columns.visible(column))
             .collect::<Vec<_>>();
 
-        let mut table = widgets::Table::new(rows, S::Item::constraints());
+        // Rows with columns are put together here, so that only the ones that
+        // are shown end up in the table.
+        let (rows, constraints, header) = if shown.is_empty() {
+            (
+                self.order
+                    .iter()
+                    .map(|idx| items[*idx].row(&self.style.row, &matches))
+                    .collect::<Vec<_>>(),
+                S::Item::constraints(),
+                S::Item::header(),
+            )
+        } else {
+            (
+                self.order
+                    .iter()
+                    .map(|idx| {
+                        let item = &items[*idx];
+
+                        widgets::Row::new(
+                            shown
+                                .iter()
+                                .map(|column| matches.cell(column.name, item.cell(column.name))),
+                        )
+                        .style(item.style(&self.style.row))
+                    })
+                    .collect(),
+                shown.iter().map(|column| column.constraint).collect(),
+                Some(widgets::Row::new(shown.iter().map(|column| {
+                    match sort.filter(|(name, _)| *name == column.name) {
+                        Some((_, order)) => format!("{} {}", column.name, order.arrow()),
+                        None => column.name.to_string(),
+                    }
+                }))),
+            )
+        };
+
+        drop(columns);
+
+        let has_header = header.is_some();
+        let mut table = widgets::Table::new(rows, constraints);
         let mut border = Block::default()
             .borders(self.border)
             .style(self.style.border);
@@ -192,7 +444,7 @@ where
             table = table.highlight_style(self.style.selected);
         }
 
-        if let Some(header) = S::Item::header() {
+        if let Some(header) = header {
             table = table.header(header).style(self.style.header);
         };
 
@@ -200,12 +452,35 @@ where
             border = border.title(title.as_str());
         };
 
-        if self.border != Borders::NONE {
-            table = table.block(border);
+        if let Some((column, order)) = sort {
+            border = border.title(
+                Line::from(format!("by {column} {}", order.arrow()))
+                    .style(self.style.header)
+                    .right_aligned(),
+            );
         }
 
+        let inner = if self.border == Borders::NONE {
+            area
+        } else {
+            let inner = border.inner(area);
+            table = table.block(border);
+
+            inner
+        };
+
+        let header = u16::from(has_header);
+        self.rows = Rect {
+            y: inner.y.saturating_add(header),
+            height: inner.height.saturating_sub(header),
+            ..inner
+        };
+
         frame.render_stateful_widget(table, area, &mut self.view);
 
+        self.selected
+            .set(self.natural(self.view.selected().unwrap_or_default()));
+
         Ok(())
     }
 }
@@ -214,20 +489,28 @@ pub type DetailFn = Box<dyn Fn(usize, Option<String>) -> Result<BoxWidget>>;
 
 pub struct Filtered {
     constructor: DetailFn,
+    actions: Option<DetailFn>,
     filter: Rc<RefCell<Option<String>>>,
+    selected: Rc<Cell<usize>>,
+    columns: Rc<RefCell<Columns>>,
+    available: fn() -> Vec<Column>,
     view: View,
 }
 
 #[bon::bon]
 impl Filtered {
     #[builder]
-    pub fn new<S>(table: Table<S>, constructor: DetailFn) -> Self
+    pub fn new<S>(table: Table<S>, constructor: DetailFn, actions: Option<DetailFn>) -> Self
     where
         S: Items + 'static,
     {
         Self {
             constructor,
+            actions,
             filter: table.filter(),
+            selected: table.selected(),
+            columns: table.columns(),
+            available: S::Item::columns,
             view: View::builder()
                 .widgets(vec![Element::builder()
                     .widget(table.boxed())
@@ -241,6 +524,31 @@ impl Filtered {
         self.select_with(idx)
     }
 
+    // Routes are absolute, so anything open on top of the table and the filter
+    // are cleared before looking for the row.
+    fn goto(&mut self, route: &[String], buffer: &Buffer, area: Rect) -> Result<()> {
+        while self.view.len() > 1 {
+            self.view.pop();
+        }
+
+        self.filter.replace(None);
+
+        let Broadcast::Routed(idx, rest) =
+            self.view
+                .dispatch(&Event::Goto(route.to_vec()), buffer, area)?
+        else {
+            return Err(eyre!("Nothing found at {}", route.join("/")));
+        };
+
+        self.select_with(idx)?;
+
+        if !rest.is_empty() {
+            self.view.dispatch(&Event::Goto(rest), buffer, area)?;
+        }
+
+        Ok(())
+    }
+
     fn select_with(&mut self, idx: usize) -> Result<()> {
         let widget = (self.constructor)(idx, self.filter.borrow().clone())?;
 
@@ -263,6 +571,14 @@ impl Filtered {
 impl Widget for Filtered {
     #[tracing::instrument(ret(level = Level::TRACE), skip_all, fields(name = self._name()))]
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Goto(route) = event {
+            if let Err(err) = self.goto(route, buffer, area) {
+                self.view.push(Error::from(err).boxed().into());
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
         match self.view.dispatch(event, buffer, area) {
             Ok(Broadcast::Selected(idx)) => {
                 self.select_with(idx)?;
@@ -270,6 +586,27 @@ impl Widget for Filtered {
                 Ok(Broadcast::Consumed)
             }
             Ok(Broadcast::Ignored) => {
+                if let (Some(Keypress::Printable('a')), Some(actions), 1) =
+                    (event.key(), self.actions.as_ref(), self.view.len())
+                {
+                    let widget = actions(self.selected.get(), self.filter.borrow().clone())?;
+                    self.view.push(widget.into());
+
+                    return Ok(Broadcast::Consumed);
+                }
+
+                if let (Some(Keypress::Printable('v')), 1) = (event.key(), self.view.len()) {
+                    if !(self.available)().is_empty() {
+                        self.view.push(
+                            columns::Picker::new(self.available, self.columns.clone())
+                                .boxed()
+                                .into(),
+                        );
+
+                        return Ok(Broadcast::Consumed);
+                    }
+                }
+
                 let Some(Keypress::Printable('/')) = event.key() else {
                     return Ok(Broadcast::Ignored);
                 };
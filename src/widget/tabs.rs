@@ -1,6 +1,10 @@
+use std::{cell::Cell, rc::Rc};
+
 use bon::Builder;
 use eyre::Result;
+use futures::FutureExt;
 use itertools::Itertools;
+use k8s_openapi::api::authorization::v1::ResourceAttributes;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Position, Rect},
@@ -11,15 +15,19 @@ use ratatui::{
     Frame,
 };
 use tachyonfx::{fx, EffectTimer, Interpolation};
+use tokio::task::JoinHandle;
 
 use super::{
+    denied::Denied,
     error::Error,
+    loading::Loading,
     view::{Element, View},
     Placement, Widget,
 };
 use crate::{
-    events::{Broadcast, Event},
+    events::{Broadcast, Event, Mouse, MouseButton, MouseKind},
     fx::{wipe, Start},
+    resources::authz::Authz,
     widget::nav::{move_cursor, Movement, Shrink},
 };
 
@@ -27,6 +35,10 @@ use crate::{
 pub struct Tab {
     name: String,
     constructor: Box<dyn Fn() -> Element + Send>,
+    // Checked before the tab is constructed, when the view it is part of has
+    // an `Authz`.
+    #[builder(default)]
+    requires: Vec<ResourceAttributes>,
 }
 
 impl Tab {
@@ -40,19 +52,20 @@ struct Bar {
     title: Vec<String>,
     style: Style,
 
-    idx: usize,
+    // Shared with the view, so that routes can change the tab as well.
+    idx: Rc<Cell<usize>>,
 }
 
 #[bon::bon]
 impl Bar {
     #[builder]
-    fn new(items: &[Tab], style: Style, title: Vec<String>) -> Self {
+    fn new(items: &[Tab], style: Style, title: Vec<String>, idx: Rc<Cell<usize>>) -> Self {
         Self {
             items: items.iter().map(|tab| tab.name.clone()).collect(),
             title,
             style,
 
-            idx: 0,
+            idx,
         }
     }
 }
@@ -65,12 +78,14 @@ impl Widget for Bar {
         };
 
         if let Some(Movement::X(x)) = move_cursor(key, area) {
-            self.idx = self
-                .idx
-                .wrapping_add_signed(x.shrink())
-                .clamp(0, self.items.len().saturating_sub(1));
-
-            return Ok(Broadcast::Selected(self.idx));
+            self.idx.set(
+                self.idx
+                    .get()
+                    .wrapping_add_signed(x.shrink())
+                    .clamp(0, self.items.len().saturating_sub(1)),
+            );
+
+            return Ok(Broadcast::Selected(self.idx.get()));
         }
 
         Ok(Broadcast::Ignored)
@@ -87,21 +102,14 @@ impl Widget for Bar {
                 .collect::<Vec<_>>(),
             ));
 
-        let layout = Layout::horizontal(
-            Itertools::intersperse(
-                std::iter::repeat(Constraint::Fill(1)),
-                Constraint::Length(1),
-            )
-            .take(self.items.len() * 2 - 1),
-        )
-        .split(border.inner(area));
+        let layout = layout(border.inner(area), self.items.len());
 
         for (i, (area, txt)) in layout
             .iter()
             .zip(Itertools::intersperse(self.items.iter(), &"|".to_string()))
             .enumerate()
         {
-            let style = if i == self.idx * 2 {
+            let style = if i == self.idx.get() * 2 {
                 self.style
             } else {
                 Style::default()
@@ -123,6 +131,38 @@ impl Widget for Bar {
     }
 }
 
+// Every other area is a tab, the ones in between are separators.
+fn layout(area: Rect, count: usize) -> Rc<[Rect]> {
+    Layout::horizontal(
+        Itertools::intersperse(
+            std::iter::repeat(Constraint::Fill(1)),
+            Constraint::Length(1),
+        )
+        .take(count * 2 - 1),
+    )
+    .split(area)
+}
+
+// The tab under a click, this makes the same assumption about the bar's
+// placement as `connector`.
+fn clicked(mouse: Mouse, area: Rect, count: usize) -> Option<usize> {
+    if mouse.kind != MouseKind::Down(MouseButton::Left) || count == 0 {
+        return None;
+    }
+
+    let bar = Block::default()
+        .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
+        .inner(Rect {
+            height: area.height.min(2),
+            ..area
+        });
+
+    layout(bar, count)
+        .iter()
+        .step_by(2)
+        .position(|area| area.contains(mouse.position()))
+}
+
 // This assumes the placement of a bar is Length(2). It'll need to change if
 // that is ever adjusted.
 fn connector(frame: &mut Frame, area: Rect) {
@@ -156,10 +196,22 @@ fn connector(frame: &mut Frame, area: Rect) {
     }
 }
 
+type Preflight = JoinHandle<Result<Vec<ResourceAttributes>>>;
+
 pub struct TabbedView {
     items: Vec<Tab>,
     current: usize,
+    bar: Rc<Cell<usize>>,
     view: View,
+    // Where the current tab lives in `view`. Anything pushed on top of it, like
+    // errors, stays put when the tab is swapped out.
+    slot: usize,
+
+    authz: Option<Authz>,
+    pending: Option<(usize, Preflight)>,
+    // What is left of a route after picking a tab. It is passed to the tab
+    // once it has been constructed.
+    route: Option<Vec<String>>,
 }
 
 #[bon::bon]
@@ -169,25 +221,111 @@ impl TabbedView {
         tabs: Vec<Tab>,
         #[builder(default = Style::default().add_modifier(Modifier::REVERSED))] style: Style,
         #[builder(default = Vec::new())] title: Vec<String>,
+        authz: Option<Authz>,
     ) -> Self {
-        let mut widgets = vec![Bar::builder()
+        let bar = Rc::new(Cell::new(0));
+        let widgets = vec![Bar::builder()
             .items(&tabs)
             .style(style)
             .title(title)
+            .idx(bar.clone())
             .build()
             .boxed()
             .into()];
 
-        if !tabs.is_empty() {
-            widgets.push(tabs[0].widget());
-        }
-
-        Self {
+        let mut view = Self {
             items: tabs,
 
             current: 0,
+            bar,
+            slot: widgets.len(),
             view: View::builder().widgets(widgets).build(),
+
+            authz,
+            pending: None,
+            route: None,
+        };
+
+        if !view.items.is_empty() {
+            let widget = view.open(0);
+            view.view.push(widget);
         }
+
+        view
+    }
+
+    // Tabs that require permissions show a loading screen until they've been
+    // checked, the tab itself is constructed once that's done.
+    fn open(&mut self, idx: usize) -> Element {
+        let tab = &self.items[idx];
+
+        let Some(authz) = self.authz.as_ref().filter(|_| !tab.requires.is_empty()) else {
+            self.pending = None;
+
+            return tab.widget();
+        };
+
+        let authz = authz.clone();
+        let requires = tab.requires.clone();
+
+        self.pending = Some((
+            idx,
+            tokio::spawn(async move { authz.missing(&requires).await }),
+        ));
+
+        Loading.boxed().into()
+    }
+
+    fn preflight(&mut self) {
+        if !self
+            .pending
+            .as_ref()
+            .map_or(false, |(_, task)| task.is_finished())
+        {
+            return;
+        }
+
+        let Some((idx, task)) = self.pending.take() else {
+            return;
+        };
+
+        let Some(result) = task.now_or_never() else {
+            return;
+        };
+
+        let widget = match result.map_err(eyre::Report::from).and_then(|r| r) {
+            Ok(missing) if !missing.is_empty() => Denied::new(missing).boxed().into(),
+            Ok(_) => self.items[idx].widget(),
+            // The check is only advisory, if it didn't work the tab can still try.
+            Err(err) => {
+                tracing::debug!("unable to check permissions: {err:?}");
+
+                self.items[idx].widget()
+            }
+        };
+
+        self.view.replace(self.slot, widget);
+    }
+
+    fn click(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Broadcast {
+        // Only the topmost layer is drawn, the bar isn't visible when anything
+        // is over it.
+        if self.view.zindex() > 0 {
+            return Broadcast::Ignored;
+        }
+
+        let Some(idx) = event
+            .mouse()
+            .and_then(|mouse| clicked(*mouse, area, self.items.len()))
+        else {
+            return Broadcast::Ignored;
+        };
+
+        if idx != self.current {
+            self.select(idx, buffer);
+        }
+
+        Broadcast::Consumed
     }
 
     fn select(&mut self, idx: usize, buffer: &Buffer) {
@@ -198,12 +336,13 @@ impl TabbedView {
         };
 
         self.current = idx;
+        self.bar.set(idx);
+
+        let widget = self.open(idx);
 
-        // TODO: this is *probably* a valid assumption, but it might need to be actually
-        // checked.
-        self.view.pop();
-        self.view.push(
-            self.items[idx].widget().animate(fx::parallel(&[
+        self.view.replace(
+            self.slot,
+            widget.animate(fx::parallel(&[
                 fx::coalesce(EffectTimer::from_ms(500, Interpolation::SineInOut)),
                 wipe()
                     .buffer(buffer.clone())
@@ -218,17 +357,46 @@ impl TabbedView {
 impl Widget for TabbedView {
     #[tracing::instrument(ret(level = tracing::Level::TRACE), skip_all, fields(name = self._name()))]
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Goto(route) = event {
+            if let Some(idx) = route.first().and_then(|name| {
+                self.items
+                    .iter()
+                    .position(|tab| tab.name.eq_ignore_ascii_case(name))
+            }) {
+                // The current tab is kept around, there's no reason to wait for
+                // it to load again.
+                if idx != self.current {
+                    self.select(idx, buffer);
+                }
+
+                self.route = Some(route[1..].to_vec()).filter(|rest| !rest.is_empty());
+
+                return Ok(Broadcast::Consumed);
+            }
+        }
+
+        if self.pending.is_none() {
+            if let Some(route) = self.route.take() {
+                self.view.dispatch(&Event::Goto(route), buffer, area)?;
+            }
+        }
+
         match self.view.dispatch(event, buffer, area)? {
             Broadcast::Selected(idx) => {
                 self.select(idx, buffer);
 
                 Ok(Broadcast::Consumed)
             }
+            // Whatever is on top gets clicks first, a detail covering this
+            // one has its own bar in the same place.
+            Broadcast::Ignored => Ok(self.click(event, buffer, area)),
             broadcast => Ok(broadcast),
         }
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.preflight();
+
         if let Err(err) = self.view.draw(frame, area) {
             self.view.push(Error::from(err).boxed().into());
         }
@@ -243,3 +411,115 @@ impl Widget for TabbedView {
         self.view.zindex()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::events::Mouse;
+
+    type Opened = Arc<Mutex<Vec<&'static str>>>;
+
+    struct Blank;
+
+    impl Widget for Blank {
+        fn draw(&mut self, _: &mut Frame, _: Rect) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    // Stands in for a detail, which covers the view it was opened from.
+    struct Cover(TabbedView);
+
+    impl Widget for Cover {
+        fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+            self.0.dispatch(event, buffer, area)
+        }
+
+        fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+            self.0.draw(frame, area)
+        }
+
+        fn zindex(&self) -> u16 {
+            1
+        }
+    }
+
+    fn tab(name: &'static str, opened: &Opened) -> Tab {
+        let opened = opened.clone();
+
+        Tab::builder()
+            .name(name.to_string())
+            .constructor(Box::new(move || {
+                opened.lock().unwrap().push(name);
+
+                Blank.boxed().into()
+            }))
+            .build()
+    }
+
+    fn click(column: u16, row: u16) -> Event {
+        Event::Mouse(Mouse {
+            kind: MouseKind::Down(MouseButton::Left),
+            column,
+            row,
+        })
+    }
+
+    #[test]
+    fn click_tab() {
+        let area = Rect::new(0, 0, 80, 20);
+        let buffer = Buffer::empty(area);
+        let opened = Opened::default();
+
+        let mut view = TabbedView::builder()
+            .tabs(vec![tab("a", &opened), tab("b", &opened)])
+            .build();
+
+        let result = view.dispatch(&click(70, 1), &buffer, area).unwrap();
+
+        assert!(matches!(result, Broadcast::Consumed));
+        assert_eq!(view.current, 1);
+        assert_eq!(*opened.lock().unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn click_inside_detail() {
+        let area = Rect::new(0, 0, 80, 20);
+        let buffer = Buffer::empty(area);
+        let opened = Opened::default();
+
+        let detail = {
+            let opened = opened.clone();
+
+            Tab::builder()
+                .name("a".to_string())
+                .constructor(Box::new(move || {
+                    Cover(
+                        TabbedView::builder()
+                            .tabs(vec![
+                                tab("x", &opened),
+                                tab("y", &opened),
+                                tab("z", &opened),
+                            ])
+                            .build(),
+                    )
+                    .boxed()
+                    .into()
+                }))
+                .build()
+        };
+
+        let mut view = TabbedView::builder()
+            .tabs(vec![detail, tab("b", &opened)])
+            .build();
+
+        // Where the outer bar has `b`, the detail's bar has `z`.
+        let result = view.dispatch(&click(70, 1), &buffer, area).unwrap();
+
+        assert!(matches!(result, Broadcast::Consumed));
+        assert_eq!(view.current, 0);
+        assert_eq!(*opened.lock().unwrap(), vec!["x", "z"]);
+    }
+}
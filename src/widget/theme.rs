@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+
+use ratatui::style::{palette::tailwind, Color, Style};
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Colours shared between widgets. Anything that only shows up in one place
+/// keeps its colour there.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub healthy: Color,
+    pub unhealthy: Color,
+    pub normal: Color,
+    pub selected: Color,
+    pub header: Color,
+    /// Text that matches the current filter.
+    pub matched: Color,
+    pub banner: Style,
+    /// Name of the `syntect` theme used to highlight yaml.
+    pub syntax: &'static str,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Preset::default().into()
+    }
+}
+
+impl From<Preset> for Theme {
+    fn from(preset: Preset) -> Self {
+        match preset {
+            Preset::Dark => Self {
+                healthy: tailwind::GREEN.c300,
+                unhealthy: tailwind::RED.c300,
+                normal: tailwind::INDIGO.c300,
+                selected: tailwind::GRAY.c700,
+                header: tailwind::INDIGO.c300,
+                matched: tailwind::AMBER.c300,
+                banner: Style::default()
+                    .fg(tailwind::GRAY.c200)
+                    .bg(tailwind::SKY.c700),
+                syntax: "base16-ocean.dark",
+            },
+            Preset::Light => Self {
+                healthy: tailwind::GREEN.c700,
+                unhealthy: tailwind::RED.c700,
+                normal: tailwind::INDIGO.c700,
+                selected: tailwind::GRAY.c300,
+                header: tailwind::INDIGO.c700,
+                matched: tailwind::AMBER.c700,
+                banner: Style::default()
+                    .fg(tailwind::GRAY.c50)
+                    .bg(tailwind::SKY.c600),
+                syntax: "InspiredGitHub",
+            },
+        }
+    }
+}
+
+thread_local! {
+    // Like the keymap, widgets are drawn without any context. Each dashboard
+    // runs on its own thread, making this per session.
+    static CURRENT: RefCell<Theme> = RefCell::new(Theme::default());
+}
+
+/// Use `theme` for everything drawn on this thread.
+pub fn set(theme: Theme) {
+    CURRENT.with(|current| current.replace(theme));
+}
+
+pub fn current() -> Theme {
+    CURRENT.with(|current| current.borrow().clone())
+}
@@ -94,6 +94,18 @@ impl View {
         self.widgets.pop().map(|element| element.widget)
     }
 
+    /// Swap out the widget at `idx`, pushing it instead if there's nothing
+    /// there anymore.
+    pub fn replace(&mut self, idx: usize, widget: Element) -> Option<BoxWidget> {
+        if let Some(current) = self.widgets.get_mut(idx) {
+            return Some(std::mem::replace(current, widget).widget);
+        }
+
+        self.widgets.push(widget);
+
+        None
+    }
+
     pub fn len(&self) -> usize {
         self.widgets.len()
     }
@@ -9,6 +9,7 @@ use ratatui::{
 
 use super::{
     nav::{BigPosition, Shrink},
+    search::{highlight, Highlight},
     Widget,
 };
 
@@ -16,48 +17,56 @@ use super::{
 pub struct Viewport<'a> {
     block: Option<Block<'a>>,
     buffer: &'a Vec<Text<'a>>,
+    // Indices into `buffer` that should be shown. If unset, every line is shown.
+    lines: Option<&'a [usize]>,
+    highlight: Option<Highlight<'a>>,
     #[builder(default)]
     view: BigPosition,
 }
 
 impl Viewport<'_> {
+    fn len(&self) -> usize {
+        self.lines.map_or(self.buffer.len(), <[usize]>::len)
+    }
+
     fn content(&mut self, frame: &mut Frame, area: Rect) {
         let y: usize = self.view.y.shrink();
 
-        let start = y.clamp(0, self.buffer.len().saturating_sub(area.height.into()));
-        let end = y
-            .saturating_add(area.height.into())
-            .clamp(0, self.buffer.len());
+        let start = y.clamp(0, self.len().saturating_sub(area.height.into()));
+        let end = y.saturating_add(area.height.into()).clamp(0, self.len());
 
-        for (i, line) in self.buffer[start..end].iter().enumerate() {
-            frame.render_widget(
-                line,
-                area.inner(Margin {
+        for (i, idx) in (start..end).enumerate() {
+            let line = &self.buffer[self.lines.map_or(idx, |lines| lines[idx])];
+
+            let area = area
+                .inner(Margin {
                     vertical: 0,
                     horizontal: 1,
                 })
                 .offset(Offset {
                     x: 0,
                     y: i.shrink(),
-                }),
-            );
+                });
+
+            if let Some(Highlight { pattern, style }) = self.highlight {
+                frame.render_widget(highlight(line, pattern, style), area);
+            } else {
+                frame.render_widget(line, area);
+            }
         }
     }
 
     fn scroll(&self, frame: &mut Frame, area: Rect) {
-        if self.buffer.len() <= area.height as usize {
+        if self.len() <= area.height as usize {
             return;
         }
 
         let scrollbar = Scrollbar::new(ratatui::widgets::ScrollbarOrientation::VerticalRight)
             .track_symbol(Some("|"));
 
-        let mut state = ScrollbarState::new(
-            self.buffer
-                .len()
-                .saturating_sub(usize::from(area.height + 1)),
-        )
-        .position(self.view.y as usize);
+        let mut state =
+            ScrollbarState::new(self.len().saturating_sub(usize::from(area.height + 1)))
+                .position(self.view.y as usize);
 
         frame.render_stateful_widget(scrollbar, area, &mut state);
     }
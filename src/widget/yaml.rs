@@ -3,54 +3,62 @@ use std::{
     sync::{Arc, LazyLock},
 };
 
-use eyre::Result;
-use kube::Resource;
+use eyre::{Report, Result};
+use futures::{future::BoxFuture, FutureExt};
+use k8s_openapi::api::authorization::v1::ResourceAttributes;
+use kube::{api::DynamicObject, Resource, ResourceExt};
 use ouroboros::self_referencing;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::{palette::tailwind, Style},
     text::{Line, Text},
     widgets::{Block, Borders},
     Frame,
 };
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+use similar::{ChangeTag, TextDiff};
 use syntect::{
-    easy::HighlightLines,
-    highlighting::{Theme, ThemeSet},
-    parsing::SyntaxSet,
-    util::LinesWithEndings,
+    easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet, util::LinesWithEndings,
 };
 use syntect_tui::into_span;
+use tokio::task::JoinHandle;
 
 use super::{
-    nav::{move_cursor, BigPosition, Movement, Shrink},
+    editor::Editor,
+    error::Error,
+    export::Export,
+    keymap,
+    nav::{move_cursor, wheel, BigPosition, Movement, Shrink},
     viewport::Viewport,
     Widget, WIDGET_VIEWS_VEC,
 };
 use crate::{
-    events::{Broadcast, Event},
-    resources::Yaml as YamlResource,
-    widget::tabs::Tab,
+    events::{Broadcast, Event, Keypress},
+    resources::{self, authz::Authz, Yaml as YamlResource},
+    widget::{propagate, tabs::Tab, theme},
 };
 
-static THEME: LazyLock<Theme> = LazyLock::new(|| {
-    let ts = ThemeSet::load_defaults();
-    let mut theme = ts.themes["base16-ocean.dark"].clone();
-    theme.settings.background = Some(syntect::highlighting::Color {
-        r: 0,
-        g: 0,
-        b: 0,
-        a: 0,
-    });
-
-    theme
+static THEMES: LazyLock<ThemeSet> = LazyLock::new(|| {
+    let mut ts = ThemeSet::load_defaults();
+
+    for theme in ts.themes.values_mut() {
+        theme.settings.background = Some(syntect::highlighting::Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        });
+    }
+
+    ts
 });
 
 fn to_lines(txt: &str) -> Vec<Text> {
     let ps = SyntaxSet::load_defaults_newlines();
     let syntax = ps.find_syntax_by_extension("yaml").unwrap();
 
-    let mut highlighter = HighlightLines::new(syntax, &THEME);
+    let mut highlighter = HighlightLines::new(syntax, &THEMES.themes[theme::current().syntax]);
 
     LinesWithEndings::from(txt)
         .map(|line| {
@@ -65,6 +73,120 @@ fn to_lines(txt: &str) -> Vec<Text> {
         .collect()
 }
 
+fn formatted(raw: String) -> Formatted {
+    FormattedBuilder {
+        raw,
+        lines_builder: |raw| to_lines(raw),
+    }
+    .build()
+}
+
+// Only the changed lines, with a little bit of context around them.
+fn diff(old: &str, new: &str) -> Vec<Text<'static>> {
+    let diff = TextDiff::from_lines(old, new);
+
+    let mut lines = Vec::new();
+
+    for (i, group) in diff.grouped_ops(3).iter().enumerate() {
+        if i > 0 {
+            lines.push(Text::styled(
+                "...",
+                Style::default().fg(tailwind::GRAY.c500),
+            ));
+        }
+
+        for change in group.iter().flat_map(|op| diff.iter_changes(op)) {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ("-", Style::default().fg(tailwind::RED.c400)),
+                ChangeTag::Insert => ("+", Style::default().fg(tailwind::GREEN.c400)),
+                ChangeTag::Equal => (" ", Style::default()),
+            };
+
+            lines.push(Text::styled(
+                format!("{sign}{}", change.value().trim_end_matches('\n')),
+                style,
+            ));
+        }
+    }
+
+    lines
+}
+
+type Apply =
+    fn(kube::Client, Option<String>, String, String, bool) -> BoxFuture<'static, Result<String>>;
+
+fn apply<K>(
+    client: kube::Client,
+    namespace: Option<String>,
+    name: String,
+    raw: String,
+    force: bool,
+) -> BoxFuture<'static, Result<String>>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+{
+    async move {
+        resources::apply::<K>(client, namespace, name, raw, force)
+            .await?
+            .to_yaml()
+    }
+    .boxed()
+}
+
+fn can_patch<K>(authz: Authz, resource: &K) -> JoinHandle<Result<bool>>
+where
+    K: Resource<DynamicType = ()>,
+{
+    let attrs = ResourceAttributes {
+        group: Some(K::group(&()).to_string()),
+        resource: Some(K::plural(&()).to_string()),
+        namespace: resource.namespace(),
+        name: Some(resource.name_any()),
+        verb: Some("patch".to_string()),
+        ..Default::default()
+    };
+
+    tokio::spawn(async move { authz.allowed(&[attrs]).await })
+}
+
+// Pull the useful part out of errors from the API server so that they can be
+// shown next to what is being edited.
+fn describe(err: &Report) -> String {
+    let Some(kube::Error::Api(resp)) = err.downcast_ref::<kube::Error>() else {
+        return format!("{err}");
+    };
+
+    if resp.code == 409 && resp.message.starts_with("Apply failed") {
+        return format!(
+            "{}\n\nOther managers own these fields. Use <F> from the diff to take ownership \
+             of them.",
+            resp.message
+        );
+    }
+
+    resp.message.clone()
+}
+
+// What saving the editor does.
+enum Target {
+    // Server-side apply changes to an existing object.
+    Existing(Apply),
+    // Create a new object, from a template.
+    New,
+    // Objects created from a template are edited from their own view.
+    Created,
+}
+
+enum Mode {
+    View,
+    Edit(Editor),
+    Diff {
+        editor: Editor,
+        lines: Vec<Text<'static>>,
+        position: BigPosition,
+    },
+}
+
 #[self_referencing]
 struct Formatted {
     raw: String,
@@ -74,73 +196,441 @@ struct Formatted {
 }
 
 pub struct Yaml {
-    buffer: Formatted,
+    client: kube::Client,
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+    target: Target,
 
+    buffer: Formatted,
     position: BigPosition,
+    export: Export,
+
+    access: Option<JoinHandle<Result<bool>>>,
+    editable: bool,
+    mode: Mode,
+    // Set after an exit key with unsaved edits, the next one discards them.
+    discard: bool,
+    task: Option<JoinHandle<Result<String>>>,
+    error: Option<Error>,
 }
 
 impl Yaml {
-    pub fn new<K>(resource: &Arc<K>) -> Self
+    pub fn new<K>(client: kube::Client, authz: Authz, resource: &Arc<K>) -> Self
     where
-        K: Resource<DynamicType = ()> + Serialize + Send + Sync + 'static,
+        K: Resource<DynamicType = ()>
+            + Clone
+            + DeserializeOwned
+            + Serialize
+            + std::fmt::Debug
+            + Send
+            + Sync
+            + 'static,
     {
         WIDGET_VIEWS_VEC
             .with_label_values(&[K::kind(&()).borrow(), "yaml"])
             .inc();
 
-        let buffer = FormattedBuilder {
-            raw: resource.to_yaml().expect("has yaml"),
-            lines_builder: |raw| to_lines(raw),
+        let access = can_patch(authz, resource.as_ref());
+
+        Self {
+            client,
+            kind: K::kind(&()).to_string(),
+            namespace: resource.namespace(),
+            name: resource.name_any(),
+            target: Target::Existing(apply::<K>),
+
+            buffer: formatted(resource.to_yaml().expect("has yaml")),
+            position: BigPosition::default(),
+            export: Export::default(),
+
+            access: Some(access),
+            editable: false,
+            mode: Mode::View,
+            discard: false,
+            task: None,
+            error: None,
         }
-        .build();
+    }
+
+    /// Start editing `raw` as a new object of `kind`. Saving creates it, see
+    /// `resources::create_raw`.
+    pub fn template(client: kube::Client, kind: String, raw: &str) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[kind.as_str(), "new"])
+            .inc();
 
         Self {
-            buffer,
+            client,
+            mode: Mode::Edit(
+                Editor::builder()
+                    .title(format!(" new {kind} | <ctrl-s> diff | <esc> cancel "))
+                    .content(raw)
+                    .build(),
+            ),
+            kind,
+            namespace: None,
+            name: String::new(),
+            target: Target::New,
+
+            buffer: formatted(String::new()),
             position: BigPosition::default(),
+            export: Export::default(),
+
+            access: None,
+            editable: true,
+            discard: false,
+            task: None,
+            error: None,
         }
     }
 
-    pub fn tab<K>(name: String, resource: Arc<K>) -> Tab
+    pub fn tab<K>(name: String, client: kube::Client, authz: Authz, resource: Arc<K>) -> Tab
     where
-        K: Resource<DynamicType = ()> + Serialize + Send + Sync + 'static,
+        K: Resource<DynamicType = ()>
+            + Clone
+            + DeserializeOwned
+            + Serialize
+            + std::fmt::Debug
+            + Send
+            + Sync
+            + 'static,
     {
         Tab::builder()
             .name(name)
-            .constructor(Box::new(move || Self::new(&resource).boxed().into()))
+            .constructor(Box::new(move || {
+                Self::new(client.clone(), authz.clone(), &resource)
+                    .boxed()
+                    .into()
+            }))
             .build()
     }
-}
 
-impl Widget for Yaml {
-    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
-        let Some(key) = event.key() else {
-            return Ok(Broadcast::Ignored);
+    fn filename(&self) -> String {
+        self.namespace
+            .as_ref()
+            .map_or_else(
+                || format!("{}-{}.yaml", self.kind, self.name),
+                |ns| format!("{}-{ns}-{}.yaml", self.kind, self.name),
+            )
+            .to_lowercase()
+    }
+
+    fn update(&mut self) {
+        if self.access.as_ref().map_or(false, JoinHandle::is_finished) {
+            if let Some(Some(result)) = self.access.take().map(FutureExt::now_or_never) {
+                self.editable = result
+                    .map_err(Report::from)
+                    .and_then(|r| r)
+                    .unwrap_or_default();
+            }
+        }
+
+        if !self.task.as_ref().map_or(false, JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(Some(result)) = self.task.take().map(FutureExt::now_or_never) else {
+            return;
+        };
+
+        match result.map_err(Report::from).and_then(|r| r) {
+            Ok(raw) => {
+                if let Target::New = self.target {
+                    if let Ok(obj) = serde_yaml::from_str::<DynamicObject>(&raw) {
+                        self.namespace = obj.namespace();
+                        self.name = obj.name_any();
+                    }
+
+                    self.target = Target::Created;
+                }
+
+                self.buffer = formatted(raw);
+                self.mode = Mode::View;
+            }
+            Err(err) => {
+                if let Mode::Diff { mut editor, .. } = std::mem::replace(&mut self.mode, Mode::View)
+                {
+                    editor.set_error(Some(describe(&err)));
+                    self.mode = Mode::Edit(editor);
+                }
+            }
+        }
+    }
+
+    fn edit(&mut self) {
+        // Still waiting to hear back on permissions.
+        if self.access.is_some() {
+            return;
+        }
+
+        if let Target::Created = self.target {
+            self.error = Some(Error::from(format!(
+                " Edit this {} from its own view.",
+                self.kind
+            )));
+
+            return;
+        }
+
+        if !self.editable {
+            self.error = Some(Error::from(format!(
+                " You don't have permission to patch this {}.",
+                self.kind
+            )));
+
+            return;
+        }
+
+        self.mode = Mode::Edit(
+            Editor::builder()
+                .title(format!(" {} | <ctrl-s> diff | <esc> cancel ", self.name))
+                .content(self.buffer.borrow_raw())
+                .build(),
+        );
+    }
+
+    fn review(&mut self) {
+        let Mode::Edit(mut editor) = std::mem::replace(&mut self.mode, Mode::View) else {
+            return;
+        };
+
+        let lines = diff(self.buffer.borrow_raw(), &editor.content());
+
+        if lines.is_empty() {
+            editor.set_error(Some("No changes to apply".to_string()));
+            self.mode = Mode::Edit(editor);
+
+            return;
+        }
+
+        editor.set_error(None);
+
+        self.mode = Mode::Diff {
+            editor,
+            lines,
+            position: BigPosition::default(),
         };
+    }
+
+    fn submit(&mut self, force: bool) {
+        let Mode::Diff { editor, .. } = &self.mode else {
+            return;
+        };
+
+        let client = self.client.clone();
+        let raw = editor.content();
+
+        let task = match self.target {
+            Target::Existing(apply) => apply(
+                client,
+                self.namespace.clone(),
+                self.name.clone(),
+                raw,
+                force,
+            ),
+            Target::New => {
+                async move { resources::create_raw(client, raw).await?.to_yaml() }.boxed()
+            }
+            Target::Created => return,
+        };
+
+        self.task = Some(tokio::spawn(task));
+    }
+
+    fn dispatch_view(&mut self, key: &Keypress, area: Rect) -> Broadcast {
+        let filename = self.filename();
+        let raw = self.buffer.borrow_raw();
+        if let Some(result) = self.export.dispatch(key, &filename, || raw.clone()) {
+            return result;
+        }
+
+        if let Keypress::Printable('e') = key {
+            self.edit();
+
+            return Broadcast::Consumed;
+        }
 
         if let Some(Movement::Y(y)) = move_cursor(key, area) {
             self.position.y = self.position.y.saturating_add_signed(y);
 
-            return Ok(Broadcast::Consumed);
+            return Broadcast::Consumed;
         }
 
-        Ok(Broadcast::Ignored)
+        Broadcast::Ignored
     }
 
-    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let txt = self.buffer.borrow_lines();
+    fn dispatch_diff(&mut self, key: &Keypress, area: Rect) {
+        if self.task.is_some() {
+            return;
+        }
+
+        match key {
+            Keypress::Enter => self.submit(false),
+            Keypress::Printable('F') if matches!(self.target, Target::Existing(_)) => {
+                self.submit(true);
+            }
+            key if keymap::exit(key) => {
+                if let Mode::Diff { editor, .. } = std::mem::replace(&mut self.mode, Mode::View) {
+                    self.mode = Mode::Edit(editor);
+                }
+            }
+            _ => {
+                if let (Some(Movement::Y(y)), Mode::Diff { position, .. }) =
+                    (move_cursor(key, area), &mut self.mode)
+                {
+                    position.y = position.y.saturating_add_signed(y);
+                }
+            }
+        }
+    }
+
+    fn draw_diff(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Mode::Diff {
+            lines, position, ..
+        } = &mut self.mode
+        else {
+            return Ok(());
+        };
 
-        self.position.y = self.position.y.clamp(
+        position.y = position.y.clamp(
             0,
-            txt.len().saturating_sub(usize::from(area.height)).shrink(),
+            lines
+                .len()
+                .saturating_sub(usize::from(area.height))
+                .shrink(),
         );
 
-        let pos = self.position;
+        let title = match self.target {
+            _ if self.task.is_some() => " applying... ".to_string(),
+            Target::Existing(_) => format!(
+                " {} | <enter> apply | <F> force apply | <esc> edit ",
+                self.name
+            ),
+            Target::New | Target::Created => {
+                format!(" new {} | <enter> create | <esc> edit ", self.kind)
+            }
+        };
 
         Viewport::builder()
-            .block(Block::default().borders(Borders::ALL))
-            .buffer(txt)
-            .view(pos)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(tailwind::BLUE.c500)),
+            )
+            .buffer(lines)
+            .view(*position)
             .build()
             .draw(frame, area)
     }
 }
+
+impl Widget for Yaml {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(error) = self.error.as_mut() {
+            propagate!(error.dispatch(event, buffer, area), self.error = None);
+        }
+
+        if let Some(Movement::Y(y)) = wheel(event) {
+            match &mut self.mode {
+                Mode::View => self.position.y = self.position.y.saturating_add_signed(y),
+                Mode::Diff { position, .. } => position.y = position.y.saturating_add_signed(y),
+                Mode::Edit(_) => return Ok(Broadcast::Ignored),
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match &mut self.mode {
+            Mode::View => return Ok(self.dispatch_view(key, area)),
+            Mode::Edit(editor) => {
+                // The editor deletes forward with it, like the rest of its
+                // emacs-style bindings, even though it can be an exit key.
+                let exit = keymap::exit(key) && *key != Keypress::Control('d');
+
+                if !exit && self.discard {
+                    self.discard = false;
+                    editor.set_error(None);
+                }
+
+                match key {
+                    _ if exit && editor.dirty() && !self.discard => {
+                        self.discard = true;
+                        editor.set_error(Some(
+                            "There are unsaved changes, exit again to discard them.".to_string(),
+                        ));
+
+                        return Ok(Broadcast::Consumed);
+                    }
+                    _ if exit && matches!(self.target, Target::New) => {
+                        return Ok(Broadcast::Exited);
+                    }
+                    _ if exit => {
+                        self.discard = false;
+                        self.mode = Mode::View;
+                    }
+                    Keypress::Control('s') => self.review(),
+                    _ => {
+                        editor.dispatch(event, buffer, area)?;
+                    }
+                }
+            }
+            Mode::Diff { .. } => self.dispatch_diff(key, area),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.update();
+
+        match &mut self.mode {
+            Mode::Edit(editor) => editor.draw(frame, area)?,
+            Mode::Diff { .. } => self.draw_diff(frame, area)?,
+            Mode::View => {
+                let txt = self.buffer.borrow_lines();
+
+                self.position.y = self.position.y.clamp(
+                    0,
+                    txt.len().saturating_sub(usize::from(area.height)).shrink(),
+                );
+
+                let mut block = Block::default().borders(Borders::ALL);
+
+                match self.target {
+                    Target::Created => {
+                        block = block.title(Line::from(" created ").right_aligned());
+                    }
+                    _ if self.editable => {
+                        block = block.title(Line::from(" <e> edit ").right_aligned());
+                    }
+                    _ => {}
+                }
+
+                Viewport::builder()
+                    .block(block)
+                    .buffer(txt)
+                    .view(self.position)
+                    .build()
+                    .draw(frame, area)?;
+
+                self.export.draw(frame, area);
+            }
+        }
+
+        if let Some(error) = self.error.as_mut() {
+            error.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        u16::from(!matches!(self.mode, Mode::View))
+    }
+}